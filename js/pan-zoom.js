@@ -0,0 +1,50 @@
+// Renders `dotText` into the element with id `divId`, then lets the user
+// zoom with the mouse wheel and pan by dragging.
+function renderPanZoomSkillTree(divId, dotText) {
+  new Viz().renderSVGElement(dotText).then(svg => {
+    const container = document.getElementById(divId);
+    svg.removeAttribute("width");
+    svg.removeAttribute("height");
+    container.appendChild(svg);
+
+    const box = svg.viewBox.baseVal;
+    const view = { x: box.x, y: box.y, width: box.width, height: box.height };
+    const apply = () => {
+      svg.setAttribute("viewBox", `${view.x} ${view.y} ${view.width} ${view.height}`);
+    };
+    const toSvgPoint = event => {
+      const point = svg.createSVGPoint();
+      point.x = event.clientX;
+      point.y = event.clientY;
+      return point.matrixTransform(svg.getScreenCTM().inverse());
+    };
+
+    svg.addEventListener("wheel", event => {
+      event.preventDefault();
+      const scale = event.deltaY < 0 ? 0.9 : 1.1;
+      const center = toSvgPoint(event);
+      view.x = center.x - (center.x - view.x) * scale;
+      view.y = center.y - (center.y - view.y) * scale;
+      view.width *= scale;
+      view.height *= scale;
+      apply();
+    });
+
+    let dragStart = null;
+    svg.addEventListener("mousedown", event => {
+      dragStart = toSvgPoint(event);
+      container.style.cursor = "grabbing";
+    });
+    window.addEventListener("mousemove", event => {
+      if (dragStart === null) return;
+      const current = toSvgPoint(event);
+      view.x -= current.x - dragStart.x;
+      view.y -= current.y - dragStart.y;
+      apply();
+    });
+    window.addEventListener("mouseup", () => {
+      dragStart = null;
+      container.style.cursor = "grab";
+    });
+  });
+}
@@ -10,8 +10,16 @@ function loadSkillTree(dot_path) {
 }
 
 function convertDivToSkillTree(divId, dotText) {
+  let div = document.getElementById(divId);
+  if (dotText.error) {
+    let pre = document.createElement("pre");
+    pre.className = "skill-tree-error";
+    pre.textContent = "skill-tree error: " + dotText.error;
+    div.appendChild(pre);
+    return;
+  }
   new Viz().renderSVGElement(dotText.dot_text).then(element => {
-    document.getElementById(divId).appendChild(element);
+    div.appendChild(element);
   })
 }
 
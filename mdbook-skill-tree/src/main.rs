@@ -23,11 +23,11 @@ struct JsFile {
 const JS_FILES: &[JsFile] = &[
     JsFile {
         name: "viz.js",
-        bytes: include_bytes!("../js/viz.js"),
+        bytes: skill_tree::VIZ_JS.as_bytes(),
     },
     JsFile {
         name: "full.render.js",
-        bytes: include_bytes!("../js/full.render.js"),
+        bytes: skill_tree::FULL_RENDER_JS.as_bytes(),
     },
     JsFile {
         name: "skill-tree.js",
@@ -4,18 +4,46 @@ use mdbook::preprocess::{Preprocessor, PreprocessorContext};
 use pulldown_cmark::{CodeBlockKind::*, Event, Options, Parser, Tag};
 use pulldown_cmark_to_cmark::cmark;
 use serde_json::json;
-use skill_tree::SkillTree;
+use skill_tree::{OutputFormat, SkillTree};
 use std::fmt::Write;
 
 #[derive(Default)]
 pub struct SkillTreePreprocessor;
 
+/// How skill-tree blocks are turned into images, set with `render` in the
+/// `[preprocessor.skill-tree]` section of `book.toml`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Render {
+    /// Emit the graphviz text and render it in the browser with viz.js
+    /// (`render = "js"`, the default).
+    Js,
+    /// Render to SVG with graphviz while building the book and inline the
+    /// result (`render = "svg"`). Needs graphviz installed, but no JS.
+    Svg,
+}
+
 impl Preprocessor for SkillTreePreprocessor {
     fn name(&self) -> &str {
         "skill-tree"
     }
 
-    fn run(&self, _ctx: &PreprocessorContext, mut book: Book) -> Result<Book> {
+    fn run(&self, ctx: &PreprocessorContext, mut book: Book) -> Result<Book> {
+        let render = match ctx
+            .config
+            .get_preprocessor(self.name())
+            .and_then(|table| table.get("render"))
+            .and_then(|value| value.as_str())
+        {
+            None | Some("js") => Render::Js,
+            Some("svg") => Render::Svg,
+            Some(other) => {
+                return Err(Error::from(format!(
+                    "unknown skill-tree render mode `{}`; expected `js` or `svg`",
+                    other
+                )))
+            }
+        };
+
         let mut counter = 0;
         let mut res = None;
         book.for_each_mut(|item: &mut BookItem| {
@@ -24,9 +52,11 @@ impl Preprocessor for SkillTreePreprocessor {
             }
 
             if let BookItem::Chapter(chapter) = item {
-                res = Some(add_skill_tree(&chapter.content, &mut counter).map(|md| {
-                    chapter.content = md;
-                }));
+                res = Some(
+                    add_skill_tree(&chapter.content, &mut counter, render).map(|md| {
+                        chapter.content = md;
+                    }),
+                );
             }
         });
 
@@ -38,7 +68,7 @@ impl Preprocessor for SkillTreePreprocessor {
     }
 }
 
-fn add_skill_tree(content: &str, counter: &mut usize) -> Result<String> {
+fn add_skill_tree(content: &str, counter: &mut usize, render: Render) -> Result<String> {
     let mut buf = String::with_capacity(content.len());
     let mut skill_tree_content = String::new();
     let mut in_skill_tree_block = false;
@@ -72,41 +102,18 @@ fn add_skill_tree(content: &str, counter: &mut usize) -> Result<String> {
                 );
                 in_skill_tree_block = false;
 
-                let graphviz_text_or_err = SkillTree::parse(&skill_tree_content)
-                    .and_then(|skill_tree| skill_tree.to_graphviz());
-                let js_value = match graphviz_text_or_err {
-                    Ok(text) => json!({
-                        "dot_text": text,
-                        "error": "",
-                    }),
-
-                    // FIXME -- we should serialize this into something that displays the error
-                    // when rendered
-                    Err(e) => panic!("encountered error {}", e),
-                };
-
                 // Get a fresh id for this block.
                 let id = *counter;
                 *counter += 1;
 
-                // Generate a "div" where the rendered code will go with
-                // a unique `id`.
-                let mut html_code = String::new();
-                write!(&mut html_code, "<div id='skill-tree-{}'>", id).unwrap();
-                write!(&mut html_code, "</div>\n\n").unwrap();
-
-                // Generate a script tag to insert the rendered skill-tree
-                // content. It is given a string argument with the graphviz
-                // output we can pass to viz-js.
-                write!(
-                    &mut html_code,
-                    r#"<script>
-                    if (!window.SKILL_TREES) window.SKILL_TREES = [];
-                    window.SKILL_TREES.push({{id:'skill-tree-{}', value:{}}});
-                    </script>"#,
-                    id, js_value
-                )
-                .unwrap();
+                let skill_tree = SkillTree::parse(&skill_tree_content).and_then(|skill_tree| {
+                    skill_tree.validate()?;
+                    Ok(skill_tree)
+                });
+                let html_code = match render {
+                    Render::Js => js_html(id, skill_tree),
+                    Render::Svg => svg_html(id, skill_tree),
+                };
                 return Some(Event::Html(html_code.into()));
             }
             Event::Text(code) => {
@@ -123,5 +130,74 @@ fn add_skill_tree(content: &str, counter: &mut usize) -> Result<String> {
         .map_err(|err| Error::from(format!("Markdown serialization failed: {}", err)))
 }
 
+/// Generates a "div" where the rendered skill-tree will go, with a unique
+/// `id`, and a script tag that hands viz.js the graphviz text for it.
+/// Errors are shown in place of the tree.
+fn js_html(id: usize, skill_tree: std::result::Result<SkillTree, skill_tree::Error>) -> String {
+    let js_value = match skill_tree.and_then(|skill_tree| skill_tree.to_graphviz()) {
+        Ok(text) => json!({
+            "dot_text": text,
+            "error": "",
+        }),
+        Err(e) => {
+            log::error!("skill-tree #{}: {}", id, e);
+            json!({
+                "dot_text": "",
+                "error": e.to_string(),
+            })
+        }
+    };
+
+    let mut html_code = String::new();
+    write!(&mut html_code, "<div id='skill-tree-{}'>", id).unwrap();
+    write!(&mut html_code, "</div>\n\n").unwrap();
+    write!(
+        &mut html_code,
+        r#"<script>
+                    if (!window.SKILL_TREES) window.SKILL_TREES = [];
+                    window.SKILL_TREES.push({{id:'skill-tree-{}', value:{}}});
+                    </script>"#,
+        id, js_value
+    )
+    .unwrap();
+    html_code
+}
+
+/// Renders the skill-tree to SVG with graphviz and inlines it in a "div".
+/// Errors are shown in place of the tree.
+fn svg_html(id: usize, skill_tree: std::result::Result<SkillTree, skill_tree::Error>) -> String {
+    let svg = skill_tree.and_then(|skill_tree| {
+        let mut svg = Vec::new();
+        skill_tree.write_rendered(OutputFormat::Svg, &mut svg)?;
+        Ok(String::from_utf8_lossy(&svg).into_owned())
+    });
+
+    let mut html_code = String::new();
+    write!(&mut html_code, "<div id='skill-tree-{}'>", id).unwrap();
+    match svg {
+        Ok(svg) => {
+            // Skip the XML prolog and doctype, and drop blank lines, which
+            // would end the HTML block when mdbook renders the markdown.
+            let start = svg.find("<svg").unwrap_or(0);
+            for line in svg[start..].lines().filter(|l| !l.trim().is_empty()) {
+                writeln!(&mut html_code, "{}", line).unwrap();
+            }
+        }
+        Err(e) => {
+            log::error!("skill-tree #{}: {}", id, e);
+            write!(&mut html_code, "{}", error_html(&e.to_string())).unwrap();
+        }
+    }
+    write!(&mut html_code, "</div>\n\n").unwrap();
+    html_code
+}
+
+fn error_html(message: &str) -> String {
+    format!(
+        "<pre class='skill-tree-error'>skill-tree error: {}</pre>",
+        htmlescape::encode_minimal(message)
+    )
+}
+
 #[cfg(test)]
 mod test;
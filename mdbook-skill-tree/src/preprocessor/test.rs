@@ -1,4 +1,4 @@
-use super::add_skill_tree;
+use super::{add_skill_tree, Render};
 
 #[test]
 fn adds_skill_tree() {
@@ -14,7 +14,7 @@ items = [ ]
 Text
 "#;
 
-    let output = add_skill_tree(content, &mut 0).unwrap();
+    let output = add_skill_tree(content, &mut 0, Render::Js).unwrap();
     println!("output:\n{}", output);
     assert!(output.contains(r#"<div id='skill-tree-0'></div>"#));
 }
@@ -38,7 +38,10 @@ fn leaves_tables_untouched() {
 |------|------|
 |Row 1|Row 2|"#;
 
-    assert_eq!(expected, add_skill_tree(content, &mut 0).unwrap());
+    assert_eq!(
+        expected,
+        add_skill_tree(content, &mut 0, Render::Js).unwrap()
+    );
 }
 
 #[test]
@@ -65,5 +68,24 @@ fn leaves_html_untouched() {
 </del>
 "#;
 
-    assert_eq!(expected, add_skill_tree(content, &mut 0).unwrap());
+    assert_eq!(
+        expected,
+        add_skill_tree(content, &mut 0, Render::Js).unwrap()
+    );
+}
+
+#[test]
+fn shows_errors_in_place() {
+    let content = r#"# Chapter
+
+```skill-tree
+[[group]]
+name = "test"
+requires = ["missing"]
+items = [ ]
+```
+"#;
+
+    let output = add_skill_tree(content, &mut 0, Render::Js).unwrap();
+    assert!(output.contains("requires unknown group or goal `missing`"));
 }
@@ -0,0 +1,108 @@
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::throws;
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Finds every `*.toml` file under `dir`, recursively, returning their
+/// paths relative to `dir` in sorted order. Files that another of them
+/// names as an `include` or `status_file` are fragments of that tree, not
+/// trees of their own, and are left out.
+#[throws(Error)]
+pub fn find_skill_trees(dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    find_in(dir, Path::new(""), &mut found)?;
+
+    let mut referenced = HashSet::new();
+    for relative in &found {
+        let path = dir.join(relative);
+        let base = path.parent().unwrap_or(dir);
+        for reference in references(&path) {
+            if let Ok(canonical) = base.join(reference).canonicalize() {
+                referenced.insert(canonical);
+            }
+        }
+    }
+    found.retain(|relative| match dir.join(relative).canonicalize() {
+        Ok(canonical) => !referenced.contains(&canonical),
+        Err(_) => true,
+    });
+
+    found.sort();
+    found
+}
+
+/// The files a skill-tree refers to by path.
+#[derive(Default, Deserialize)]
+struct References {
+    #[serde(default)]
+    include: Vec<String>,
+    status_file: Option<String>,
+}
+
+/// The files named by the `include` and `status_file` of the tree at
+/// `path`. Files that can't be read are left for loading to report.
+fn references(path: &Path) -> Vec<String> {
+    let references: References = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default();
+    references
+        .include
+        .into_iter()
+        .chain(references.status_file)
+        .collect()
+}
+
+#[throws(Error)]
+fn find_in(root: &Path, relative: &Path, found: &mut Vec<PathBuf>) {
+    let dir = root.join(relative);
+    let entries = std::fs::read_dir(&dir).map_err(|source| Error::Io {
+        path: Some(dir.clone()),
+        source,
+    })?;
+    for entry in entries {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            find_in(root, &path, found)?;
+        } else if path.extension() == Some("toml".as_ref()) {
+            found.push(path);
+        }
+    }
+}
+
+/// Loads every skill-tree under `input` (see `find_skill_trees`) and calls
+/// `render` with the path it was loaded from, the tree, and the path to
+/// write it to. Output paths mirror the input's directory structure under
+/// `output`, with `extension` in place of `.toml`; their parent
+/// directories are created first. Stops at the first error. Returns the
+/// number of trees rendered.
+pub fn render_dir<E>(
+    input: &Path,
+    output: &Path,
+    extension: &str,
+    mut render: impl FnMut(&Path, &SkillTree, &Path) -> Result<(), E>,
+) -> Result<usize, E>
+where
+    E: From<Error>,
+{
+    let trees = find_skill_trees(input)?;
+    for relative in &trees {
+        let input_path = input.join(relative);
+        let output_path = output.join(relative).with_extension(extension);
+        let skill_tree = SkillTree::load(&input_path)?;
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| Error::Io {
+                path: Some(parent.to_owned()),
+                source,
+            })?;
+        }
+        render(&input_path, &skill_tree, &output_path)?;
+    }
+    Ok(trees.len())
+}
+
+#[cfg(test)]
+mod test;
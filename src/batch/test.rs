@@ -0,0 +1,83 @@
+use crate::{find_skill_trees, render_dir, Error};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("skill-tree-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn renders_nested_trees() {
+    let input = temp_dir("batch-input");
+    let output = temp_dir("batch-output");
+    std::fs::create_dir_all(input.join("team")).unwrap();
+    let tree = "[[group]]\nname = \"a\"\nitems = []\n";
+    std::fs::write(input.join("top.toml"), tree).unwrap();
+    std::fs::write(input.join("team/plan.toml"), tree).unwrap();
+    std::fs::write(input.join("team/notes.md"), "not a tree").unwrap();
+
+    assert_eq!(
+        find_skill_trees(&input).unwrap(),
+        [Path::new("team/plan.toml"), Path::new("top.toml")]
+    );
+
+    let count = render_dir(
+        &input,
+        &output,
+        "dot",
+        |_, tree, path| -> Result<(), Error> {
+            std::fs::write(path, tree.to_graphviz()?)?;
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(count, 2);
+    assert!(output.join("top.dot").exists());
+    assert!(output.join("team/plan.dot").exists());
+
+    std::fs::remove_dir_all(&input).unwrap();
+    std::fs::remove_dir_all(&output).unwrap();
+}
+
+#[test]
+fn skips_included_files() {
+    let input = temp_dir("batch-includes");
+    std::fs::create_dir_all(input.join("shared")).unwrap();
+    std::fs::write(
+        input.join("plan.toml"),
+        r#"
+include = ["shared/lexer.toml"]
+status_file = "shared/statuses.toml"
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = []
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        input.join("shared/lexer.toml"),
+        "[[group]]\nname = \"lexer\"\nitems = []\n",
+    )
+    .unwrap();
+    std::fs::write(
+        input.join("shared/statuses.toml"),
+        "[status.Reviewing]\nemoji = \"👀\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        input.join("other.toml"),
+        "[[group]]\nname = \"a\"\nitems = []\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        find_skill_trees(&input).unwrap(),
+        [Path::new("other.toml"), Path::new("plan.toml")]
+    );
+
+    std::fs::remove_dir_all(&input).unwrap();
+}
@@ -0,0 +1,531 @@
+//! Fluent builders for constructing skill-trees in code rather than by
+//! deserializing them.
+//!
+//! ```
+//! use skill_tree::{GroupBuilder, ItemBuilder, SkillTree};
+//!
+//! let tree = SkillTree::builder()
+//!     .group(
+//!         GroupBuilder::new("lexer")
+//!             .label("Lexer")
+//!             .item(ItemBuilder::new("Tokens").port("tokens").status("Complete")),
+//!     )
+//!     .group(GroupBuilder::new("parser").requires("lexer:tokens"))
+//!     .build();
+//! tree.validate().unwrap();
+//! ```
+
+use crate::theme::Theme;
+use crate::tree::{
+    default_status, AreaStyle, Collapse, Goal, Group, Item, MilestoneStyle, Phase, PriorityStyle,
+    Requirement, SkillTree, StatusPalette, StatusStyle,
+};
+
+impl SkillTree {
+    /// Starts building a skill-tree in code.
+    pub fn builder() -> SkillTreeBuilder {
+        SkillTreeBuilder::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct SkillTreeBuilder {
+    tree: SkillTree,
+}
+
+impl SkillTreeBuilder {
+    pub fn new() -> Self {
+        SkillTreeBuilder {
+            tree: SkillTree {
+                default_status: default_status(),
+                ..SkillTree::default()
+            },
+        }
+    }
+
+    /// Defines a status. If no statuses are defined, the default ones are used.
+    pub fn status(mut self, name: impl Into<String>, style: StatusStyle) -> Self {
+        self.tree.status.insert(name.into(), style);
+        self
+    }
+
+    /// Sets the label and color of an area that groups can be placed in.
+    pub fn area(mut self, name: impl Into<String>, style: AreaStyle) -> Self {
+        self.tree.area.insert(name.into(), style);
+        self
+    }
+
+    /// Sets how items with the given priority are drawn.
+    pub fn priority(mut self, name: impl Into<String>, style: PriorityStyle) -> Self {
+        self.tree.priority.insert(name.into(), style);
+        self
+    }
+
+    /// Sets the label, date, and color of a milestone that goals can be placed in.
+    pub fn milestone(mut self, name: impl Into<String>, style: MilestoneStyle) -> Self {
+        self.tree.milestone.insert(name.into(), style);
+        self
+    }
+
+    /// Adds a phase after those added so far.
+    pub fn phase(mut self, phase: Phase) -> Self {
+        self.tree.phase.push(phase);
+        self
+    }
+
+    /// The status used by items that don't have one (and whose group doesn't either).
+    pub fn default_status(mut self, name: impl Into<String>) -> Self {
+        self.tree.default_status = Some(name.into());
+        self
+    }
+
+    /// Sets the title shown above the graph.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.tree.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.tree.description = Some(description.into());
+        self
+    }
+
+    /// Records when the plan was last revised; shown under the title.
+    pub fn updated(mut self, updated: impl Into<String>) -> Self {
+        self.tree.updated = Some(updated.into());
+        self
+    }
+
+    /// Sets the link for items without an `href`; see `SkillTree::href_template`.
+    pub fn href_template(mut self, template: impl Into<String>) -> Self {
+        self.tree.href_template = Some(template.into());
+        self
+    }
+
+    /// Sets what relative links are resolved against; see `SkillTree::href_base`.
+    pub fn href_base(mut self, base: impl Into<String>) -> Self {
+        self.tree.href_base = Some(base.into());
+        self
+    }
+
+    /// Lets requirements ignore case and surrounding whitespace; see
+    /// `SkillTree::loose_references`.
+    pub fn loose_references(mut self) -> Self {
+        self.tree.loose_references = true;
+        self
+    }
+
+    /// Adds the default statuses to those defined; see
+    /// `SkillTree::inherit_default_statuses`.
+    pub fn inherit_default_statuses(mut self) -> Self {
+        self.tree.inherit_default_statuses = true;
+        self
+    }
+
+    /// Picks the statuses used if none are defined.
+    pub fn palette(mut self, palette: StatusPalette) -> Self {
+        self.tree.palette = Some(palette);
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.tree.theme = Some(theme);
+        self
+    }
+
+    pub fn group(mut self, group: GroupBuilder) -> Self {
+        self.tree.group.push(group.build());
+        self
+    }
+
+    pub fn goal(mut self, goal: GoalBuilder) -> Self {
+        self.tree
+            .goal
+            .get_or_insert_with(Vec::new)
+            .push(goal.build());
+        self
+    }
+
+    /// Adds a graphviz attribute that is emitted as is.
+    pub fn graphviz_attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tree.graphviz_attrs.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn build(mut self) -> SkillTree {
+        self.tree.flatten_subgroups();
+        self.tree.add_default_statuses();
+        self.tree
+    }
+}
+
+impl Default for SkillTreeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct GroupBuilder {
+    group: Group,
+}
+
+impl GroupBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        GroupBuilder {
+            group: Group {
+                name: name.into(),
+                ..Group::default()
+            },
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.group.label = Some(label.into());
+        self
+    }
+
+    /// Adds a requirement, of the form `name` or `name:port`, or a
+    /// labeled `Requirement`.
+    pub fn requires(mut self, requirement: impl Into<Requirement>) -> Self {
+        self.group
+            .requires
+            .get_or_insert_with(Vec::new)
+            .push(requirement.into());
+        self
+    }
+
+    /// Adds a set of alternatives, any one of which satisfies the requirement.
+    pub fn requires_any<R: Into<Requirement>>(
+        mut self,
+        alternatives: impl IntoIterator<Item = R>,
+    ) -> Self {
+        self.group
+            .requires_any
+            .push(alternatives.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn item(mut self, item: ItemBuilder) -> Self {
+        self.group.items.push(item.build());
+        self
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.group.width = Some(width);
+        self
+    }
+
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.group.status = Some(status.into());
+        self
+    }
+
+    /// The status of items that don't have one, instead of `status`.
+    pub fn default_status(mut self, status: impl Into<String>) -> Self {
+        self.group.default_status = Some(status.into());
+        self
+    }
+
+    pub fn href(mut self, href: impl Into<String>) -> Self {
+        self.group.href = Some(href.into());
+        self
+    }
+
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.group.tooltip = Some(tooltip.into());
+        self
+    }
+
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.group.owner = Some(owner.into());
+        self
+    }
+
+    /// Sets whether the group is drawn as just its header once complete.
+    pub fn collapse(mut self, collapse: Collapse) -> Self {
+        self.group.collapse = Some(collapse);
+        self
+    }
+
+    /// Adds a tag that applies to all of the group's items.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.group.tags.push(tag.into());
+        self
+    }
+
+    /// Shows an image next to the label.
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.group.icon = Some(icon.into());
+        self
+    }
+
+    /// Adds a second link to the header, besides `href`.
+    pub fn details_href(mut self, href: impl Into<String>) -> Self {
+        self.group.details_href = Some(href.into());
+        self
+    }
+
+    pub fn header_color(mut self, color: impl Into<String>) -> Self {
+        self.group.header_color = Some(color.into());
+        self
+    }
+
+    /// Lines the group up with the other groups and goals of the same rank.
+    pub fn rank(mut self, rank: u32) -> Self {
+        self.group.rank = Some(rank);
+        self
+    }
+
+    /// Places the group in an area, drawn as a box around its groups.
+    pub fn area(mut self, area: impl Into<String>) -> Self {
+        self.group.area = Some(area.into());
+        self
+    }
+
+    /// Places the group in a phase defined with `SkillTreeBuilder::phase`.
+    pub fn phase(mut self, phase: impl Into<String>) -> Self {
+        self.group.phase = Some(phase.into());
+        self
+    }
+
+    /// Adds a sub-group, drawn in a box together with this group.
+    pub fn subgroup(mut self, group: GroupBuilder) -> Self {
+        self.group.group.push(group.build());
+        self
+    }
+
+    /// Adds a graphviz attribute that is emitted as is.
+    pub fn graphviz_attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.group.graphviz_attrs.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> Group {
+        self.group
+    }
+}
+
+#[derive(Debug)]
+pub struct ItemBuilder {
+    item: Item,
+}
+
+impl ItemBuilder {
+    pub fn new(label: impl Into<String>) -> Self {
+        ItemBuilder {
+            item: Item {
+                label: label.into(),
+                ..Item::default()
+            },
+        }
+    }
+
+    /// Passes HTML tags and entities in the label through unescaped.
+    pub fn label_html(mut self) -> Self {
+        self.item.label_html = true;
+        self
+    }
+
+    pub fn href(mut self, href: impl Into<String>) -> Self {
+        self.item.href = Some(href.into());
+        self
+    }
+
+    /// Sets the issue number used by the tree's `href_template`.
+    pub fn issue(mut self, issue: u64) -> Self {
+        self.item.issue = Some(issue);
+        self
+    }
+
+    pub fn port(mut self, port: impl Into<String>) -> Self {
+        self.item.port = Some(port.into());
+        self
+    }
+
+    /// Adds a requirement, of the form `name` or `name:port`, or a
+    /// labeled `Requirement`.
+    pub fn requires(mut self, requirement: impl Into<Requirement>) -> Self {
+        self.item
+            .requires
+            .get_or_insert_with(Vec::new)
+            .push(requirement.into());
+        self
+    }
+
+    /// Adds a set of alternatives, any one of which satisfies the requirement.
+    pub fn requires_any<R: Into<Requirement>>(
+        mut self,
+        alternatives: impl IntoIterator<Item = R>,
+    ) -> Self {
+        self.item
+            .requires_any
+            .push(alternatives.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.item.status = Some(status.into());
+        self
+    }
+
+    /// Adds a graphviz attribute that is emitted as is.
+    pub fn graphviz_attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.item.graphviz_attrs.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the longer explanation shown as a tooltip.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.item.description = Some(description.into());
+        self
+    }
+
+    /// Shows an image next to the status emoji.
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.item.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets how far along the item is, as a percentage from 0 to 100.
+    pub fn progress(mut self, percent: u8) -> Self {
+        self.item.progress = Some(percent);
+        self
+    }
+
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.item.owner = Some(owner.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.item.tags.push(tag.into());
+        self
+    }
+
+    /// Sets how urgent the item is, from `P0` to `P3`.
+    pub fn priority(mut self, priority: impl Into<String>) -> Self {
+        self.item.priority = Some(priority.into());
+        self
+    }
+
+    /// Estimates the work the item takes, in any unit.
+    pub fn effort(mut self, effort: f64) -> Self {
+        self.item.effort = Some(effort);
+        self
+    }
+
+    /// Shows `emoji` in place of the status's emoji.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.item.emoji = Some(emoji.into());
+        self
+    }
+
+    pub fn build(self) -> Item {
+        self.item
+    }
+}
+
+#[derive(Debug)]
+pub struct GoalBuilder {
+    goal: Goal,
+}
+
+impl GoalBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        GoalBuilder {
+            goal: Goal {
+                name: name.into(),
+                ..Goal::default()
+            },
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.goal.label = Some(label.into());
+        self
+    }
+
+    /// Adds a requirement, of the form `name` or `name:port`, or a
+    /// labeled `Requirement`.
+    pub fn requires(mut self, requirement: impl Into<Requirement>) -> Self {
+        self.goal
+            .requires
+            .get_or_insert_with(Vec::new)
+            .push(requirement.into());
+        self
+    }
+
+    /// Adds a set of alternatives, any one of which satisfies the requirement.
+    pub fn requires_any<R: Into<Requirement>>(
+        mut self,
+        alternatives: impl IntoIterator<Item = R>,
+    ) -> Self {
+        self.goal
+            .requires_any
+            .push(alternatives.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Places the goal in a milestone, drawn as a box around its goals.
+    pub fn milestone(mut self, milestone: impl Into<String>) -> Self {
+        self.goal.milestone = Some(milestone.into());
+        self
+    }
+
+    /// Adds an item to the goal's checklist.
+    pub fn item(mut self, item: ItemBuilder) -> Self {
+        self.goal.items.push(item.build());
+        self
+    }
+
+    pub fn href(mut self, href: impl Into<String>) -> Self {
+        self.goal.href = Some(href.into());
+        self
+    }
+
+    /// Opens the goal's `href` in a new tab.
+    pub fn new_tab(mut self) -> Self {
+        self.goal.new_tab = true;
+        self
+    }
+
+    /// Adds a graphviz attribute that is emitted as is.
+    pub fn graphviz_attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.goal.graphviz_attrs.insert(name.into(), value.into());
+        self
+    }
+
+    /// Lines the goal up with the groups and goals of the same rank.
+    pub fn rank(mut self, rank: u32) -> Self {
+        self.goal.rank = Some(rank);
+        self
+    }
+
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.goal.status = Some(status.into());
+        self
+    }
+
+    /// Fills the goal's node with `color` instead of the theme's goal color.
+    pub fn fillcolor(mut self, color: impl Into<String>) -> Self {
+        self.goal.fillcolor = Some(color.into());
+        self
+    }
+
+    /// Sets the graphviz shape of the goal's node.
+    pub fn shape(mut self, shape: impl Into<String>) -> Self {
+        self.goal.shape = Some(shape.into());
+        self
+    }
+
+    /// Sets the longer explanation shown as a tooltip.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.goal.description = Some(description.into());
+        self
+    }
+
+    pub fn build(self) -> Goal {
+        self.goal
+    }
+}
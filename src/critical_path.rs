@@ -0,0 +1,177 @@
+//! Finds the chain of unfinished work that most delays a goal.
+
+use crate::error::Error;
+use crate::tree::{Item, Requirement, SkillTree};
+use fehler::{throw, throws};
+use std::collections::HashMap;
+
+impl SkillTree {
+    /// Finds the longest chain of incomplete groups and goals that `goal`
+    /// requires, listed from the first prerequisite to the goal itself.
+    /// Each link weighs its remaining `effort` if any item in the tree has
+    /// an estimate, and its number of incomplete items otherwise. Completed
+    /// groups and goals end a chain, and soft requirements aren't followed.
+    /// Of each set of `requires_any` alternatives only the cheapest is
+    /// followed, and none if one of them is completed or soft. Empty if the
+    /// goal is already completed.
+    #[throws(Error)]
+    pub fn critical_path(&self, goal: &str) -> Vec<&str> {
+        let goal = match self.goals().find(|g| g.name == goal) {
+            Some(goal) => goal,
+            None => throw!(Error::UnknownGoal {
+                name: goal.to_owned()
+            }),
+        };
+
+        let mut search = Search {
+            tree: self,
+            by_effort: self
+                .groups()
+                .flat_map(|group| group.items())
+                .chain(self.goals().flat_map(|goal| goal.items()))
+                .any(|item| item.effort.is_some()),
+            longest: HashMap::new(),
+        };
+        let mut path = vec![];
+        let mut next = search.longest(&goal.name).map(|_| goal.name.as_str());
+        while let Some(name) = next {
+            path.push(name);
+            next = search.longest[name].and_then(|(_, before)| before);
+        }
+        path.reverse();
+        path
+    }
+}
+
+struct Search<'t> {
+    tree: &'t SkillTree,
+    by_effort: bool,
+    /// For each group or goal visited, the weight of the longest chain
+    /// ending in it and the node before it on that chain. `None` for
+    /// completed nodes, and while a node is being visited, so that cycles
+    /// end a chain rather than looping forever.
+    longest: HashMap<&'t str, Option<(f64, Option<&'t str>)>>,
+}
+
+impl<'t> Search<'t> {
+    fn longest(&mut self, name: &'t str) -> Option<(f64, Option<&'t str>)> {
+        if let Some(&longest) = self.longest.get(name) {
+            return longest;
+        }
+        self.longest.insert(name, None);
+
+        let tree = self.tree;
+        let (weight, requires, requires_any): (f64, Vec<&Requirement>, Vec<&[Requirement]>) =
+            if let Some(group) = tree.group(name) {
+                if self.completed(name) {
+                    return None;
+                }
+                let weight = group
+                    .items()
+                    .map(|item| self.weight(item, tree.item_status(group, item)))
+                    .sum();
+                let requires = group
+                    .requires
+                    .iter()
+                    .flatten()
+                    .chain(
+                        group
+                            .items()
+                            .flat_map(|item| item.requires.iter().flatten()),
+                    )
+                    .collect();
+                let requires_any = group
+                    .requires_any
+                    .iter()
+                    .chain(group.items().flat_map(|item| &item.requires_any))
+                    .map(Vec::as_slice)
+                    .collect();
+                (weight, requires, requires_any)
+            } else if let Some(goal) = tree.goals().find(|goal| goal.name == name) {
+                if self.completed(name) {
+                    return None;
+                }
+                let weight = goal
+                    .items()
+                    .map(|item| self.weight(item, tree.goal_item_status(item)))
+                    .sum();
+                let requires = goal.requires.iter().flatten().collect();
+                let requires_any = goal.requires_any.iter().map(Vec::as_slice).collect();
+                (weight, requires, requires_any)
+            } else {
+                return None;
+            };
+
+        let mut best: Option<(f64, &str)> = None;
+        for requirement in requires.into_iter().filter(|r| !r.soft) {
+            let before = tree.required_name(&requirement.on);
+            if let Some((length, _)) = self.longest(before) {
+                keep_longer(&mut best, (length, before));
+            }
+        }
+        for alternatives in requires_any {
+            if alternatives.iter().any(|r| r.soft) {
+                continue;
+            }
+            let mut cheapest: Option<(f64, &str)> = None;
+            let mut satisfied = false;
+            for requirement in alternatives {
+                let before = tree.required_name(&requirement.on);
+                match self.longest(before) {
+                    Some((length, _)) => {
+                        if !matches!(cheapest, Some((cheapest, _)) if cheapest <= length) {
+                            cheapest = Some((length, before));
+                        }
+                    }
+                    // Also `None` for what is being visited, as part of a
+                    // cycle, and for names that don't resolve; neither is done.
+                    None => satisfied |= self.completed(before),
+                }
+            }
+            if let (false, Some(cheapest)) = (satisfied, cheapest) {
+                keep_longer(&mut best, cheapest);
+            }
+        }
+
+        let longest = Some((
+            weight + best.map_or(0.0, |(length, _)| length),
+            best.map(|(_, before)| before),
+        ));
+        self.longest.insert(name, longest);
+        longest
+    }
+
+    /// Whether `name` is a group whose items are all completed, or a
+    /// completed goal.
+    fn completed(&self, name: &str) -> bool {
+        let tree = self.tree;
+        if let Some(group) = tree.group(name) {
+            let (completed, total) = tree.group_progress(group);
+            total > 0 && completed == total
+        } else if let Some(goal) = tree.goals().find(|goal| goal.name == name) {
+            tree.is_completed(goal.status.as_ref())
+        } else {
+            false
+        }
+    }
+
+    fn weight(&self, item: &Item, status: Option<&String>) -> f64 {
+        if self.by_effort {
+            self.tree.item_effort(item, status).remaining
+        } else if self.tree.is_completed(status) {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Replaces `best` with `candidate` if the chain it ends is longer.
+fn keep_longer<'t>(best: &mut Option<(f64, &'t str)>, candidate: (f64, &'t str)) {
+    if !matches!(best, Some((best, _)) if *best >= candidate.0) {
+        *best = Some(candidate);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,147 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[status.Complete]
+completed = true
+
+[status.Unassigned]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Expressions" }, { label = "Statements" }]
+
+[[group]]
+name = "macros"
+items = [
+    { label = "Hygiene" },
+    { label = "Expansion" },
+    { label = "Derive" },
+    { label = "Attributes" },
+]
+
+[[group]]
+name = "codegen"
+requires = ["parser", "macros"]
+items = [{ label = "LLVM" }]
+
+[[group]]
+name = "docs"
+items = [{ label = "Guide" }, { label = "Reference" }, { label = "Book" }, { label = "FAQ" }, { label = "Index" }]
+
+[[goal]]
+name = "compile"
+requires = ["codegen", "docs?"]
+"#;
+
+#[test]
+fn counts_incomplete_items() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    // The soft requirement on the larger docs group doesn't count.
+    assert_eq!(
+        tree.critical_path("compile").unwrap(),
+        ["macros", "codegen", "compile"]
+    );
+}
+
+#[test]
+fn skips_completed_groups() {
+    let toml = TREE.replace(
+        "name = \"macros\"\n",
+        "name = \"macros\"\nstatus = \"Complete\"\n",
+    );
+    let tree = SkillTree::parse(&toml).unwrap();
+    assert_eq!(
+        tree.critical_path("compile").unwrap(),
+        ["lexer", "parser", "codegen", "compile"]
+    );
+}
+
+#[test]
+fn weighs_by_effort() {
+    let toml = TREE
+        .replace(
+            r#"{ label = "Hygiene" }"#,
+            r#"{ label = "Hygiene", effort = 1 }"#,
+        )
+        .replace(
+            r#"{ label = "Statements" }"#,
+            r#"{ label = "Statements", effort = 5, progress = 20 }"#,
+        );
+    let tree = SkillTree::parse(&toml).unwrap();
+    // Unestimated items count as no effort, but are still on the way.
+    assert_eq!(
+        tree.critical_path("compile").unwrap(),
+        ["lexer", "parser", "codegen", "compile"]
+    );
+}
+
+#[test]
+fn unknown_or_completed_goal() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    assert!(tree.critical_path("codegen").is_err());
+
+    let toml = format!("{}status = \"Complete\"\n", TREE);
+    let tree = SkillTree::parse(&toml).unwrap();
+    assert!(tree.critical_path("compile").unwrap().is_empty());
+}
+
+const ALTERNATIVES: &str = r#"
+[status.Complete]
+completed = true
+
+[[group]]
+name = "interpreter"
+items = [{ label = "Eval", effort = 2 }]
+
+[[group]]
+name = "llvm"
+items = [{ label = "Codegen", effort = 8 }]
+
+[[group]]
+name = "backend"
+requires_any = [["llvm", "interpreter"]]
+items = [{ label = "Driver", effort = 1 }]
+
+[[goal]]
+name = "run"
+requires = ["backend"]
+"#;
+
+#[test]
+fn follows_the_cheapest_alternative() {
+    let tree = SkillTree::parse(ALTERNATIVES).unwrap();
+    assert_eq!(
+        tree.critical_path("run").unwrap(),
+        ["interpreter", "backend", "run"]
+    );
+}
+
+#[test]
+fn skips_alternatives_already_done() {
+    let toml = ALTERNATIVES.replace("effort = 2 }", r#"effort = 2, status = "Complete" }"#);
+    let tree = SkillTree::parse(&toml).unwrap();
+    assert_eq!(tree.critical_path("run").unwrap(), ["backend", "run"]);
+}
+
+#[test]
+fn unresolved_alternatives_are_not_done() {
+    let toml = ALTERNATIVES.replace(r#"["llvm", "interpreter"]"#, r#"["llvm", "nowhere"]"#);
+    let tree = SkillTree::parse(&toml).unwrap();
+    assert_eq!(
+        tree.critical_path("run").unwrap(),
+        ["llvm", "backend", "run"]
+    );
+
+    // `run` is being visited when `backend` is reached through it.
+    let toml = ALTERNATIVES.replace(r#"["llvm", "interpreter"]"#, r#"["llvm", "run"]"#);
+    let tree = SkillTree::parse(&toml).unwrap();
+    assert_eq!(
+        tree.critical_path("run").unwrap(),
+        ["llvm", "backend", "run"]
+    );
+}
@@ -0,0 +1,63 @@
+//! Just enough CSV (RFC 4180) for the CSV export and the formats in
+//! `interop`.
+
+/// Splits `text` into records of fields. Fields may be quoted, with `""`
+/// for a quote, and quoted fields may span lines. Blank lines are skipped.
+pub(crate) fn parse(text: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                end_record(&mut records, &mut record);
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    end_record(&mut records, &mut record);
+    records
+}
+
+fn end_record(records: &mut Vec<Vec<String>>, record: &mut Vec<String>) {
+    let record = std::mem::take(record);
+    if !(record.len() == 1 && record[0].is_empty()) {
+        records.push(record);
+    }
+}
+
+/// Appends `fields` to `output` as a record, quoting those that need it.
+pub(crate) fn write_record(output: &mut String, fields: &[impl AsRef<str>]) {
+    for (index, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+        if index > 0 {
+            output.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod test;
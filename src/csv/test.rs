@@ -0,0 +1,21 @@
+use super::{parse, write_record};
+
+#[test]
+fn reads_records() {
+    let records = parse("a,\"b,c\",\"d \"\"e\"\"\"\r\n\r\n\"multi\nline\",,\n");
+    assert_eq!(
+        records,
+        [vec!["a", "b,c", "d \"e\""], vec!["multi\nline", "", ""],]
+    );
+}
+
+#[test]
+fn writes_records() {
+    let mut output = String::new();
+    write_record(&mut output, &["a", "b,c", "d \"e\"", "multi\nline", ""]);
+    write_record(&mut output, &[String::from("f")]);
+    assert_eq!(
+        output,
+        "a,\"b,c\",\"d \"\"e\"\"\",\"multi\nline\",\r\nf\r\n"
+    );
+}
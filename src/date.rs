@@ -0,0 +1,43 @@
+//! Just enough calendar handling for due dates, which are written as
+//! `YYYY-MM-DD` so that comparing them as strings compares the dates.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Whether `date` is a `YYYY-MM-DD` date.
+pub(crate) fn is_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let number = |part: &str, len: usize, max: u32| {
+        part.len() == len
+            && part.chars().all(|c| c.is_ascii_digit())
+            && (1..=max).contains(&part.parse().unwrap_or(0))
+    };
+    matches!(parts.as_slice(), [year, month, day]
+        if number(year, 4, 9999) && number(month, 2, 12) && number(day, 2, 31))
+}
+
+/// Today's date in UTC, as `YYYY-MM-DD`, such as for
+/// `GraphvizOptions::as_of`.
+pub fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    from_days((seconds / 86_400) as i64)
+}
+
+/// The date `days` days after 1970-01-01, using the algorithm from
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn from_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod test;
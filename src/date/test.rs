@@ -0,0 +1,17 @@
+use super::{from_days, is_date};
+
+#[test]
+fn checks_format() {
+    assert!(is_date("2024-09-01"));
+    assert!(!is_date("2024-9-1"));
+    assert!(!is_date("2024-13-01"));
+    assert!(!is_date("2024-00-10"));
+    assert!(!is_date("next week"));
+}
+
+#[test]
+fn converts_days() {
+    assert_eq!(from_days(0), "1970-01-01");
+    assert_eq!(from_days(11_016), "2000-02-29");
+    assert_eq!(from_days(19_967), "2024-09-01");
+}
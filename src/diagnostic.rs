@@ -0,0 +1,264 @@
+//! Locating errors in the text of a skill-tree and rendering them with a
+//! snippet of the offending line, like:
+//!
+//! ```text
+//! error: group `parser` requires unknown group or goal `lexr`; did you mean `lexer`?
+//!  --> tree.toml:7:13
+//!   |
+//! 7 | requires = ["lexr"]
+//!   |             ^^^^^^
+//! ```
+//!
+//! The tree itself doesn't remember where things were defined, so
+//! validation errors are located by scanning the source for the string
+//! values they refer to.
+
+use crate::error::Error;
+use crate::tree::split_requirement;
+use crate::validate::ValidationIssue;
+
+/// A position in a skill-tree's source text. `line` and `column` count from
+/// 1; `len` is the number of characters to highlight.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Error {
+    /// Finds the part of `source` this error is about, if it can be found.
+    /// `source` should be the text of the file the error came from.
+    pub fn span(&self, source: &str) -> Option<Span> {
+        if let Error::Parse {
+            line_col: Some((line, column)),
+            ..
+        } = self
+        {
+            return Some(Span {
+                line: *line,
+                column: *column,
+                len: 1,
+            });
+        }
+
+        let strings = strings(source);
+        let value = match self {
+            Error::UnknownReference { name, .. } => {
+                find_requirement(&strings, 0, |value| split_requirement(value).0 == name)
+            }
+            Error::UnknownPort { requirement, .. }
+            | Error::GoalPort { requirement, .. }
+            | Error::LooseReference { requirement, .. } => {
+                find_requirement(&strings, 0, |value| value == requirement)
+            }
+            Error::InvalidProgress { item, .. } => {
+                find(&strings, 0, "label", |value| value == item)
+            }
+            Error::UnknownStatus { status, .. } => {
+                find(&strings, 0, "status", |value| value == status)
+                    .or_else(|| find(&strings, 0, "default_status", |value| value == status))
+            }
+            Error::InvalidDate { date, .. } => find(&strings, 0, "due", |value| value == date),
+            Error::InvalidHref { href, .. } => ["href", "details_href", "href_base"]
+                .iter()
+                .find_map(|key| find(&strings, 0, key, |value| value == href)),
+            Error::UnknownPhase { phase, .. } => find(&strings, 0, "phase", |value| value == phase),
+            Error::UnknownParent { parent, .. } => {
+                find(&strings, 0, "parent", |value| value == parent)
+            }
+            Error::DuplicateName { name, .. } => {
+                let first = find(&strings, 0, "name", |value| value == name)?;
+                find(&strings, first + 1, "name", |value| value == name)
+            }
+            Error::DuplicatePort { group, port, .. } => {
+                let group = find(&strings, 0, "name", |value| value == group)?;
+                let first = find(&strings, group, "port", |value| value == port)?;
+                find(&strings, first + 1, "port", |value| value == port)
+            }
+            Error::CycleDetected { cycle } if cycle.len() > 1 => {
+                // Point at the requirement that closes the cycle.
+                let dependent = find(&strings, 0, "name", |value| value == cycle[1])?;
+                find_requirement(&strings, dependent, |value| {
+                    split_requirement(value).0 == cycle[0]
+                })
+            }
+            _ => None,
+        }?;
+        Some(span_at(source, strings[value].offset, strings[value].len))
+    }
+
+    /// Renders this error along with the line of `source` it is about.
+    /// `file_name` is only used for display.
+    pub fn render_diagnostic(&self, file_name: &str, source: &str) -> String {
+        render(
+            &format!("error: {}", self),
+            self.span(source),
+            file_name,
+            source,
+        )
+    }
+}
+
+impl ValidationIssue {
+    /// Like `Error::render_diagnostic`, but labeled with the issue's severity.
+    pub fn render_diagnostic(&self, file_name: &str, source: &str) -> String {
+        render(
+            &self.to_string(),
+            self.error.span(source),
+            file_name,
+            source,
+        )
+    }
+}
+
+fn render(message: &str, span: Option<Span>, file_name: &str, source: &str) -> String {
+    let mut output = format!("{}\n", message);
+    let span = match span {
+        Some(span) => span,
+        None => return output,
+    };
+    let text = source.lines().nth(span.line - 1).unwrap_or("");
+    let gutter = " ".repeat(span.line.to_string().len());
+    output.push_str(&format!(
+        "{}--> {}:{}:{}\n",
+        gutter, file_name, span.line, span.column
+    ));
+    output.push_str(&format!("{} |\n", gutter));
+    output.push_str(&format!("{} | {}\n", span.line, text));
+    output.push_str(&format!(
+        "{} | {}{}\n",
+        gutter,
+        " ".repeat(span.column - 1),
+        "^".repeat(span.len.max(1))
+    ));
+    output
+}
+
+/// A string value in the source, along with the key it was assigned to.
+struct StringValue {
+    key: Option<String>,
+    value: String,
+    /// Byte offset of the opening quote.
+    offset: usize,
+    /// Length in characters, including the quotes.
+    len: usize,
+}
+
+/// Index of the first string at or after `start` whose key is `key` and
+/// whose value satisfies `matches`.
+fn find(
+    strings: &[StringValue],
+    start: usize,
+    key: &str,
+    matches: impl Fn(&str) -> bool,
+) -> Option<usize> {
+    (start..strings.len())
+        .find(|&i| strings[i].key.as_deref() == Some(key) && matches(&strings[i].value))
+}
+
+/// Like `find`, for requirements, which are either strings in `requires`
+/// or `requires_any`, or the `on` of a labeled requirement.
+fn find_requirement(
+    strings: &[StringValue],
+    start: usize,
+    matches: impl Fn(&str) -> bool,
+) -> Option<usize> {
+    (start..strings.len()).find(|&i| {
+        matches!(
+            strings[i].key.as_deref(),
+            Some("requires") | Some("requires_any") | Some("on")
+        ) && matches(&strings[i].value)
+    })
+}
+
+/// Scans TOML (or JSON) text for string values. This is not a full parser;
+/// it only tracks enough to know which key each string belongs to, which
+/// is all that's needed to find things like `requires = ["a", "b"]`.
+fn strings(source: &str) -> Vec<StringValue> {
+    let mut strings = Vec::new();
+    let mut key: Option<String> = None;
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '#' => {
+                while let Some(&(_, next)) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                let mut len = 1;
+                while let Some((_, next)) = chars.next() {
+                    len += 1;
+                    if next == c {
+                        break;
+                    }
+                    if next == '\\' && c == '"' {
+                        if let Some((_, escaped)) = chars.next() {
+                            len += 1;
+                            value.push(escaped);
+                        }
+                        continue;
+                    }
+                    value.push(next);
+                }
+                if is_key(&mut chars) {
+                    key = Some(value);
+                } else {
+                    strings.push(StringValue {
+                        key: key.clone(),
+                        value,
+                        offset,
+                        len,
+                    });
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '-' => {
+                let mut word = c.to_string();
+                while let Some(&(_, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '-') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                if is_key(&mut chars) {
+                    key = Some(word);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    strings
+}
+
+/// Whether the next non-blank character makes the preceding token a key.
+fn is_key(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>) -> bool {
+    while let Some(&(_, c)) = chars.peek() {
+        if c == ' ' || c == '\t' {
+            chars.next();
+        } else {
+            return c == '=' || c == ':';
+        }
+    }
+    false
+}
+
+fn span_at(source: &str, offset: usize, len: usize) -> Span {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Span {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        len,
+    }
+}
+
+#[cfg(test)]
+mod test;
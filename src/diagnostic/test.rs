@@ -0,0 +1,79 @@
+use crate::{SkillTree, Span};
+
+const TREE: &str = r#"[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexr"]
+items = [
+  { label = "AST", port = "ast", requires = ["lexer:token"] },
+]
+"#;
+
+#[test]
+fn locates_unknown_reference() {
+    let err = SkillTree::parse(TREE).unwrap().validate().unwrap_err();
+    assert_eq!(
+        err.span(TREE),
+        Some(Span {
+            line: 7,
+            column: 13,
+            len: 6
+        })
+    );
+    assert_eq!(
+        err.render_diagnostic("tree.toml", TREE),
+        "error: group `parser` requires unknown group or goal `lexr`; did you mean `lexer`?
+ --> tree.toml:7:13
+  |
+7 | requires = [\"lexr\"]
+  |             ^^^^^^
+"
+    );
+}
+
+#[test]
+fn locates_unknown_port() {
+    let source = TREE.replace(r#"["lexr"]"#, r#"["lexer"]"#);
+    let err = SkillTree::parse(&source).unwrap().validate().unwrap_err();
+    assert_eq!(
+        err.span(&source),
+        Some(Span {
+            line: 9,
+            column: 46,
+            len: 13
+        })
+    );
+}
+
+#[test]
+fn locates_parse_errors() {
+    let source = "[[group]]\nname = \"a\"\nitems = [\n";
+    let err = SkillTree::parse(source).unwrap_err();
+    assert_eq!(err.span(source).map(|span| span.line), Some(4));
+}
+
+#[test]
+fn renders_plain_message_without_span() {
+    let err = SkillTree::parse(TREE).unwrap().validate().unwrap_err();
+    assert_eq!(
+        err.render_diagnostic("other.toml", "[[group]]\n"),
+        format!("error: {}\n", err)
+    );
+}
+
+#[test]
+fn locates_invalid_href() {
+    let source = "[[group]]\nname = \"a\"\nitems = [{ label = \"x\", href = \"a b.html\" }]\n";
+    let issues = SkillTree::parse(source).unwrap().check();
+    assert_eq!(
+        issues[0].error.span(source),
+        Some(Span {
+            line: 3,
+            column: 32,
+            len: 10
+        })
+    );
+}
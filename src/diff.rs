@@ -0,0 +1,224 @@
+//! Compares two versions of a skill-tree.
+
+use crate::tree::{Goal, Group, Item, Requirement, SkillTree};
+
+/// What changed from one version of a skill-tree to another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreeDiff {
+    /// Groups and goals only in the new tree, and items only in the new
+    /// version of a group that is in both. The items of an added group
+    /// aren't listed separately.
+    pub added: Vec<DiffNode>,
+    /// Groups and goals only in the old tree, and items only in the old
+    /// version of a group that is in both.
+    pub removed: Vec<DiffNode>,
+    /// Items and goals in both trees whose status changed. An item's status
+    /// is the one that applies to it, wherever it is set.
+    pub status_changes: Vec<StatusChange>,
+    /// Requirements added to or removed from groups, items, and goals that
+    /// are in both trees.
+    pub requirement_changes: Vec<RequirementChange>,
+}
+
+/// A group, item, or goal in a `TreeDiff`. Items are matched up between
+/// the trees by their port if they have one, and by their label otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiffNode {
+    Group(String),
+    Item { group: String, label: String },
+    Goal(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusChange {
+    pub node: DiffNode,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequirementChange {
+    pub node: DiffNode,
+    /// The requirement, as written in `requires`: with a trailing `?` if
+    /// it is soft.
+    pub on: String,
+    /// Whether the requirement was added, rather than removed.
+    pub added: bool,
+}
+
+impl SkillTree {
+    /// Compares this tree, as the old version, with `other`, as the new one.
+    pub fn diff(&self, other: &SkillTree) -> TreeDiff {
+        let mut diff = TreeDiff::default();
+
+        for new in other.groups() {
+            let node = DiffNode::Group(new.name.clone());
+            let old = match self.group(&new.name) {
+                Some(old) => old,
+                None => {
+                    diff.added.push(node);
+                    continue;
+                }
+            };
+            diff.requirements(node, old.all_requirements(), new.all_requirements());
+
+            for new_item in new.items() {
+                let node = DiffNode::Item {
+                    group: new.name.clone(),
+                    label: new_item.label.clone(),
+                };
+                let old_item = match find_item(old, new_item) {
+                    Some(old_item) => old_item,
+                    None => {
+                        diff.added.push(node);
+                        continue;
+                    }
+                };
+                diff.status(
+                    &node,
+                    self.status_name(self.item_status(old, old_item)),
+                    other.status_name(other.item_status(new, new_item)),
+                );
+                diff.requirements(
+                    node,
+                    old_item.all_requirements(),
+                    new_item.all_requirements(),
+                );
+            }
+            for old_item in old.items() {
+                if find_item(new, old_item).is_none() {
+                    diff.removed.push(DiffNode::Item {
+                        group: old.name.clone(),
+                        label: old_item.label.clone(),
+                    });
+                }
+            }
+        }
+        for old in self.groups() {
+            if other.group(&old.name).is_none() {
+                diff.removed.push(DiffNode::Group(old.name.clone()));
+            }
+        }
+
+        for new in other.goals() {
+            let node = DiffNode::Goal(new.name.clone());
+            let old = match self.goals().find(|goal| goal.name == new.name) {
+                Some(old) => old,
+                None => {
+                    diff.added.push(node);
+                    continue;
+                }
+            };
+            diff.status(
+                &node,
+                self.status_name(old.status.as_ref()),
+                other.status_name(new.status.as_ref()),
+            );
+            diff.requirements(node, old.all_requirements(), new.all_requirements());
+        }
+        for old in self.goals() {
+            if !other.is_goal(&old.name) {
+                diff.removed.push(DiffNode::Goal(old.name.clone()));
+            }
+        }
+
+        diff
+    }
+
+    /// Returns a copy of the tree with stand-ins for what `diff` lists as
+    /// removed, so that they can be drawn: groups and goals with just their
+    /// name, and items with just their label.
+    pub(crate) fn with_removed(&self, diff: &TreeDiff) -> SkillTree {
+        let mut tree = self.clone();
+        for node in &diff.removed {
+            match node {
+                DiffNode::Group(name) => tree.group.push(Group {
+                    name: name.clone(),
+                    ..Group::default()
+                }),
+                DiffNode::Item { group, label } => {
+                    if let Some(group) = tree.group_mut(group) {
+                        group.items.push(Item {
+                            label: label.clone(),
+                            ..Item::default()
+                        });
+                    }
+                }
+                DiffNode::Goal(name) => tree.goal.get_or_insert_with(Vec::new).push(Goal {
+                    name: name.clone(),
+                    ..Goal::default()
+                }),
+            }
+        }
+        tree
+    }
+
+    /// The name `status` is defined under, so that switching to an alias
+    /// isn't a change.
+    fn status_name(&self, status: Option<&String>) -> Option<String> {
+        status.map(|status| self.canonical_status(status).to_owned())
+    }
+}
+
+impl TreeDiff {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.status_changes.is_empty()
+            && self.requirement_changes.is_empty()
+    }
+
+    fn status(&mut self, node: &DiffNode, from: Option<String>, to: Option<String>) {
+        if from != to {
+            self.status_changes.push(StatusChange {
+                node: node.clone(),
+                from,
+                to,
+            });
+        }
+    }
+
+    fn requirements<'a>(
+        &mut self,
+        node: DiffNode,
+        old: impl Iterator<Item = &'a Requirement>,
+        new: impl Iterator<Item = &'a Requirement>,
+    ) {
+        let written = |requirement: &Requirement| {
+            format!(
+                "{}{}",
+                requirement.on,
+                if requirement.soft { "?" } else { "" }
+            )
+        };
+        let old: Vec<String> = old.map(written).collect();
+        let new: Vec<String> = new.map(written).collect();
+        for on in new.iter().filter(|on| !old.contains(on)) {
+            self.requirement_changes.push(RequirementChange {
+                node: node.clone(),
+                on: on.clone(),
+                added: true,
+            });
+        }
+        for on in old.iter().filter(|on| !new.contains(on)) {
+            self.requirement_changes.push(RequirementChange {
+                node: node.clone(),
+                on: on.clone(),
+                added: false,
+            });
+        }
+    }
+}
+
+/// The item in `group` that is the same as `item`, from another version
+/// of the tree.
+fn find_item<'g>(group: &'g Group, item: &Item) -> Option<&'g Item> {
+    group.items().find(|other| match (&other.port, &item.port) {
+        (Some(port), Some(other_port)) => port == other_port,
+        _ => other.label == item.label,
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,100 @@
+use crate::{DiffNode, SkillTree};
+
+const OLD: &str = r#"
+[status.Done]
+completed = true
+aliases = ["Shipped"]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }, { label = "Spans", status = "Done" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Expressions" }]
+
+[[goal]]
+name = "beta"
+requires = ["parser"]
+"#;
+
+const NEW: &str = r#"
+[status.Done]
+completed = true
+aliases = ["Shipped"]
+
+[[group]]
+name = "lexer"
+items = [
+    { label = "Token kinds", port = "tokens", status = "Done" },
+    { label = "Spans", status = "Shipped" },
+    { label = "Unicode" },
+]
+
+[[group]]
+name = "codegen"
+items = [{ label = "LLVM" }]
+
+[[goal]]
+name = "beta"
+requires = ["lexer", "codegen?"]
+"#;
+
+fn item(group: &str, label: &str) -> DiffNode {
+    DiffNode::Item {
+        group: group.to_owned(),
+        label: label.to_owned(),
+    }
+}
+
+#[test]
+fn added_and_removed() {
+    let old = SkillTree::parse(OLD).unwrap();
+    let new = SkillTree::parse(NEW).unwrap();
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.added,
+        [
+            item("lexer", "Unicode"),
+            DiffNode::Group("codegen".to_owned())
+        ]
+    );
+    assert_eq!(diff.removed, [DiffNode::Group("parser".to_owned())]);
+    assert!(old.diff(&old).is_empty());
+}
+
+#[test]
+fn status_changes() {
+    let old = SkillTree::parse(OLD).unwrap();
+    let new = SkillTree::parse(NEW).unwrap();
+    let diff = old.diff(&new);
+    // Items are matched by port, and aliases are the same status.
+    assert_eq!(diff.status_changes.len(), 1);
+    assert_eq!(diff.status_changes[0].node, item("lexer", "Token kinds"));
+    assert_eq!(diff.status_changes[0].from.as_deref(), Some("Unassigned"));
+    assert_eq!(diff.status_changes[0].to.as_deref(), Some("Done"));
+}
+
+#[test]
+fn requirement_changes() {
+    let old = SkillTree::parse(OLD).unwrap();
+    let new = SkillTree::parse(NEW).unwrap();
+    let changes: Vec<(String, bool)> = old
+        .diff(&new)
+        .requirement_changes
+        .into_iter()
+        .map(|change| {
+            assert_eq!(change.node, DiffNode::Goal("beta".to_owned()));
+            (change.on, change.added)
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("lexer".to_owned(), true),
+            ("codegen?".to_owned(), true),
+            ("parser".to_owned(), false),
+        ]
+    );
+}
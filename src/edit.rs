@@ -0,0 +1,198 @@
+//! Methods for editing a skill-tree in place.
+
+use crate::error::Error;
+use crate::tree::{split_requirement, Goal, Group, Item, Requirement, SkillTree};
+use fehler::{throw, throws};
+use std::collections::HashMap;
+
+impl SkillTree {
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.group.iter().find(|group| group.name == name)
+    }
+
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut Group> {
+        self.group.iter_mut().find(|group| group.name == name)
+    }
+
+    pub fn goal(&self, name: &str) -> Option<&Goal> {
+        self.goals().find(|goal| goal.name == name)
+    }
+
+    pub fn goal_mut(&mut self, name: &str) -> Option<&mut Goal> {
+        self.goal
+            .iter_mut()
+            .flatten()
+            .find(|goal| goal.name == name)
+    }
+
+    /// Adds a group, failing if a group or goal already has its name.
+    #[throws(Error)]
+    pub fn add_group(&mut self, group: Group) {
+        self.check_name_is_free(&group.name)?;
+        self.group.push(group);
+    }
+
+    /// Removes the group called `name`. References to it are left alone.
+    pub fn remove_group(&mut self, name: &str) -> Option<Group> {
+        let index = self.group.iter().position(|group| group.name == name)?;
+        Some(self.group.remove(index))
+    }
+
+    /// Adds a goal, failing if a group or goal already has its name.
+    #[throws(Error)]
+    pub fn add_goal(&mut self, goal: Goal) {
+        self.check_name_is_free(&goal.name)?;
+        self.goal.get_or_insert_with(Vec::new).push(goal);
+    }
+
+    /// Removes the goal called `name`. References to it are left alone.
+    pub fn remove_goal(&mut self, name: &str) -> Option<Goal> {
+        let goals = self.goal.as_mut()?;
+        let index = goals.iter().position(|goal| goal.name == name)?;
+        Some(goals.remove(index))
+    }
+
+    /// Appends an item to the group called `group`.
+    #[throws(Error)]
+    pub fn add_item(&mut self, group: &str, item: Item) {
+        self.group_mut(group)
+            .ok_or_else(|| Error::UnknownGroup {
+                name: group.to_owned(),
+            })?
+            .items
+            .push(item);
+    }
+
+    /// Removes the first item labeled `label` from the group called `group`.
+    pub fn remove_item(&mut self, group: &str, label: &str) -> Option<Item> {
+        let items = &mut self.group_mut(group)?.items;
+        let index = items.iter().position(|item| item.label == label)?;
+        Some(items.remove(index))
+    }
+
+    /// Renames a group, rewriting every `requires` entry (on groups, items,
+    /// and goals) that refers to it or, as `group/item label`, to its items,
+    /// and the `parent` of its sub-groups.
+    #[throws(Error)]
+    pub fn rename_group(&mut self, old: &str, new: &str) {
+        self.check_name_is_free(new)?;
+        let rewrites = self.indirect_renames(old, new);
+        self.group_mut(old)
+            .ok_or_else(|| Error::UnknownGroup {
+                name: old.to_owned(),
+            })?
+            .name = new.to_owned();
+        for group in &mut self.group {
+            if group.parent.as_deref() == Some(old) {
+                group.parent = Some(new.to_owned());
+            }
+        }
+        self.rename_requirements(old, new, &rewrites);
+    }
+
+    /// Renames a goal, rewriting every `requires` entry that refers to it.
+    #[throws(Error)]
+    pub fn rename_goal(&mut self, old: &str, new: &str) {
+        self.check_name_is_free(new)?;
+        let rewrites = self.indirect_renames(old, new);
+        self.goal_mut(old)
+            .ok_or_else(|| Error::UnknownGoal {
+                name: old.to_owned(),
+            })?
+            .name = new.to_owned();
+        self.rename_requirements(old, new, &rewrites);
+    }
+
+    #[throws(Error)]
+    fn check_name_is_free(&self, name: &str) {
+        if self.group(name).is_some() || self.goal(name).is_some() {
+            throw!(Error::NameInUse {
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    /// What renaming `old` to `new` turns each `requires` entry into that
+    /// refers to `old` other than as `old` or `old:port`: as `old/item
+    /// label`, or by way of `loose_references`. These have to be worked out
+    /// before the rename, while they still resolve.
+    fn indirect_renames(&self, old: &str, new: &str) -> HashMap<String, String> {
+        self.requirements()
+            .into_iter()
+            .map(|(_, _, requirement)| requirement.on.as_str())
+            .filter(|on| split_requirement(on).0 != old && self.required_name(on) == old)
+            .map(|on| {
+                let renamed = match on.strip_prefix(old) {
+                    Some(label) if label.starts_with('/') => format!("{}{}", new, label),
+                    _ => match split_requirement(on).1 {
+                        Some(port) => format!("{}:{}", new, port),
+                        None => new.to_owned(),
+                    },
+                };
+                (on.to_owned(), renamed)
+            })
+            .collect()
+    }
+
+    fn rename_requirements(&mut self, old: &str, new: &str, rewrites: &HashMap<String, String>) {
+        for group in &mut self.group {
+            rename_in(&mut group.requires, old, new, rewrites);
+            rename_in_any(&mut group.requires_any, old, new, rewrites);
+            for item in &mut group.items {
+                rename_in(&mut item.requires, old, new, rewrites);
+                rename_in_any(&mut item.requires_any, old, new, rewrites);
+            }
+        }
+        for goal in self.goal.iter_mut().flatten() {
+            rename_in(&mut goal.requires, old, new, rewrites);
+            rename_in_any(&mut goal.requires_any, old, new, rewrites);
+        }
+    }
+}
+
+/// Rewrites requirements of the form `old` or `old:port` to refer to `new`,
+/// and those in `rewrites` to what they map to.
+fn rename_in(
+    requires: &mut Option<Vec<Requirement>>,
+    old: &str,
+    new: &str,
+    rewrites: &HashMap<String, String>,
+) {
+    for requirement in requires.iter_mut().flatten() {
+        rename_requirement(requirement, old, new, rewrites);
+    }
+}
+
+/// Like `rename_in`, for the alternatives in `requires_any`.
+fn rename_in_any(
+    requires_any: &mut [Vec<Requirement>],
+    old: &str,
+    new: &str,
+    rewrites: &HashMap<String, String>,
+) {
+    for requirement in requires_any.iter_mut().flatten() {
+        rename_requirement(requirement, old, new, rewrites);
+    }
+}
+
+fn rename_requirement(
+    requirement: &mut Requirement,
+    old: &str,
+    new: &str,
+    rewrites: &HashMap<String, String>,
+) {
+    if let Some(renamed) = rewrites.get(&requirement.on) {
+        requirement.on = renamed.clone();
+        return;
+    }
+    let (name, port) = split_requirement(&requirement.on);
+    if name == old {
+        requirement.on = match port {
+            Some(port) => format!("{}:{}", new, port),
+            None => new.to_owned(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test;
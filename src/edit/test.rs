@@ -0,0 +1,77 @@
+use crate::{GoalBuilder, GroupBuilder, ItemBuilder, SkillTree};
+
+fn tree() -> SkillTree {
+    SkillTree::builder()
+        .group(GroupBuilder::new("lexer").item(ItemBuilder::new("Tokens").port("tokens")))
+        .group(
+            GroupBuilder::new("parser").requires("lexer").item(
+                ItemBuilder::new("Grammar")
+                    .port("grammar")
+                    .requires("lexer:tokens"),
+            ),
+        )
+        .goal(
+            GoalBuilder::new("compile")
+                .requires("parser")
+                .requires("lexer"),
+        )
+        .build()
+}
+
+#[test]
+fn rename_group_rewrites_requirements() {
+    let mut tree = tree();
+    tree.rename_group("lexer", "scanner").unwrap();
+
+    assert!(tree.group("lexer").is_none());
+    assert!(tree.group("scanner").is_some());
+
+    let parser = tree.group("parser").unwrap();
+    assert_eq!(parser.requires, Some(vec!["scanner".into()]));
+    assert_eq!(
+        parser.items[0].requires,
+        Some(vec!["scanner:tokens".into()])
+    );
+
+    let goal = tree.goal("compile").unwrap();
+    assert_eq!(goal.requires, Some(vec!["parser".into(), "scanner".into()]));
+}
+
+#[test]
+fn rename_group_rewrites_indirect_references() {
+    let mut tree = tree();
+    tree.loose_references = true;
+    let requires = tree.goal_mut("compile").unwrap().requires.as_mut().unwrap();
+    requires.push("lexer/Tokens".into());
+    requires.push(" Lexer:tokens".into());
+    tree.rename_group("lexer", "scanner").unwrap();
+    let goal = tree.goal("compile").unwrap();
+    assert_eq!(goal.requires.as_ref().unwrap()[2], "scanner/Tokens".into());
+    assert_eq!(goal.requires.as_ref().unwrap()[3], "scanner:tokens".into());
+    tree.validate().unwrap();
+}
+
+#[test]
+fn rename_group_rejects_existing_name() {
+    let mut tree = tree();
+    assert!(tree.rename_group("lexer", "parser").is_err());
+    assert!(tree.rename_group("lexer", "compile").is_err());
+    assert!(tree.rename_group("missing", "other").is_err());
+}
+
+#[test]
+fn add_and_remove() {
+    let mut tree = tree();
+    assert!(tree.add_group(GroupBuilder::new("lexer").build()).is_err());
+
+    tree.add_item("lexer", ItemBuilder::new("Comments").build())
+        .unwrap();
+    assert_eq!(tree.group("lexer").unwrap().items.len(), 2);
+
+    let removed = tree.remove_item("lexer", "Tokens").unwrap();
+    assert_eq!(removed.port.as_deref(), Some("tokens"));
+    assert!(tree.remove_item("lexer", "Tokens").is_none());
+
+    assert!(tree.remove_group("parser").is_some());
+    assert!(tree.group("parser").is_none());
+}
@@ -0,0 +1,85 @@
+//! Adds up the `effort` estimates of items, per group and per goal.
+
+use crate::tree::{Group, Item, SkillTree};
+
+/// The effort of a group's or goal's items, in whatever unit the estimates
+/// use (story points, person-weeks, ...). Items without an estimate count
+/// as no effort.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Effort {
+    pub total: f64,
+    /// The effort of items that aren't completed, less their `progress`.
+    pub remaining: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EffortSummary {
+    /// The effort of each group's own items, in the order of the groups.
+    pub groups: Vec<(String, Effort)>,
+    /// The effort of each goal's own items plus those of everything it
+    /// requires, directly or indirectly, in the order of the goals. Every
+    /// alternative of a `requires_any` counts; soft requirements don't.
+    pub goals: Vec<(String, Effort)>,
+}
+
+impl SkillTree {
+    /// Adds up the effort of the items in each group, and of everything
+    /// each goal depends on.
+    pub fn effort_summary(&self) -> EffortSummary {
+        EffortSummary {
+            groups: self
+                .groups()
+                .map(|group| (group.name.clone(), self.group_effort(group)))
+                .collect(),
+            goals: self
+                .goals()
+                .map(|goal| (goal.name.clone(), self.effort_before(&goal.name)))
+                .collect(),
+        }
+    }
+
+    /// The effort of the items in `group`.
+    pub fn group_effort(&self, group: &Group) -> Effort {
+        let mut effort = Effort::default();
+        for item in group.items() {
+            effort.add(self.item_effort(item, self.item_status(group, item)));
+        }
+        effort
+    }
+
+    pub(crate) fn item_effort(&self, item: &Item, status: Option<&String>) -> Effort {
+        let total = item.effort.unwrap_or(0.0);
+        let remaining = if self.is_completed(status) {
+            0.0
+        } else {
+            let progress = f64::from(item.progress.unwrap_or(0).min(100));
+            total * (1.0 - progress / 100.0)
+        };
+        Effort { total, remaining }
+    }
+
+    /// The effort of the group or goal `name` and of everything it requires.
+    fn effort_before(&self, name: &str) -> Effort {
+        let mut effort = Effort::default();
+        for name in self.needed_for(name) {
+            if let Some(group) = self.group(name) {
+                effort.add(self.group_effort(group));
+            } else if let Some(goal) = self.goals().find(|goal| goal.name == name) {
+                for item in goal.items() {
+                    effort.add(self.item_effort(item, self.goal_item_status(item)));
+                }
+            }
+        }
+        effort
+    }
+}
+
+impl Effort {
+    fn add(&mut self, other: Effort) {
+        self.total += other.total;
+        self.remaining += other.remaining;
+    }
+}
+
+#[cfg(test)]
+mod test;
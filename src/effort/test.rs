@@ -0,0 +1,86 @@
+use crate::{Effort, SkillTree};
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+items = [
+    { label = "Tokens", port = "tokens", effort = 3, status = "Complete" },
+    { label = "Spans", effort = 2, progress = 50 },
+]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Expressions", effort = 5 }, { label = "Unestimated" }]
+
+[[group]]
+name = "docs"
+items = [{ label = "Guide", effort = 8 }]
+
+[[goal]]
+name = "parse"
+requires = ["parser", "docs?"]
+items = [{ label = "Release notes", effort = 1 }]
+
+[[goal]]
+name = "release"
+requires = ["parse", "docs"]
+"#;
+
+#[test]
+fn sums_groups() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let summary = tree.effort_summary();
+    assert_eq!(
+        summary.groups,
+        [
+            (
+                "lexer".to_owned(),
+                Effort {
+                    total: 5.0,
+                    remaining: 1.0
+                }
+            ),
+            (
+                "parser".to_owned(),
+                Effort {
+                    total: 5.0,
+                    remaining: 5.0
+                }
+            ),
+            (
+                "docs".to_owned(),
+                Effort {
+                    total: 8.0,
+                    remaining: 8.0
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn sums_everything_a_goal_requires() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let summary = tree.effort_summary();
+    assert_eq!(
+        summary.goals,
+        [
+            // The soft requirement on `docs` doesn't count.
+            (
+                "parse".to_owned(),
+                Effort {
+                    total: 11.0,
+                    remaining: 7.0
+                }
+            ),
+            (
+                "release".to_owned(),
+                Effort {
+                    total: 19.0,
+                    remaining: 15.0
+                }
+            ),
+        ]
+    );
+}
@@ -0,0 +1,428 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything that can go wrong when loading, validating, or rendering a
+/// skill-tree.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing failed; `path` is the file involved, if known.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+
+    /// A document could not be parsed. `line_col` is where, counting from 1.
+    Parse {
+        path: Option<PathBuf>,
+        message: String,
+        line_col: Option<(usize, usize)>,
+    },
+
+    /// A document is written for a version of the format this crate
+    /// doesn't know: 0, or newer than `latest`.
+    UnsupportedVersion { version: u32, latest: u32 },
+
+    /// A skill-tree could not be serialized.
+    Serialize { message: String },
+
+    /// Loading an included file failed.
+    Include {
+        include: String,
+        from: PathBuf,
+        source: Box<Error>,
+    },
+
+    /// Files include each other in a loop.
+    IncludeCycle { cycle: Vec<PathBuf> },
+
+    /// Two groups or goals share a name. `first` and `second` describe the
+    /// two definitions.
+    DuplicateName {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    /// Two items in the same group share a port.
+    DuplicatePort {
+        group: String,
+        port: String,
+        first: String,
+        second: String,
+    },
+
+    /// A `requires` entry names a group or goal that doesn't exist. `owner`
+    /// describes where the requirement was written.
+    UnknownReference {
+        owner: String,
+        name: String,
+        suggestion: Option<String>,
+    },
+
+    /// With `loose_references`, a `requires` entry only names the group or
+    /// goal `name` when case and surrounding whitespace are ignored.
+    LooseReference {
+        owner: String,
+        requirement: String,
+        name: String,
+    },
+
+    /// A `requires` entry names a port that the group doesn't have.
+    UnknownPort {
+        owner: String,
+        requirement: String,
+        group: String,
+        port: String,
+        suggestion: Option<String>,
+    },
+
+    /// A `requires` entry names a port on a goal; goals have no ports.
+    GoalPort {
+        owner: String,
+        requirement: String,
+        goal: String,
+    },
+
+    /// A group's `parent` names a group that doesn't exist.
+    UnknownParent { group: String, parent: String },
+
+    /// Groups are each other's sub-groups, listed from child to parent.
+    ParentCycle { cycle: Vec<String> },
+
+    /// An item's `progress` is over 100 percent.
+    InvalidProgress { item: String, progress: u8 },
+
+    /// A `due` date isn't written as `YYYY-MM-DD`.
+    InvalidDate { owner: String, date: String },
+
+    /// A status is used that has no style defined.
+    UnknownStatus {
+        owner: String,
+        status: String,
+        suggestion: Option<String>,
+    },
+
+    /// A group is in a phase that isn't listed in the tree's `phase`.
+    UnknownPhase {
+        group: String,
+        phase: String,
+        suggestion: Option<String>,
+    },
+
+    /// A link is neither a URL nor a relative path; `reason` says why.
+    InvalidHref {
+        owner: String,
+        href: String,
+        reason: String,
+    },
+
+    /// The requirements form a cycle, listed in the direction edges are drawn.
+    CycleDetected { cycle: Vec<String> },
+
+    /// No group by this name exists.
+    UnknownGroup { name: String },
+
+    /// No goal by this name exists.
+    UnknownGoal { name: String },
+
+    /// No group or goal by this name exists.
+    UnknownNode { name: String },
+
+    /// The group or goal `owner` has no item with this port or label.
+    UnknownItem { owner: String, item: String },
+
+    /// A group or goal by this name already exists.
+    NameInUse { name: String },
+
+    /// An output format name was not recognized.
+    UnknownFormat { name: String },
+
+    /// The graphviz `dot` executable could not be found.
+    GraphvizNotFound { program: String, env_var: String },
+
+    /// Running graphviz failed.
+    GraphvizFailed {
+        program: String,
+        status: String,
+        stderr: String,
+    },
+
+    /// Looking up an issue on GitHub failed.
+    GitHubFailed { url: String, message: String },
+}
+
+impl Error {
+    /// Records the file a parse error came from.
+    pub(crate) fn with_path(self, path: &Path) -> Error {
+        match self {
+            Error::Parse {
+                message, line_col, ..
+            } => Error::Parse {
+                path: Some(path.to_owned()),
+                message,
+                line_col,
+            },
+            error => error,
+        }
+    }
+}
+
+/// Formats `; did you mean `...`?` if there is a suggestion.
+struct DidYouMean<'a>(&'a Option<String>);
+
+impl fmt::Display for DidYouMean<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(suggestion) => write!(f, "; did you mean `{}`?", suggestion),
+            None => Ok(()),
+        }
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    items.join(" -> ")
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => {
+                write!(f, "cannot read `{}`: {}", path.display(), source)
+            }
+            Error::Io { path: None, source } => write!(f, "{}", source),
+            Error::Parse {
+                path: Some(path),
+                message,
+                ..
+            } => {
+                write!(f, "parsing `{}`: {}", path.display(), message)
+            }
+            Error::Parse {
+                path: None,
+                message,
+                ..
+            } => write!(f, "{}", message),
+            Error::UnsupportedVersion { version, latest } => write!(
+                f,
+                "this tree is written for version {} of the format, but only versions 1 to {} \
+                 are supported",
+                version, latest
+            ),
+            Error::Serialize { message } => write!(f, "serializing skill-tree: {}", message),
+            Error::Include { include, from, .. } => {
+                write!(f, "including `{}` from `{}`", include, from.display())
+            }
+            Error::IncludeCycle { cycle } => {
+                let cycle: Vec<_> = cycle.iter().map(|p| p.display()).collect();
+                write!(f, "include cycle: {}", join(&cycle))
+            }
+            Error::DuplicateName {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "`{}` is defined more than once: {} and {}",
+                name, first, second
+            ),
+            Error::DuplicatePort {
+                group,
+                port,
+                first,
+                second,
+            } => write!(
+                f,
+                "group `{}` has more than one item with port `{}`: {:?} and {:?}",
+                group, port, first, second
+            ),
+            Error::UnknownReference {
+                owner,
+                name,
+                suggestion,
+            } => write!(
+                f,
+                "{} requires unknown group or goal `{}`{}",
+                owner,
+                name,
+                DidYouMean(suggestion)
+            ),
+            Error::LooseReference {
+                owner,
+                requirement,
+                name,
+            } => write!(
+                f,
+                "{} requires `{}`, which only matches `{}` ignoring case and whitespace",
+                owner, requirement, name
+            ),
+            Error::UnknownPort {
+                owner,
+                requirement,
+                group,
+                port,
+                suggestion,
+            } => write!(
+                f,
+                "{} requires `{}`, but group `{}` has no port `{}`{}",
+                owner,
+                requirement,
+                group,
+                port,
+                DidYouMean(suggestion)
+            ),
+            Error::GoalPort {
+                owner,
+                requirement,
+                goal,
+            } => write!(
+                f,
+                "{} requires `{}`, but `{}` is a goal and goals have no ports",
+                owner, requirement, goal
+            ),
+            Error::UnknownStatus {
+                owner,
+                status,
+                suggestion,
+            } => write!(
+                f,
+                "{} has unknown status `{}`{}",
+                owner,
+                status,
+                DidYouMean(suggestion)
+            ),
+            Error::InvalidProgress { item, progress } => write!(
+                f,
+                "item `{}` has progress {}, but progress is a percentage from 0 to 100",
+                item, progress
+            ),
+            Error::InvalidDate { owner, date } => write!(
+                f,
+                "{} is due `{}`, but dates must be written as YYYY-MM-DD",
+                owner, date
+            ),
+            Error::UnknownPhase {
+                group,
+                phase,
+                suggestion,
+            } => write!(
+                f,
+                "group `{}` is in unknown phase `{}`{}",
+                group,
+                phase,
+                DidYouMean(suggestion)
+            ),
+            Error::InvalidHref {
+                owner,
+                href,
+                reason,
+            } => write!(
+                f,
+                "{} links to `{}`, which is not a valid URL or path: {}",
+                owner, href, reason
+            ),
+            Error::UnknownParent { group, parent } => write!(
+                f,
+                "group `{}` is a sub-group of unknown group `{}`",
+                group, parent
+            ),
+            Error::ParentCycle { cycle } => {
+                write!(f, "groups are each other's sub-groups: {}", join(cycle))
+            }
+            Error::CycleDetected { cycle } => write!(f, "dependency cycle: {}", join(cycle)),
+            Error::UnknownGroup { name } => write!(f, "no group named `{}`", name),
+            Error::UnknownGoal { name } => write!(f, "no goal named `{}`", name),
+            Error::UnknownNode { name } => write!(f, "no group or goal named `{}`", name),
+            Error::UnknownItem { owner, item } => write!(f, "`{}` has no item `{}`", owner, item),
+            Error::NameInUse { name } => {
+                write!(f, "a group or goal named `{}` already exists", name)
+            }
+            Error::UnknownFormat { name } => write!(f, "unknown output format `{}`", name),
+            Error::GraphvizNotFound { program, env_var } => write!(
+                f,
+                "could not find the graphviz `{}` executable; install graphviz \
+                 (https://graphviz.org/download/) or set `{}` to its location",
+                program, env_var
+            ),
+            Error::GraphvizFailed {
+                program,
+                status,
+                stderr,
+            } => write!(f, "`{}` failed ({}): {}", program, status, stderr),
+            Error::GitHubFailed { url, message } => {
+                write!(f, "fetching `{}` failed: {}", url, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // I/O errors are already part of the message; see `Display`.
+        match self {
+            Error::Include { source, .. } => Some(&**source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Error {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err).into()
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse {
+            path: None,
+            message: err.to_string(),
+            line_col: err.line_col().map(|(line, col)| (line + 1, col + 1)),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        // serde_json reports line 0 when it has no position.
+        let line_col = Some((err.line(), err.column())).filter(|&(line, _)| line > 0);
+        Error::Parse {
+            path: None,
+            message: err.to_string(),
+            line_col,
+        }
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl From<serde_yaml::Error> for Error {
+    fn from(err: serde_yaml::Error) -> Self {
+        let line_col = err
+            .location()
+            .map(|location| (location.line(), location.column()));
+        Error::Parse {
+            path: None,
+            message: err.to_string(),
+            line_col,
+        }
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::Serialize {
+            message: err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Exporters for formats other than graphviz.
+
+mod csv;
+mod graphml;
+mod html;
+mod markdown;
+mod mermaid;
+
+pub use html::{FULL_RENDER_JS, VIZ_JS};
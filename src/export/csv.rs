@@ -0,0 +1,67 @@
+use crate::csv;
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::throws;
+use std::io::Write;
+
+impl SkillTree {
+    /// Writes the items of this skill-tree as CSV to `items_output` and the
+    /// dependency edges between them as CSV to `edges_output`, with lines
+    /// ending in `\r\n` as RFC 4180 has it. Archived groups, items, and
+    /// goals are left out unless `include_archived` is set.
+    #[throws(Error)]
+    pub fn write_csv(&self, items_output: &mut dyn Write, edges_output: &mut dyn Write) {
+        let tree = self.visible();
+        write_items_csv(&tree, items_output)?;
+        write_edges_csv(&tree, edges_output)?;
+    }
+}
+
+#[throws(Error)]
+fn write_items_csv(tree: &SkillTree, output: &mut dyn Write) {
+    write_row(&["group", "label", "status", "href"], output)?;
+
+    for group in tree.groups() {
+        for item in group.items() {
+            let status = tree.item_status(group, item).map_or("", String::as_str);
+            let href = tree.item_href(&group.name, item).unwrap_or_default();
+            write_row(&[&group.name, &item.label, status, &href], output)?;
+        }
+    }
+}
+
+#[throws(Error)]
+fn write_edges_csv(tree: &SkillTree, output: &mut dyn Write) {
+    let resolved = tree.with_resolved_requirements();
+    let tree = &resolved;
+    write_row(&["from", "to"], output)?;
+
+    for group in tree.groups() {
+        for requirement in group.all_requirements() {
+            write_row(&[&requirement.on, &group.name], output)?;
+        }
+
+        for (item, port) in group.items().zip(group.ports()) {
+            for requirement in item.all_requirements() {
+                let to = format!("{}:{}", group.name, port);
+                write_row(&[&requirement.on, &to], output)?;
+            }
+        }
+    }
+
+    for goal in tree.goals() {
+        for requirement in goal.all_requirements() {
+            write_row(&[&requirement.on, &goal.name], output)?;
+        }
+    }
+}
+
+#[throws(Error)]
+fn write_row(fields: &[&str], output: &mut dyn Write) {
+    let mut record = String::new();
+    csv::write_record(&mut record, fields);
+    output.write_all(record.as_bytes())?;
+}
+
+#[cfg(test)]
+mod test;
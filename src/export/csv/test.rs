@@ -0,0 +1,104 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+status = "Complete"
+items = [
+    { label = "Tokens, spans", port = "tokens", href = "https://example.com/tokens" },
+    { label = 'The "trivia"', status = "InProgress" },
+]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Grammar", requires = ["lexer:tokens"] }]
+
+[[goal]]
+name = "ship"
+requires = ["parser"]
+"#;
+
+fn csv(text: &str) -> (String, String) {
+    let tree = SkillTree::parse(text).unwrap();
+    let mut items = vec![];
+    let mut edges = vec![];
+    tree.write_csv(&mut items, &mut edges).unwrap();
+    (
+        String::from_utf8(items).unwrap(),
+        String::from_utf8(edges).unwrap(),
+    )
+}
+
+#[test]
+fn items() {
+    let (items, _) = csv(TREE);
+    assert_eq!(
+        items,
+        "group,label,status,href\r
+lexer,\"Tokens, spans\",Complete,https://example.com/tokens\r
+lexer,\"The \"\"trivia\"\"\",InProgress,\r
+parser,Grammar,Unassigned,\r
+"
+    );
+}
+
+#[test]
+fn edges() {
+    let (_, edges) = csv(TREE);
+    assert_eq!(
+        edges,
+        "from,to\r\nlexer,parser\r\nlexer:tokens,parser:grammar\r\nparser,ship\r\n"
+    );
+}
+
+#[test]
+fn resolves_links() {
+    let (items, _) = csv(r#"
+href_template = "https://example.com/issues/{issue}"
+href_base = "https://example.com/book"
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", issue = 12 }, { label = "Trivia", href = "trivia.html" }]
+"#);
+    assert_eq!(
+        items,
+        "group,label,status,href\r
+lexer,Tokens,Unassigned,https://example.com/issues/12\r
+lexer,Trivia,Unassigned,https://example.com/book/trivia.html\r
+"
+    );
+}
+
+#[test]
+fn skips_archived() {
+    let text = r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }, { label = "Old", archived = true }]
+
+[[group]]
+name = "legacy"
+archived = true
+requires = ["lexer"]
+items = [{ label = "Shim" }]
+"#;
+    let (items, edges) = csv(text);
+    assert_eq!(
+        items,
+        "group,label,status,href\r\nlexer,Tokens,Unassigned,\r\n"
+    );
+    assert_eq!(edges, "from,to\r\n");
+
+    let mut tree = SkillTree::parse(text).unwrap();
+    tree.include_archived = true;
+    let mut items = vec![];
+    let mut edges = vec![];
+    tree.write_csv(&mut items, &mut edges).unwrap();
+    assert_eq!(String::from_utf8(items).unwrap().lines().count(), 4);
+    assert_eq!(
+        String::from_utf8(edges).unwrap(),
+        "from,to\r\nlexer,legacy\r\n"
+    );
+}
@@ -0,0 +1,168 @@
+use crate::error::Error;
+use crate::tree::{split_requirement, Goal, Group, SkillTree};
+use fehler::throws;
+use std::io::Write;
+
+impl SkillTree {
+    /// Writes GraphML representing this skill-tree to the given output.
+    /// Archived groups, items, and goals are left out unless
+    /// `include_archived` is set.
+    #[throws(Error)]
+    pub fn write_graphml(&self, output: &mut dyn Write) {
+        write_graphml(self, output)?
+    }
+
+    /// Generates a string containing GraphML content for this skill-tree.
+    #[throws(Error)]
+    pub fn to_graphml(&self) -> String {
+        let mut output = Vec::new();
+        write_graphml(self, &mut output)?;
+        String::from_utf8(output)?
+    }
+}
+
+#[throws(Error)]
+fn write_graphml(tree: &SkillTree, output: &mut dyn Write) {
+    let resolved = tree.visible().with_resolved_requirements();
+    let tree = &resolved;
+    writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        output,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="status" for="node" attr.name="status" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="href" for="node" attr.name="href" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="source_port" for="edge" attr.name="source_port" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <key id="target_port" for="edge" attr.name="target_port" attr.type="string"/>"#
+    )?;
+    writeln!(
+        output,
+        r#"  <graph id="skill-tree" edgedefault="directed">"#
+    )?;
+
+    for group in tree.groups() {
+        write_group_node(tree, group, output)?;
+    }
+
+    for goal in tree.goals() {
+        write_goal_node(goal, output)?;
+    }
+
+    let mut edge_index = 0;
+    for group in tree.groups() {
+        for requirement in group.all_requirements() {
+            write_edge(&mut edge_index, &requirement.on, &group.name, None, output)?;
+        }
+
+        for (item, port) in group.items().zip(group.ports()) {
+            for requirement in item.all_requirements() {
+                write_edge(
+                    &mut edge_index,
+                    &requirement.on,
+                    &group.name,
+                    Some(&port),
+                    output,
+                )?;
+            }
+        }
+    }
+
+    for goal in tree.goals() {
+        for requirement in goal.all_requirements() {
+            write_edge(&mut edge_index, &requirement.on, &goal.name, None, output)?;
+        }
+    }
+
+    writeln!(output, r#"  </graph>"#)?;
+    writeln!(output, r#"</graphml>"#)?;
+}
+
+fn escape(s: &str) -> String {
+    htmlescape::encode_minimal(s)
+}
+
+#[throws(Error)]
+fn write_group_node(tree: &SkillTree, group: &Group, output: &mut dyn Write) {
+    let label = group.label.as_ref().unwrap_or(&group.name);
+    let status = group.status.as_ref().or(tree.default_status.as_ref());
+
+    writeln!(output, r#"    <node id="{}">"#, escape(&group.name))?;
+    write_data("kind", Some("group"), output)?;
+    write_data("label", Some(label), output)?;
+    write_data("status", status, output)?;
+    write_data("href", group.href.as_ref(), output)?;
+    for port in group.ports() {
+        writeln!(output, r#"      <port name="{}"/>"#, escape(&port))?;
+    }
+    writeln!(output, r#"    </node>"#)?;
+}
+
+#[throws(Error)]
+fn write_goal_node(goal: &Goal, output: &mut dyn Write) {
+    let label = goal.label.as_ref().unwrap_or(&goal.name);
+
+    writeln!(output, r#"    <node id="{}">"#, escape(&goal.name))?;
+    write_data("kind", Some("goal"), output)?;
+    write_data("label", Some(label), output)?;
+    write_data("status", goal.status.as_ref(), output)?;
+    write_data("href", goal.href.as_ref(), output)?;
+    writeln!(output, r#"    </node>"#)?;
+}
+
+#[throws(Error)]
+fn write_data(key: &str, value: Option<impl AsRef<str>>, output: &mut dyn Write) {
+    if let Some(value) = value {
+        writeln!(
+            output,
+            r#"      <data key="{}">{}</data>"#,
+            key,
+            escape(value.as_ref())
+        )?;
+    }
+}
+
+#[throws(Error)]
+fn write_edge(
+    edge_index: &mut usize,
+    requirement: &str,
+    target: &str,
+    target_port: Option<&String>,
+    output: &mut dyn Write,
+) {
+    let (source, source_port) = split_requirement(requirement);
+
+    writeln!(
+        output,
+        r#"    <edge id="e{}" source="{}" target="{}">"#,
+        edge_index,
+        escape(source),
+        escape(target)
+    )?;
+    write_data("source_port", source_port, output)?;
+    write_data("target_port", target_port, output)?;
+    writeln!(output, r#"    </edge>"#)?;
+
+    *edge_index += 1;
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,89 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+label = "Lexer & <tokens>"
+status = "Complete"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Grammar", requires = ["lexer:tokens"] }]
+
+[[goal]]
+name = "ship"
+requires = ["parser"]
+"#;
+
+#[test]
+fn nodes() {
+    let graphml = SkillTree::parse(TREE).unwrap().to_graphml().unwrap();
+    assert!(graphml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(graphml.contains(
+        r#"    <node id="lexer">
+      <data key="kind">group</data>
+      <data key="label">Lexer &amp; &lt;tokens&gt;</data>
+      <data key="status">Complete</data>
+      <port name="tokens"/>
+    </node>"#
+    ));
+    // Items without a port get a made-up one.
+    assert!(graphml.contains(r#"<port name="grammar"/>"#));
+    assert!(graphml.contains(
+        r#"    <node id="ship">
+      <data key="kind">goal</data>
+      <data key="label">ship</data>
+    </node>"#
+    ));
+    assert!(!graphml.contains("<tokens>"));
+}
+
+#[test]
+fn edges() {
+    let graphml = SkillTree::parse(TREE).unwrap().to_graphml().unwrap();
+    let edges: Vec<&str> = graphml
+        .lines()
+        .filter(|line| line.trim_start().starts_with("<edge"))
+        .map(str::trim)
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            r#"<edge id="e0" source="lexer" target="parser">"#,
+            r#"<edge id="e1" source="lexer" target="parser">"#,
+            r#"<edge id="e2" source="parser" target="ship">"#,
+        ]
+    );
+    assert!(graphml.contains(
+        r#"    <edge id="e1" source="lexer" target="parser">
+      <data key="source_port">tokens</data>
+      <data key="target_port">grammar</data>
+    </edge>"#
+    ));
+}
+
+#[test]
+fn skips_archived() {
+    let text = r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }, { label = "Old", port = "old", archived = true }]
+
+[[goal]]
+name = "legacy"
+archived = true
+requires = ["lexer"]
+"#;
+    let graphml = SkillTree::parse(text).unwrap().to_graphml().unwrap();
+    assert!(!graphml.contains(r#"<port name="old"/>"#));
+    assert!(!graphml.contains("legacy"));
+    assert!(!graphml.contains("<edge"));
+
+    let mut tree = SkillTree::parse(text).unwrap();
+    tree.include_archived = true;
+    let graphml = tree.to_graphml().unwrap();
+    assert!(graphml.contains(r#"<port name="old"/>"#));
+    assert!(graphml.contains(r#"<edge id="e0" source="lexer" target="legacy">"#));
+}
@@ -0,0 +1,99 @@
+use crate::error::Error;
+use crate::graphviz::GraphvizOptions;
+use crate::tree::SkillTree;
+use fehler::throws;
+use std::io::Write;
+
+/// The viz.js library, used to render graphviz in the browser.
+pub const VIZ_JS: &str = include_str!("../../js/viz.js");
+
+/// The graphviz engine that viz.js loads to do the actual rendering.
+pub const FULL_RENDER_JS: &str = include_str!("../../js/full.render.js");
+
+const PAN_ZOOM_JS: &str = include_str!("../../js/pan-zoom.js");
+
+impl SkillTree {
+    /// Writes a self-contained HTML page to the given output. The page
+    /// renders this skill-tree in the browser using viz.js and supports
+    /// panning and zooming.
+    #[throws(Error)]
+    pub fn write_html(&self, output: &mut dyn Write) {
+        write_html(self, &GraphvizOptions::default(), output)?
+    }
+
+    /// Like `write_html`, but with the given graph-wide settings.
+    #[throws(Error)]
+    pub fn write_html_with(&self, options: &GraphvizOptions, output: &mut dyn Write) {
+        write_html(self, options, output)?
+    }
+
+    /// Generates a string containing a self-contained HTML page for this skill-tree.
+    #[throws(Error)]
+    pub fn to_html(&self) -> String {
+        let mut output = Vec::new();
+        write_html(self, &GraphvizOptions::default(), &mut output)?;
+        String::from_utf8(output)?
+    }
+}
+
+#[throws(Error)]
+fn write_html(tree: &SkillTree, options: &GraphvizOptions, output: &mut dyn Write) {
+    let dot_text = tree.to_graphviz_with(options)?;
+
+    writeln!(output, r#"<!DOCTYPE html>"#)?;
+    writeln!(output, r#"<html>"#)?;
+    writeln!(output, r#"<head>"#)?;
+    writeln!(output, r#"  <meta charset="utf-8">"#)?;
+    let title = tree.title.as_deref().unwrap_or("Skill Tree");
+    writeln!(
+        output,
+        r#"  <title>{}</title>"#,
+        htmlescape::encode_minimal(title)
+    )?;
+    writeln!(output, r#"  <style>"#)?;
+    writeln!(output, r#"    html, body {{ margin: 0; height: 100%; }}"#)?;
+    writeln!(
+        output,
+        r#"    #skill-tree {{ width: 100%; height: 100%; overflow: hidden; cursor: grab; }}"#
+    )?;
+    writeln!(
+        output,
+        r#"    #skill-tree svg {{ width: 100%; height: 100%; }}"#
+    )?;
+    writeln!(output, r#"  </style>"#)?;
+    writeln!(output, r#"</head>"#)?;
+    writeln!(output, r#"<body>"#)?;
+    writeln!(output, r#"  <div id="skill-tree"></div>"#)?;
+    writeln!(output, r#"  <script>{}</script>"#, VIZ_JS)?;
+    writeln!(output, r#"  <script>{}</script>"#, FULL_RENDER_JS)?;
+    writeln!(output, r#"  <script>{}</script>"#, PAN_ZOOM_JS)?;
+    writeln!(
+        output,
+        r#"  <script>renderPanZoomSkillTree("skill-tree", {});</script>"#,
+        js_string(&dot_text)
+    )?;
+    writeln!(output, r#"</body>"#)?;
+    writeln!(output, r#"</html>"#)?;
+}
+
+/// Encodes `s` as a javascript string literal that is safe to embed in a
+/// `<script>` tag.
+fn js_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '<' => result.push_str("\\u003c"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,52 @@
+use super::js_string;
+use crate::SkillTree;
+
+#[test]
+fn title() {
+    let tree = SkillTree::parse(
+        r#"
+title = "R&D <roadmap>"
+
+[[group]]
+name = "lexer"
+items = []
+"#,
+    )
+    .unwrap();
+    let html = tree.to_html().unwrap();
+    assert!(html.contains("<title>R&amp;D &lt;roadmap&gt;</title>"));
+
+    let tree = SkillTree::parse("[[group]]\nname = \"lexer\"\nitems = []\n").unwrap();
+    assert!(tree
+        .to_html()
+        .unwrap()
+        .contains("<title>Skill Tree</title>"));
+}
+
+#[test]
+fn embeds_graphviz() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+label = "</script>"
+items = []
+"#,
+    )
+    .unwrap();
+    let html = tree.to_html().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(html.contains(&format!(
+        r#"renderPanZoomSkillTree("skill-tree", {});"#,
+        js_string(&dot)
+    )));
+    assert_eq!(html.matches("</script>").count(), 4);
+}
+
+#[test]
+fn escapes_js_strings() {
+    assert_eq!(
+        js_string("a \"b\"\\\n</script>"),
+        r#""a \"b\"\\\n\u003c/script>""#
+    );
+}
@@ -0,0 +1,76 @@
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::throws;
+use std::io::Write;
+
+impl SkillTree {
+    /// Writes a markdown checklist representing this skill-tree to the given output.
+    #[throws(Error)]
+    pub fn write_markdown(&self, output: &mut dyn Write) {
+        write_markdown(self, output)?
+    }
+
+    /// Generates a string containing a markdown checklist for this skill-tree.
+    #[throws(Error)]
+    pub fn to_markdown(&self) -> String {
+        let mut output = Vec::new();
+        write_markdown(self, &mut output)?;
+        String::from_utf8(output)?
+    }
+}
+
+#[throws(Error)]
+fn write_markdown(tree: &SkillTree, output: &mut dyn Write) {
+    let tree = &*tree.visible();
+    let mut first = true;
+
+    for group in tree.groups() {
+        if !first {
+            writeln!(output)?;
+        }
+        first = false;
+
+        let label = group.label.as_ref().unwrap_or(&group.name);
+        let href = group.href.as_deref().map(|href| tree.resolve_href(href));
+        writeln!(output, "## {}", link(label, &href))?;
+        writeln!(output)?;
+
+        for item in group.items() {
+            let complete = tree.is_completed(tree.item_status(group, item));
+            let check = if complete { "x" } else { " " };
+            let href = tree.item_href(&group.name, item);
+            writeln!(output, "- [{}] {}", check, link(&item.label, &href))?;
+        }
+    }
+
+    let mut goals = tree.goals().peekable();
+    if goals.peek().is_some() {
+        if !first {
+            writeln!(output)?;
+        }
+        writeln!(output, "## Goals")?;
+        writeln!(output)?;
+
+        for goal in goals {
+            let label = goal.label.as_ref().unwrap_or(&goal.name);
+            let href = goal.href.as_deref().map(|href| tree.resolve_href(href));
+            writeln!(output, "- {}", link(label, &href))?;
+            for item in goal.items() {
+                let complete = tree.is_completed(tree.goal_item_status(item));
+                let check = if complete { "x" } else { " " };
+                let href = tree.item_href(&goal.name, item);
+                writeln!(output, "  - [{}] {}", check, link(&item.label, &href))?;
+            }
+        }
+    }
+}
+
+fn link(label: &str, href: &Option<String>) -> String {
+    match href {
+        None => label.to_owned(),
+        Some(href) => format!("[{}]({})", label, href),
+    }
+}
+
+#[cfg(test)]
+mod test;
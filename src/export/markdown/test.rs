@@ -0,0 +1,91 @@
+use crate::SkillTree;
+
+#[test]
+fn checklist() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+label = "Lexer"
+href = "https://example.com/lexer"
+status = "Complete"
+items = [{ label = "Tokens" }, { label = "Trivia", status = "InProgress" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Grammar", href = "https://example.com/grammar" }]
+
+[[goal]]
+name = "ship"
+label = "Ship it"
+requires = ["parser"]
+items = [{ label = "Announce" }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.to_markdown().unwrap(),
+        "## [Lexer](https://example.com/lexer)
+
+- [x] Tokens
+- [ ] Trivia
+
+## parser
+
+- [ ] [Grammar](https://example.com/grammar)
+
+## Goals
+
+- Ship it
+  - [ ] Announce
+"
+    );
+}
+
+#[test]
+fn no_goals() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(tree.to_markdown().unwrap(), "## lexer\n\n- [ ] Tokens\n");
+}
+
+#[test]
+fn resolves_links() {
+    let tree = SkillTree::parse(
+        r#"
+href_template = "https://example.com/issues/{issue}"
+href_base = "https://example.com/book"
+
+[[group]]
+name = "lexer"
+href = "lexer.html"
+items = [{ label = "Tokens", issue = 12 }, { label = "Trivia", href = "trivia.html" }]
+
+[[goal]]
+name = "ship"
+href = "ship.html"
+items = [{ label = "Announce", issue = 13 }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.to_markdown().unwrap(),
+        "## [lexer](https://example.com/book/lexer.html)
+
+- [ ] [Tokens](https://example.com/issues/12)
+- [ ] [Trivia](https://example.com/book/trivia.html)
+
+## Goals
+
+- [ship](https://example.com/book/ship.html)
+  - [ ] [Announce](https://example.com/issues/13)
+"
+    );
+}
@@ -0,0 +1,217 @@
+use crate::date;
+use crate::error::Error;
+use crate::tree::{split_requirement, Requirement, SkillTree, StatusStyle};
+use fehler::throws;
+use std::collections::HashMap;
+use std::io::Write;
+
+impl SkillTree {
+    /// Writes a Mermaid Gantt chart of this skill-tree to the given output.
+    /// See `to_mermaid_gantt`.
+    #[throws(Error)]
+    pub fn write_mermaid_gantt(&self, output: &mut dyn Write) {
+        write_gantt(self, output)?
+    }
+
+    /// Generates a Mermaid Gantt chart for this skill-tree, with a section
+    /// for each group and a task for each of its items. Tasks start after
+    /// the tasks they require (every item of a required group, and the
+    /// milestone of a required goal) and take `effort` days, or last until
+    /// they are `due`, or else take a day. Tasks that require nothing start
+    /// on the tree's `updated` date, if it is one, or else today. Goals are
+    /// milestones. Completed items are marked `done` and those in progress
+    /// `active`. Soft requirements are left out.
+    #[throws(Error)]
+    pub fn to_mermaid_gantt(&self) -> String {
+        let mut output = Vec::new();
+        write_gantt(self, &mut output)?;
+        String::from_utf8(output)?
+    }
+}
+
+#[throws(Error)]
+fn write_gantt(tree: &SkillTree, output: &mut dyn Write) {
+    let resolved = tree.visible().with_resolved_requirements();
+    let tree = &resolved;
+    let start = match &tree.updated {
+        Some(updated) if date::is_date(updated) => updated.clone(),
+        _ => date::today(),
+    };
+    let ids = TaskIds::new(tree);
+
+    writeln!(output, "gantt")?;
+    if let Some(title) = &tree.title {
+        writeln!(output, "    title {}", task_name(title))?;
+    }
+    writeln!(output, "    dateFormat YYYY-MM-DD")?;
+
+    for group in tree.groups().filter(|group| !group.items.is_empty()) {
+        let label = group.label.as_ref().unwrap_or(&group.name);
+        writeln!(output, "    section {}", task_name(label))?;
+        for (item, port) in group.items().zip(group.ports()) {
+            let requirements = group.all_requirements().chain(item.all_requirements());
+            let style = tree
+                .item_status(group, item)
+                .and_then(|status| tree.status_style(status));
+            let end = match (item.effort, &item.due) {
+                (Some(effort), _) => format!("{}d", effort.ceil().max(1.0)),
+                (None, Some(due)) if date::is_date(due) => due.clone(),
+                _ => "1d".to_owned(),
+            };
+            writeln!(
+                output,
+                "    {} :{}{}, {}, {}",
+                task_name(&item.label),
+                status_tag(style),
+                ids.item(&group.name, &port),
+                ids.start(requirements, &start),
+                end,
+            )?;
+        }
+    }
+
+    let mut goals = tree.goals().peekable();
+    if goals.peek().is_some() {
+        writeln!(output, "    section Goals")?;
+    }
+    for goal in goals {
+        let label = goal.label.as_ref().unwrap_or(&goal.name);
+        let style = goal.status.as_ref().and_then(|s| tree.status_style(s));
+        let start = match &goal.due {
+            Some(due) if date::is_date(due) && goal.all_requirements().next().is_none() => {
+                due.clone()
+            }
+            _ => ids.start(goal.all_requirements(), &start),
+        };
+        writeln!(
+            output,
+            "    {} :milestone, {}{}, {}, 0d",
+            task_name(label),
+            status_tag(style),
+            ids.node(&goal.name),
+            start,
+        )?;
+    }
+}
+
+/// Mermaid ids for the items of each group and for the goals.
+struct TaskIds<'t> {
+    tree: &'t SkillTree,
+    ids: HashMap<(String, String), String>,
+}
+
+impl<'t> TaskIds<'t> {
+    fn new(tree: &'t SkillTree) -> Self {
+        let mut ids: HashMap<(String, String), String> = HashMap::new();
+        let mut taken: Vec<String> = vec![];
+        let mut add = |key: (String, String), name: String| {
+            let base = task_id(&name);
+            let mut id = base.clone();
+            let mut n = 1;
+            while taken.contains(&id) {
+                n += 1;
+                id = format!("{}_{}", base, n);
+            }
+            taken.push(id.clone());
+            ids.insert(key, id);
+        };
+        for group in tree.groups() {
+            for port in group.ports() {
+                add(
+                    (group.name.clone(), port.clone()),
+                    format!("{}_{}", group.name, port),
+                );
+            }
+        }
+        for goal in tree.goals() {
+            add((goal.name.clone(), String::new()), goal.name.clone());
+        }
+        TaskIds { tree, ids }
+    }
+
+    fn item(&self, group: &str, port: &str) -> &str {
+        &self.ids[&(group.to_owned(), port.to_owned())]
+    }
+
+    /// The id of a goal's milestone.
+    fn node(&self, goal: &str) -> &str {
+        &self.ids[&(goal.to_owned(), String::new())]
+    }
+
+    /// When a task with `requirements` starts: `after` the tasks they name,
+    /// or at `start` if there are none.
+    fn start<'r>(
+        &self,
+        requirements: impl Iterator<Item = &'r Requirement>,
+        start: &str,
+    ) -> String {
+        let mut after: Vec<&str> = vec![];
+        for requirement in requirements.filter(|requirement| !requirement.soft) {
+            let (name, port) = split_requirement(&requirement.on);
+            let ids: Vec<&str> = match (self.tree.group(name), port) {
+                (Some(_), Some(port)) => self
+                    .ids
+                    .get(&(name.to_owned(), port.to_owned()))
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect(),
+                (Some(group), None) => group
+                    .ports()
+                    .into_iter()
+                    .filter_map(|port| self.ids.get(&(name.to_owned(), port)))
+                    .map(String::as_str)
+                    .collect(),
+                (None, _) => self
+                    .ids
+                    .get(&(name.to_owned(), String::new()))
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect(),
+            };
+            for id in ids {
+                if !after.contains(&id) {
+                    after.push(id);
+                }
+            }
+        }
+        if after.is_empty() {
+            start.to_owned()
+        } else {
+            format!("after {}", after.join(" "))
+        }
+    }
+}
+
+/// `done, ` or `active, ` for tasks with a completed or in-progress status.
+fn status_tag(style: Option<&StatusStyle>) -> &'static str {
+    match style {
+        Some(style) if style.completed => "done, ",
+        Some(style) if style.in_progress => "active, ",
+        _ => "",
+    }
+}
+
+/// Makes `name` usable as a task id, which can only contain letters,
+/// digits, and underscores.
+fn task_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Escapes the characters Mermaid would otherwise take as the end of a
+/// task's name or a comment.
+fn task_name(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        match c {
+            '#' | ':' | ';' => escaped.push_str(&format!("#{};", c as u32)),
+            '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,72 @@
+use crate::SkillTree;
+
+#[test]
+fn gantt() {
+    let tree = SkillTree::parse(
+        r#"
+title = "Road: map"
+updated = "2020-03-01"
+
+[[group]]
+name = "lexer"
+status = "Complete"
+items = [
+    { label = "Tokens", port = "tokens", effort = 2.5 },
+    { label = "Trivia", status = "Assigned", due = "2020-04-01" },
+]
+
+[[group]]
+name = "parser"
+label = "Parser #1"
+requires = ["lexer"]
+items = [{ label = "Grammar", requires = ["ship-lexer"] }]
+
+[[goal]]
+name = "ship-lexer"
+requires = ["lexer:tokens"]
+
+[[goal]]
+name = "release"
+due = "2020-06-01"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.to_mermaid_gantt().unwrap(),
+        "gantt
+    title Road#58; map
+    dateFormat YYYY-MM-DD
+    section lexer
+    Tokens :done, lexer_tokens, 2020-03-01, 3d
+    Trivia :active, lexer_trivia, 2020-03-01, 2020-04-01
+    section Parser #35;1
+    Grammar :parser_grammar, after lexer_tokens lexer_trivia ship_lexer, 1d
+    section Goals
+    ship-lexer :milestone, ship_lexer, after lexer_tokens, 0d
+    release :milestone, release, 2020-06-01, 0d
+"
+    );
+}
+
+#[test]
+fn skips_soft_requirements() {
+    let tree = SkillTree::parse(
+        r#"
+updated = "2020-03-01"
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer?"]
+items = [{ label = "Grammar" }]
+"#,
+    )
+    .unwrap();
+    assert!(tree
+        .to_mermaid_gantt()
+        .unwrap()
+        .contains("Grammar :parser_grammar, 2020-03-01, 1d"));
+}
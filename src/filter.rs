@@ -0,0 +1,385 @@
+//! Narrows a skill-tree down to some of its items, e.g. one team's.
+
+use crate::error::Error;
+use crate::tree::{split_requirement, Goal, Group, Item, ItemIndex, Requirement, SkillTree};
+use fehler::{throw, throws};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+impl SkillTree {
+    /// Returns a copy of the tree with only the items `keep` accepts.
+    /// Groups left without items are dropped. Requirements on something
+    /// dropped are replaced by its own requirements, so what remains is
+    /// still connected the way it was. Goals are always kept.
+    pub fn filtered(&self, keep: impl Fn(&Group, &Item) -> bool) -> SkillTree {
+        self.retained(|group| !group.items.is_empty(), keep, |_| true)
+    }
+
+    /// Like `filtered`, but also drops the groups and goals `keep_group` and
+    /// `keep_goal` reject. Groups left without items are still dropped,
+    /// unless they had none to begin with.
+    fn retained(
+        &self,
+        keep_group: impl Fn(&Group) -> bool,
+        keep: impl Fn(&Group, &Item) -> bool,
+        keep_goal: impl Fn(&Goal) -> bool,
+    ) -> SkillTree {
+        let mut dropped = Dropped::default();
+        let mut tree = self.clone();
+        for (group, original) in tree.group.iter_mut().zip(&self.group) {
+            let mut originals = original.items().zip(original.ports());
+            group.items.retain(|_| {
+                let (item, port) = originals.next().unwrap();
+                let kept = keep(original, item);
+                if !kept {
+                    dropped.ports.insert((original.name.clone(), port));
+                }
+                kept
+            });
+            if !keep_group(original) || (group.items.is_empty() && !original.items.is_empty()) {
+                dropped.groups.insert(group.name.clone());
+            }
+        }
+        tree.group
+            .retain(|group| !dropped.groups.contains(&group.name));
+        if let Some(goals) = &mut tree.goal {
+            goals.retain(|goal| {
+                let kept = keep_goal(goal);
+                if !kept {
+                    dropped.goals.insert(goal.name.clone());
+                }
+                kept
+            });
+        }
+
+        for group in &mut tree.group {
+            dropped.reroute_all(self, &mut group.requires, &mut group.requires_any);
+            for item in &mut group.items {
+                dropped.reroute_all(self, &mut item.requires, &mut item.requires_any);
+            }
+        }
+        for goal in tree.goal.iter_mut().flatten() {
+            dropped.reroute_all(self, &mut goal.requires, &mut goal.requires_any);
+        }
+        tree
+    }
+
+    /// Returns a copy of the tree without its archived groups, items, and
+    /// goals. As with `filtered`, requirements on them are replaced by
+    /// their own requirements, and groups whose items are all archived are
+    /// dropped too. Archiving a group archives its sub-groups as well.
+    pub fn without_archived(&self) -> SkillTree {
+        self.retained(
+            |group| !self.is_archived(group),
+            |_, item| !item.archived,
+            |goal| !goal.archived,
+        )
+    }
+
+    /// Whether `group` or any group it is a sub-group of is archived.
+    fn is_archived(&self, group: &Group) -> bool {
+        let mut seen = HashSet::new();
+        let mut group = Some(group);
+        while let Some(g) = group {
+            if g.archived {
+                return true;
+            }
+            if !seen.insert(&g.name) {
+                break;
+            }
+            group = self.parent_group(g);
+        }
+        false
+    }
+
+    /// The tree as it is drawn and counted: without what is archived,
+    /// unless `include_archived` is set.
+    pub(crate) fn visible(&self) -> Cow<'_, SkillTree> {
+        let archived = self
+            .groups()
+            .any(|group| group.archived || group.items().any(|item| item.archived))
+            || self.goals().any(|goal| goal.archived);
+        if archived && !self.include_archived {
+            Cow::Owned(self.without_archived())
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
+
+    /// Keeps only the items tagged with any of `tags`, as with `filtered`.
+    /// A group's tags count as tags of each of its items.
+    pub fn with_tags(&self, tags: &[impl AsRef<str>]) -> SkillTree {
+        self.filtered(|group, item| is_tagged(group, item, tags))
+    }
+
+    /// Returns a copy of the tree with only the goal `name` and the groups
+    /// and goals it requires, directly or indirectly. Soft requirements and
+    /// every alternative of a `requires_any` count, so nothing the goal
+    /// points at is lost.
+    #[throws(Error)]
+    pub fn subtree_for_goal(&self, name: &str) -> SkillTree {
+        if !self.is_goal(name) {
+            throw!(Error::UnknownGoal {
+                name: name.to_owned()
+            });
+        }
+
+        let mut needed = HashSet::new();
+        let mut pending = vec![name];
+        while let Some(name) = pending.pop() {
+            if !needed.insert(name) {
+                continue;
+            }
+            if let Some(group) = self.group(name) {
+                pending.extend(
+                    group
+                        .all_requirements()
+                        .chain(group.items().flat_map(Item::all_requirements))
+                        .map(|requirement| self.required_name(&requirement.on)),
+                );
+            } else if let Some(goal) = self.goals().find(|goal| goal.name == name) {
+                pending.extend(
+                    goal.all_requirements()
+                        .map(|requirement| self.required_name(&requirement.on)),
+                );
+            }
+        }
+
+        let mut tree = self.clone();
+        tree.group
+            .retain(|group| needed.contains(group.name.as_str()));
+        if let Some(goals) = &mut tree.goal {
+            goals.retain(|goal| needed.contains(goal.name.as_str()));
+        }
+        tree
+    }
+
+    /// Names the groups and goals that depend on the group, goal, or
+    /// `group:port` item `name`, directly or indirectly, in the order they
+    /// are defined: what finishing `name` helps unblock. Soft requirements
+    /// don't count, since they don't block anything.
+    pub fn dependents_of(&self, name: &str) -> Vec<&str> {
+        let mut dependents = HashSet::new();
+        let mut pending = vec![split_requirement(name)];
+        while let Some((name, port)) = pending.pop() {
+            let requires_it = |requirement: &Requirement| {
+                let on = self.resolve_requirement(&requirement.on);
+                let (on, on_port) = split_requirement(&on);
+                !requirement.soft
+                    && on == name
+                    && (port.is_none() || on_port.is_none() || on_port == port)
+            };
+            let groups = self.groups().filter(|group| {
+                group
+                    .all_requirements()
+                    .chain(group.items().flat_map(Item::all_requirements))
+                    .any(requires_it)
+            });
+            let goals = self
+                .goals()
+                .filter(|goal| goal.all_requirements().any(requires_it));
+            for dependent in groups
+                .map(|group| group.name.as_str())
+                .chain(goals.map(|goal| goal.name.as_str()))
+            {
+                if dependents.insert(dependent) {
+                    pending.push((dependent, None));
+                }
+            }
+        }
+
+        self.groups()
+            .map(|group| group.name.as_str())
+            .chain(self.goals().map(|goal| goal.name.as_str()))
+            .filter(|name| dependents.contains(name))
+            .collect()
+    }
+
+    /// Returns a copy of the tree with only the group, goal, or
+    /// `group:port` item `name` and what depends on it, as listed by
+    /// `dependents_of`. Requirements on what was left out are dropped.
+    #[throws(Error)]
+    pub fn subtree_from(&self, name: &str) -> SkillTree {
+        let (start, port) = split_requirement(name);
+        if self.group(start).is_none() && !self.is_goal(start) {
+            throw!(Error::UnknownGroup {
+                name: start.to_owned()
+            });
+        }
+
+        let start_item = match (self.group(start), port) {
+            (Some(group), Some(port)) => group
+                .item_with_port(port)
+                .map(|ItemIndex(index)| &group.items[index]),
+            _ => None,
+        };
+        let dependents: HashSet<&str> = self.dependents_of(name).into_iter().collect();
+        self.retained(
+            |group| group.name == start || dependents.contains(group.name.as_str()),
+            |group, item| {
+                group.name != start
+                    || port.is_none()
+                    || matches!(start_item, Some(start) if std::ptr::eq(start, item))
+            },
+            |goal| goal.name == start || dependents.contains(goal.name.as_str()),
+        )
+    }
+
+    /// Returns a copy of the tree in which the requirements of the items in
+    /// `groups` are moved to the groups themselves, and requirements on
+    /// those items' ports point at the groups instead, so that the groups
+    /// can be drawn without their items.
+    pub(crate) fn without_ports(&self, groups: &HashSet<&str>) -> SkillTree {
+        let mut tree = self.clone();
+        for group in &mut tree.group {
+            if !groups.contains(group.name.as_str()) {
+                continue;
+            }
+            for item in &mut group.items {
+                if let Some(requires) = item.requires.take() {
+                    let group_requires = group.requires.get_or_insert_with(Vec::new);
+                    for requirement in requires {
+                        if !group_requires.contains(&requirement) {
+                            group_requires.push(requirement);
+                        }
+                    }
+                }
+                group.requires_any.append(&mut item.requires_any);
+            }
+        }
+
+        let retarget = |requirement: &mut Requirement| {
+            let name = self.required_name(&requirement.on).to_owned();
+            if groups.contains(name.as_str()) {
+                requirement.on = name;
+            }
+        };
+        for group in &mut tree.group {
+            group
+                .requires
+                .iter_mut()
+                .flatten()
+                .chain(group.requires_any.iter_mut().flatten())
+                .for_each(retarget);
+            for item in &mut group.items {
+                item.requires
+                    .iter_mut()
+                    .flatten()
+                    .chain(item.requires_any.iter_mut().flatten())
+                    .for_each(retarget);
+            }
+        }
+        for goal in tree.goal.iter_mut().flatten() {
+            goal.requires
+                .iter_mut()
+                .flatten()
+                .chain(goal.requires_any.iter_mut().flatten())
+                .for_each(retarget);
+        }
+        tree
+    }
+
+    /// Whether the status of `item` is one of `statuses`, or an alias of one.
+    pub(crate) fn has_status(&self, group: &Group, item: &Item, statuses: &[String]) -> bool {
+        match self.item_status(group, item) {
+            Some(status) => {
+                let status = self.canonical_status(status);
+                statuses
+                    .iter()
+                    .any(|wanted| self.canonical_status(wanted) == status)
+            }
+            None => false,
+        }
+    }
+}
+
+/// Whether `item`, or the group it is in, has any of `tags`.
+pub(crate) fn is_tagged(group: &Group, item: &Item, tags: &[impl AsRef<str>]) -> bool {
+    group
+        .tags
+        .iter()
+        .chain(&item.tags)
+        .any(|tag| tags.iter().any(|wanted| wanted.as_ref() == tag))
+}
+
+/// The groups, goals, and ports of items that a filter removed.
+#[derive(Default)]
+struct Dropped {
+    groups: HashSet<String>,
+    goals: HashSet<String>,
+    ports: HashSet<(String, String)>,
+}
+
+impl Dropped {
+    fn reroute_all(
+        &self,
+        original: &SkillTree,
+        requires: &mut Option<Vec<Requirement>>,
+        requires_any: &mut Vec<Vec<Requirement>>,
+    ) {
+        if let Some(requires) = requires {
+            *requires = self.reroute(original, requires);
+        }
+        for alternatives in requires_any.iter_mut() {
+            *alternatives = self.reroute(original, alternatives);
+        }
+        requires_any.retain(|alternatives| !alternatives.is_empty());
+    }
+
+    /// Replaces each requirement on something dropped with the requirements
+    /// of what was dropped, recursively.
+    fn reroute(&self, original: &SkillTree, requirements: &[Requirement]) -> Vec<Requirement> {
+        let mut rerouted = vec![];
+        let mut seen = HashSet::new();
+        let mut pending: Vec<Requirement> = requirements.iter().rev().cloned().collect();
+        while let Some(requirement) = pending.pop() {
+            if !seen.insert(requirement.on.clone()) {
+                continue;
+            }
+            let on = original.resolve_requirement(&requirement.on);
+            let (name, port) = split_requirement(&on);
+            let group = original.groups().find(|group| group.name == name);
+            let replacements: Vec<&Requirement> = match (group, port) {
+                (None, None) if self.goals.contains(name) => original
+                    .goals()
+                    .filter(|goal| goal.name == name)
+                    .flat_map(Goal::all_requirements)
+                    .collect(),
+                (Some(group), _) if self.groups.contains(name) => group
+                    .all_requirements()
+                    .chain(group.items().flat_map(Item::all_requirements))
+                    .collect(),
+                (Some(group), Some(port))
+                    if self.ports.contains(&(name.to_owned(), port.to_owned())) =>
+                {
+                    group
+                        .item_with_port(port)
+                        .map(|ItemIndex(index)| &group.items[index])
+                        .into_iter()
+                        .flat_map(Item::all_requirements)
+                        .collect()
+                }
+                _ => {
+                    if !rerouted.contains(&requirement) {
+                        rerouted.push(requirement);
+                    }
+                    continue;
+                }
+            };
+            // Whatever is reached through a soft requirement is soft too.
+            pending.extend(
+                replacements
+                    .into_iter()
+                    .rev()
+                    .map(|replacement| Requirement {
+                        soft: replacement.soft || requirement.soft,
+                        ..replacement.clone()
+                    }),
+            );
+        }
+        rerouted
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,181 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+tags = ["frontend"]
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [
+    { label = "Expressions", port = "exprs", tags = ["frontend"] },
+    { label = "Macros", port = "macros", requires = ["lexer:tokens"], tags = ["macros"] },
+]
+
+[[group]]
+name = "codegen"
+requires = ["parser"]
+items = [{ label = "LLVM", requires = ["parser:macros"], port = "llvm" }]
+
+[[group]]
+name = "docs"
+requires = ["codegen?"]
+tags = ["frontend"]
+items = [{ label = "Guide" }]
+
+[[goal]]
+name = "compile"
+requires = ["codegen"]
+"#;
+
+fn requires(tree: &SkillTree, name: &str) -> Vec<String> {
+    let group = tree.groups().find(|group| group.name == name);
+    let goal = tree.goals().find(|goal| goal.name == name);
+    let requirements: Vec<_> = match (group, goal) {
+        (Some(group), _) => group.all_requirements().collect(),
+        (_, Some(goal)) => goal.all_requirements().collect(),
+        _ => panic!("no node named {}", name),
+    };
+    requirements
+        .iter()
+        .map(|requirement| {
+            format!(
+                "{}{}",
+                requirement.on,
+                if requirement.soft { "?" } else { "" }
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn keeps_tagged_items() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let filtered = tree.with_tags(&["frontend"]);
+    let groups: Vec<&str> = filtered.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["lexer", "parser", "docs"]);
+    let parser = filtered
+        .groups()
+        .find(|group| group.name == "parser")
+        .unwrap();
+    assert_eq!(parser.items.len(), 1);
+    assert_eq!(filtered.goals().count(), 1);
+    filtered.validate().unwrap();
+}
+
+#[test]
+fn reroutes_requirements_on_dropped_nodes() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let filtered = tree.with_tags(&["frontend"]);
+    // `codegen` is gone, so the goal depends on what `codegen` did, and
+    // `parser:macros` is replaced by what the macros item required.
+    assert_eq!(requires(&filtered, "compile"), ["parser", "lexer:tokens"]);
+    // Requirements reached through a soft one stay soft.
+    assert_eq!(requires(&filtered, "docs"), ["parser?", "lexer:tokens?"]);
+}
+
+#[test]
+fn subtree_for_goal() {
+    let toml = format!(
+        r#"{}
+[[group]]
+name = "unrelated"
+items = [{{ label = "Other" }}]
+
+[[goal]]
+name = "document"
+requires = ["docs"]
+"#,
+        TREE
+    );
+    let tree = SkillTree::parse(&toml).unwrap();
+    let subtree = tree.subtree_for_goal("compile").unwrap();
+    let groups: Vec<&str> = subtree.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["lexer", "parser", "codegen"]);
+    let goals: Vec<&str> = subtree.goals().map(|goal| goal.name.as_str()).collect();
+    assert_eq!(goals, ["compile"]);
+    subtree.validate().unwrap();
+
+    // Soft requirements are followed too.
+    let subtree = tree.subtree_for_goal("document").unwrap();
+    assert_eq!(subtree.groups().count(), 4);
+
+    assert!(tree.subtree_for_goal("lexer").is_err());
+}
+
+#[test]
+fn dependents_of() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    assert_eq!(
+        tree.dependents_of("lexer"),
+        ["parser", "codegen", "compile"]
+    );
+    // Only `codegen` needs the macros, but everything needing the whole
+    // parser does too.
+    assert_eq!(tree.dependents_of("parser:macros"), ["codegen", "compile"]);
+    // `docs` only softly requires `codegen`.
+    assert_eq!(tree.dependents_of("codegen"), ["compile"]);
+    assert!(tree.dependents_of("compile").is_empty());
+}
+
+#[test]
+fn subtree_from() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let subtree = tree.subtree_from("parser:macros").unwrap();
+    let groups: Vec<&str> = subtree.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["parser", "codegen"]);
+    let parser = subtree.group("parser").unwrap();
+    assert_eq!(parser.items.len(), 1);
+    // The macros no longer require the lexer, which was left out.
+    assert!(parser.items[0].requires.as_ref().unwrap().is_empty());
+    assert_eq!(subtree.goals().count(), 1);
+    subtree.validate().unwrap();
+
+    assert!(tree.subtree_from("nothing").is_err());
+}
+
+#[test]
+fn without_archived() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }]
+
+[[group]]
+name = "old-parser"
+archived = true
+requires = ["lexer"]
+items = [{ label = "Expressions" }]
+
+[[group.group]]
+name = "old-macros"
+items = [{ label = "Macros" }]
+
+[[group]]
+name = "parser"
+requires = ["old-parser"]
+items = [{ label = "Grammar" }, { label = "Recovery", archived = true }]
+
+[[goal]]
+name = "v1"
+archived = true
+requires = ["parser"]
+"#,
+    )
+    .unwrap();
+    // References to archived groups still resolve.
+    tree.validate().unwrap();
+
+    let current = tree.without_archived();
+    let groups: Vec<&str> = current.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["lexer", "parser"]);
+    let parser = current.group("parser").unwrap();
+    assert_eq!(parser.items.len(), 1);
+    // The parser now requires what the archived one did.
+    assert_eq!(requires(&current, "parser"), ["lexer"]);
+    assert_eq!(current.goals().count(), 0);
+    current.validate().unwrap();
+}
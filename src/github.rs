@@ -0,0 +1,183 @@
+//! Updates item statuses from the GitHub issues their `href`s link to.
+
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::{throw, throws};
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io;
+use std::process::Command;
+
+/// Environment variable that can be used to point at a specific `curl` executable.
+const CURL_ENV_VAR: &str = "SKILL_TREE_CURL";
+
+/// Environment variable holding the token to authenticate with, if any.
+const TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+
+/// The statuses given to items whose issue is closed, open and assigned to
+/// someone, or open and unassigned. They are those of the default palettes.
+const CLOSED_STATUS: &str = "Complete";
+const ASSIGNED_STATUS: &str = "Assigned";
+const UNASSIGNED_STATUS: &str = "Unassigned";
+
+/// A GitHub issue (or pull request), as linked to by an item.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IssueRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+impl IssueRef {
+    /// Recognizes links of the form
+    /// `https://github.com/{owner}/{repo}/issues/{number}`, or `/pull/` for
+    /// pull requests, ignoring any fragment or query.
+    pub fn from_href(href: &str) -> Option<IssueRef> {
+        let path = href
+            .strip_prefix("https://github.com/")
+            .or_else(|| href.strip_prefix("http://github.com/"))?;
+        let path = path.split(['#', '?']).next()?;
+        match path.trim_end_matches('/').split('/').collect::<Vec<_>>()[..] {
+            [owner, repo, "issues", number] | [owner, repo, "pull", number] => Some(IssueRef {
+                owner: owner.to_owned(),
+                repo: repo.to_owned(),
+                number: number.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Where an issue stands, as far as the status of its item is concerned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IssueState {
+    Open { assigned: bool },
+    Closed,
+}
+
+/// Looks up the state of issues; `GitHubApi` asks GitHub.
+pub trait IssueTracker {
+    fn issue_state(&mut self, issue: &IssueRef) -> Result<IssueState, Error>;
+}
+
+/// Asks the GitHub REST API about issues, by running `curl` (or the program
+/// named by `SKILL_TREE_CURL`).
+#[derive(Clone, Debug, Default)]
+pub struct GitHubApi {
+    /// Sent as a bearer token, for private repositories and a higher rate limit.
+    pub token: Option<String>,
+}
+
+impl GitHubApi {
+    /// Uses the token in `GITHUB_TOKEN`, if it is set.
+    pub fn from_env() -> GitHubApi {
+        GitHubApi {
+            token: std::env::var(TOKEN_ENV_VAR)
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+/// The parts of the API's issue object that matter here.
+#[derive(Deserialize)]
+struct ApiIssue {
+    state: String,
+    #[serde(default)]
+    assignees: Vec<serde_json::Value>,
+}
+
+impl IssueTracker for GitHubApi {
+    #[throws(Error)]
+    fn issue_state(&mut self, issue: &IssueRef) -> IssueState {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}",
+            issue.owner, issue.repo, issue.number
+        );
+        let curl = std::env::var_os(CURL_ENV_VAR).unwrap_or_else(|| OsString::from("curl"));
+        let mut command = Command::new(&curl);
+        command
+            .args(["--silent", "--show-error", "--fail", "--location"])
+            .args(["--header", "Accept: application/vnd.github+json"])
+            .args(["--header", "User-Agent: skill-tree"]);
+        if let Some(token) = &self.token {
+            command
+                .arg("--header")
+                .arg(format!("Authorization: Bearer {}", token));
+        }
+        let output = command.arg(&url).output().map_err(|err| {
+            let message = match err.kind() {
+                io::ErrorKind::NotFound => format!(
+                    "could not find `{}`; install curl or set `{}` to its location",
+                    curl.to_string_lossy(),
+                    CURL_ENV_VAR
+                ),
+                _ => err.to_string(),
+            };
+            Error::GitHubFailed {
+                url: url.clone(),
+                message,
+            }
+        })?;
+        if !output.status.success() {
+            throw!(Error::GitHubFailed {
+                url,
+                message: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            });
+        }
+
+        let api_issue: ApiIssue =
+            serde_json::from_slice(&output.stdout).map_err(|err| Error::GitHubFailed {
+                url: url.clone(),
+                message: err.to_string(),
+            })?;
+        if api_issue.state == "closed" {
+            IssueState::Closed
+        } else {
+            IssueState::Open {
+                assigned: !api_issue.assignees.is_empty(),
+            }
+        }
+    }
+}
+
+impl SkillTree {
+    /// Returns a copy of the tree in which every item that links to a GitHub
+    /// issue, through its `href` or the tree's `href_template`, has the
+    /// status that issue calls for: `Complete` if it is closed, otherwise
+    /// `Assigned` or `Unassigned` depending on whether anyone is assigned to
+    /// it. Other items are left alone, and each issue is only looked up once.
+    #[throws(Error)]
+    pub fn synced_with_issues(&self, tracker: &mut dyn IssueTracker) -> SkillTree {
+        let mut states: HashMap<IssueRef, IssueState> = HashMap::new();
+        let mut tree = self.clone();
+        for group in &mut tree.group {
+            for item in &mut group.items {
+                let href = self.item_href(&group.name, item);
+                let issue = match href.as_deref().and_then(IssueRef::from_href) {
+                    Some(issue) => issue,
+                    None => continue,
+                };
+                let state = match states.get(&issue) {
+                    Some(&state) => state,
+                    None => {
+                        let state = tracker.issue_state(&issue)?;
+                        states.insert(issue, state);
+                        state
+                    }
+                };
+                let status = match state {
+                    IssueState::Closed => CLOSED_STATUS,
+                    IssueState::Open { assigned: true } => ASSIGNED_STATUS,
+                    IssueState::Open { assigned: false } => UNASSIGNED_STATUS,
+                };
+                item.status = Some(status.to_owned());
+            }
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod test;
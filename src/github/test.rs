@@ -0,0 +1,95 @@
+use super::{IssueRef, IssueState, IssueTracker};
+use crate::{Error, SkillTree};
+use std::collections::HashMap;
+
+/// Answers from a fixed set of issues, counting the lookups.
+#[derive(Default)]
+struct FakeTracker {
+    states: HashMap<u64, IssueState>,
+    lookups: usize,
+}
+
+impl IssueTracker for FakeTracker {
+    fn issue_state(&mut self, issue: &IssueRef) -> Result<IssueState, Error> {
+        self.lookups += 1;
+        self.states
+            .get(&issue.number)
+            .copied()
+            .ok_or_else(|| Error::GitHubFailed {
+                url: issue.number.to_string(),
+                message: "not found".to_owned(),
+            })
+    }
+}
+
+#[test]
+fn parses_issue_links() {
+    assert_eq!(
+        IssueRef::from_href("https://github.com/rust-lang/rust/issues/1234#issuecomment-5"),
+        Some(IssueRef {
+            owner: "rust-lang".to_owned(),
+            repo: "rust".to_owned(),
+            number: 1234,
+        })
+    );
+    assert_eq!(
+        IssueRef::from_href("https://github.com/rust-lang/rust/pull/7/").map(|issue| issue.number),
+        Some(7)
+    );
+    assert_eq!(
+        IssueRef::from_href("https://github.com/rust-lang/rust"),
+        None
+    );
+    assert_eq!(
+        IssueRef::from_href("https://example.com/a/b/issues/1"),
+        None
+    );
+    assert_eq!(IssueRef::from_href("https://github.com/a/b/issues/x"), None);
+}
+
+#[test]
+fn updates_statuses() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "closed", href = "https://github.com/o/r/issues/1", status = "Blocked" },
+    { label = "assigned", href = "https://github.com/o/r/issues/2" },
+    { label = "unassigned", href = "https://github.com/o/r/issues/3", status = "Assigned" },
+    { label = "again", href = "https://github.com/o/r/issues/1" },
+    { label = "elsewhere", href = "https://example.com", status = "Blocked" },
+]
+"#,
+    )
+    .unwrap();
+    let mut tracker = FakeTracker::default();
+    tracker.states.insert(1, IssueState::Closed);
+    tracker
+        .states
+        .insert(2, IssueState::Open { assigned: true });
+    tracker
+        .states
+        .insert(3, IssueState::Open { assigned: false });
+
+    let synced = tree.synced_with_issues(&mut tracker).unwrap();
+    let statuses: Vec<Option<&str>> = synced.group[0]
+        .items()
+        .map(|item| item.status.as_deref())
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            Some("Complete"),
+            Some("Assigned"),
+            Some("Unassigned"),
+            Some("Complete"),
+            Some("Blocked"),
+        ]
+    );
+    assert_eq!(tracker.lookups, 3);
+    synced.validate().unwrap();
+
+    tracker.states.clear();
+    assert!(tree.synced_with_issues(&mut tracker).is_err());
+}
@@ -0,0 +1,375 @@
+//! The requirements between groups, items, and goals, as a graph.
+
+use crate::error::Error;
+use crate::tree::{
+    split_requirement, Goal, GoalIndex, Group, GroupIndex, Item, ItemIndex, Requirement, SkillTree,
+};
+use fehler::{throw, throws};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// A group, an item in a group, or a goal, by its position in the tree.
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub enum NodeRef {
+    Group(GroupIndex),
+    Item(GroupIndex, ItemIndex),
+    Goal(GoalIndex),
+}
+
+/// The requirements of a skill-tree, resolved into edges between its
+/// groups, items, and goals.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Graph {
+    /// Every group, item, and goal, in the order they are defined, with
+    /// each group's items just before it.
+    pub nodes: Vec<NodeRef>,
+    /// One edge per requirement, in the order they are written.
+    pub edges: Vec<Edge>,
+}
+
+/// A resolved requirement: `to` requires `from`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge {
+    pub from: NodeRef,
+    pub to: NodeRef,
+    pub label: Option<String>,
+    pub soft: bool,
+    /// The index in `to`'s `requires_any` of the set of alternatives this
+    /// edge is one of, if it is one.
+    pub alternatives: Option<usize>,
+}
+
+impl Graph {
+    /// The edges to what `node` requires.
+    pub fn requirements_of(&self, node: NodeRef) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == node)
+    }
+
+    /// The edges to what requires `node`.
+    pub fn dependents_of(&self, node: NodeRef) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == node)
+    }
+
+    /// What has to be done before `node`, in the order written: what it
+    /// requires, and what its group requires if it is an item, or what its
+    /// items require if it is a group. Soft requirements don't count.
+    pub fn prerequisites(&self, node: NodeRef) -> Vec<NodeRef> {
+        let mut prerequisites = vec![];
+        for edge in &self.edges {
+            let applies = match (node, edge.to) {
+                (NodeRef::Group(group), NodeRef::Item(of, _))
+                | (NodeRef::Item(group, _), NodeRef::Group(of)) => group == of,
+                (node, to) => node == to,
+            };
+            if applies && !edge.soft && !prerequisites.contains(&edge.from) {
+                prerequisites.push(edge.from);
+            }
+        }
+        prerequisites
+    }
+}
+
+impl SkillTree {
+    /// Resolves every requirement in the tree into an edge. Fails on the
+    /// first requirement that doesn't name a group, goal, or `group:port`.
+    #[throws(Error)]
+    pub fn graph(&self) -> Graph {
+        let mut edges = vec![];
+        for (to, alternatives, requirement) in self.requirements() {
+            let from = match self.node(&requirement.on) {
+                Some(from) => from,
+                // `node` only finds nothing where there is an error.
+                None => throw!(self
+                    .requirement_error(&requirement.on, || self.describe_node(to))
+                    .unwrap()),
+            };
+            edges.push(Edge {
+                from,
+                to,
+                label: requirement.label.clone(),
+                soft: requirement.soft,
+                alternatives,
+            });
+        }
+        Graph {
+            nodes: self.nodes(),
+            edges,
+        }
+    }
+
+    /// The group, item, or goal that `requirement` refers to, written as a
+    /// group or goal name, as `group:port`, or as `group/item label`.
+    pub fn node(&self, requirement: &str) -> Option<NodeRef> {
+        let requirement = self.resolve_requirement(requirement);
+        let (name, port) = split_requirement(&requirement);
+        if let Some(g) = self.groups().position(|group| group.name == name) {
+            match port {
+                None => Some(NodeRef::Group(GroupIndex(g))),
+                Some(port) => self.group[g]
+                    .item_with_port(port)
+                    .map(|item| NodeRef::Item(GroupIndex(g), item)),
+            }
+        } else {
+            self.goals()
+                .position(|goal| goal.name == name && port.is_none())
+                .map(|g| NodeRef::Goal(GoalIndex(g)))
+        }
+    }
+
+    /// What the group, goal, or `group:port` item `name` directly
+    /// requires, as with `Graph::prerequisites`.
+    #[throws(Error)]
+    pub fn prerequisites_of(&self, name: &str) -> Vec<NodeRef> {
+        let node = self.named_node(name)?;
+        self.graph()?.prerequisites(node)
+    }
+
+    /// Everything that has to be done before the group, goal, or
+    /// `group:port` item `name`: its prerequisites, theirs, and so on, in
+    /// the order they are defined.
+    #[throws(Error)]
+    pub fn all_transitive_requirements(&self, name: &str) -> Vec<NodeRef> {
+        let node = self.named_node(name)?;
+        let graph = self.graph()?;
+        let mut found = HashSet::new();
+        let mut pending = vec![node];
+        while let Some(node) = pending.pop() {
+            for prerequisite in graph.prerequisites(node) {
+                if found.insert(prerequisite) {
+                    pending.push(prerequisite);
+                }
+            }
+        }
+        graph
+            .nodes
+            .into_iter()
+            .filter(|node| found.contains(node))
+            .collect()
+    }
+
+    /// Like `node`, but fails if nothing is called `name`.
+    #[throws(Error)]
+    fn named_node(&self, name: &str) -> NodeRef {
+        match self.node(name) {
+            Some(node) => node,
+            None => throw!(self.unknown_node(name)),
+        }
+    }
+
+    /// The error for a `name` that `node` can't find: an unknown item if it
+    /// names one in a group or goal that exists, or else an unknown group
+    /// or goal.
+    fn unknown_node(&self, name: &str) -> Error {
+        let requirement = self.resolve_requirement(name);
+        let (owner, item) = match split_requirement(&requirement) {
+            (owner, Some(port)) => (owner, Some(port)),
+            (name, None) => match name.find('/') {
+                Some(index) if self.group(&name[..index]).is_some() => {
+                    (&name[..index], Some(&name[index + 1..]))
+                }
+                _ => (name, None),
+            },
+        };
+        match item {
+            Some(item) if self.group(owner).is_some() || self.is_goal(owner) => {
+                Error::UnknownItem {
+                    owner: owner.to_owned(),
+                    item: item.to_owned(),
+                }
+            }
+            _ => Error::UnknownNode {
+                name: owner.to_owned(),
+            },
+        }
+    }
+
+    pub fn group_at(&self, GroupIndex(g): GroupIndex) -> &Group {
+        &self.group[g]
+    }
+
+    pub fn item_at(&self, group: GroupIndex, ItemIndex(i): ItemIndex) -> &Item {
+        &self.group_at(group).items[i]
+    }
+
+    pub fn goal_at(&self, GoalIndex(g): GoalIndex) -> &Goal {
+        self.goals().nth(g).unwrap()
+    }
+
+    /// Lists every group, item, and goal so that each comes after what it
+    /// requires. Requirements of a group apply to its items too, and a
+    /// group comes after its items. Soft requirements don't count. Among
+    /// nodes that could go next, the first defined goes first, so a tree
+    /// that is already in order is listed as written (with each group's
+    /// items just before it). Fails if the requirements form a cycle.
+    #[throws(Error)]
+    pub fn topological_order(&self) -> Vec<NodeRef> {
+        let (nodes, before) = self.ordering();
+        // `after[i]` lists what requires node `i`.
+        let mut after = vec![vec![]; nodes.len()];
+        for (to, before) in before.iter().enumerate() {
+            for &from in before {
+                after[from].push(to);
+            }
+        }
+
+        let mut waiting_on: Vec<usize> = before.iter().map(Vec::len).collect();
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+            .filter(|&index| waiting_on[index] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(Reverse(index)) = ready.pop() {
+            order.push(nodes[index]);
+            for &next in &after[index] {
+                waiting_on[next] -= 1;
+                if waiting_on[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() < nodes.len() {
+            // Everything left is on a cycle or after one; walking back
+            // through what's left must come around to a node seen before.
+            let mut path = vec![];
+            let mut index = (0..nodes.len()).find(|&i| waiting_on[i] > 0).unwrap();
+            while !path.contains(&index) {
+                path.push(index);
+                index = *before[index].iter().find(|&&i| waiting_on[i] > 0).unwrap();
+            }
+            let start = path.iter().position(|&i| i == index).unwrap();
+            let cycle: Vec<NodeRef> = path[start..].iter().rev().map(|&i| nodes[i]).collect();
+            throw!(Error::CycleDetected {
+                cycle: self.cycle_names(&cycle)
+            });
+        }
+        order
+    }
+
+    /// Every node, as listed by `nodes`, along with the indices of what
+    /// each has to wait for: what it requires, what its group requires if
+    /// it is an item, and its items if it is a group. Soft requirements
+    /// don't count, nor do requirements that don't resolve.
+    pub(crate) fn ordering(&self) -> (Vec<NodeRef>, Vec<Vec<usize>>) {
+        let nodes = self.nodes();
+        let index_of: HashMap<NodeRef, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, &node)| (node, index))
+            .collect();
+
+        let mut before = vec![vec![]; nodes.len()];
+        let mut edge = |from: NodeRef, to: NodeRef| before[index_of[&to]].push(index_of[&from]);
+        for (to, _, requirement) in self.requirements() {
+            let from = match self.node(&requirement.on) {
+                Some(from) if !requirement.soft => from,
+                _ => continue,
+            };
+            edge(from, to);
+            if let NodeRef::Group(group) = to {
+                for i in 0..self.group_at(group).items.len() {
+                    edge(from, NodeRef::Item(group, ItemIndex(i)));
+                }
+            }
+        }
+        for (g, group) in self.groups().enumerate() {
+            for i in 0..group.items.len() {
+                edge(
+                    NodeRef::Item(GroupIndex(g), ItemIndex(i)),
+                    NodeRef::Group(GroupIndex(g)),
+                );
+            }
+        }
+        (nodes, before)
+    }
+
+    /// Names the groups and goals along `cycle`, listed in the direction
+    /// edges are drawn, for `Error::CycleDetected`. Items are named by
+    /// their group, once for a run of them, and the first name is
+    /// repeated at the end.
+    pub(crate) fn cycle_names(&self, cycle: &[NodeRef]) -> Vec<String> {
+        let mut names: Vec<String> = cycle
+            .iter()
+            .map(|&node| self.owner_name(node).to_owned())
+            .collect();
+        names.dedup();
+        if names.len() > 1 && names.first() == names.last() {
+            names.pop();
+        }
+        names.push(names[0].clone());
+        names
+    }
+
+    /// Every group, item, and goal, in the order they are defined, with
+    /// each group's items just before it.
+    fn nodes(&self) -> Vec<NodeRef> {
+        let mut nodes = vec![];
+        for (g, group) in self.groups().enumerate() {
+            nodes
+                .extend((0..group.items.len()).map(|i| NodeRef::Item(GroupIndex(g), ItemIndex(i))));
+            nodes.push(NodeRef::Group(GroupIndex(g)));
+        }
+        nodes.extend((0..self.goals().count()).map(|g| NodeRef::Goal(GoalIndex(g))));
+        nodes
+    }
+
+    /// Every requirement in the tree, with the node that has it and, for
+    /// alternatives, the index of their set in its `requires_any`.
+    pub(crate) fn requirements(&self) -> Vec<(NodeRef, Option<usize>, &Requirement)> {
+        let mut requirements = vec![];
+        for (g, group) in self.groups().enumerate() {
+            let node = NodeRef::Group(GroupIndex(g));
+            push_requirements(
+                &mut requirements,
+                node,
+                &group.requires,
+                &group.requires_any,
+            );
+            for (i, item) in group.items().enumerate() {
+                let node = NodeRef::Item(GroupIndex(g), ItemIndex(i));
+                push_requirements(&mut requirements, node, &item.requires, &item.requires_any);
+            }
+        }
+        for (g, goal) in self.goals().enumerate() {
+            let node = NodeRef::Goal(GoalIndex(g));
+            push_requirements(&mut requirements, node, &goal.requires, &goal.requires_any);
+        }
+        requirements
+    }
+
+    /// Describes `node` for error messages, the way `check` does.
+    fn describe_node(&self, node: NodeRef) -> String {
+        match node {
+            NodeRef::Group(group) => format!("group `{}`", self.group_at(group).name),
+            NodeRef::Item(group, item) => format!(
+                "item `{}` in group `{}`",
+                self.item_at(group, item).label,
+                self.group_at(group).name
+            ),
+            NodeRef::Goal(goal) => format!("goal `{}`", self.goal_at(goal).name),
+        }
+    }
+
+    /// The name of `node`, or of the group it is in.
+    fn owner_name(&self, node: NodeRef) -> &str {
+        match node {
+            NodeRef::Group(group) | NodeRef::Item(group, _) => &self.group_at(group).name,
+            NodeRef::Goal(goal) => &self.goal_at(goal).name,
+        }
+    }
+}
+
+fn push_requirements<'t>(
+    requirements: &mut Vec<(NodeRef, Option<usize>, &'t Requirement)>,
+    node: NodeRef,
+    requires: &'t Option<Vec<Requirement>>,
+    requires_any: &'t [Vec<Requirement>],
+) {
+    requirements.extend(requires.iter().flatten().map(|r| (node, None, r)));
+    for (index, alternatives) in requires_any.iter().enumerate() {
+        requirements.extend(alternatives.iter().map(|r| (node, Some(index), r)));
+    }
+}
+
+#[cfg(test)]
+mod test;
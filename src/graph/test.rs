@@ -0,0 +1,258 @@
+use crate::{Error, GoalIndex, GroupIndex, ItemIndex, NodeRef, SkillTree};
+
+#[test]
+fn orders_by_requirements() {
+    let tree = SkillTree::parse(
+        r#"
+[[goal]]
+name = "compile"
+requires = ["codegen"]
+
+[[group]]
+name = "codegen"
+requires = ["parser"]
+items = [{ label = "LLVM", port = "llvm", requires = ["lexer:tokens"] }]
+
+[[group]]
+name = "parser"
+items = [{ label = "Expressions" }]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }, { label = "Spans", requires = ["parser?"], port = "spans" }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.topological_order().unwrap(),
+        [
+            NodeRef::Item(GroupIndex(1), ItemIndex(0)),
+            NodeRef::Group(GroupIndex(1)),
+            NodeRef::Item(GroupIndex(2), ItemIndex(0)),
+            NodeRef::Item(GroupIndex(0), ItemIndex(0)),
+            NodeRef::Group(GroupIndex(0)),
+            NodeRef::Item(GroupIndex(2), ItemIndex(1)),
+            NodeRef::Group(GroupIndex(2)),
+            NodeRef::Goal(GoalIndex(0)),
+        ]
+    );
+}
+
+#[test]
+fn reports_cycles() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+requires = ["b"]
+items = [{ label = "x" }]
+
+[[group]]
+name = "b"
+items = [{ label = "y", port = "y", requires = ["a"] }]
+"#,
+    )
+    .unwrap();
+    match tree.topological_order() {
+        Err(Error::CycleDetected { cycle }) => assert_eq!(cycle, ["a", "b", "a"]),
+        other => panic!("expected a cycle, got {:?}", other),
+    }
+}
+
+#[test]
+fn resolves_requirements() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = [{ on = "lexer", label = "spans" }]
+items = [{ label = "Macros", port = "macros", requires_any = [["lexer:tokens", "done?"]] }]
+
+[[goal]]
+name = "done"
+requires = ["parser:macros"]
+"#,
+    )
+    .unwrap();
+    let graph = tree.graph().unwrap();
+    assert_eq!(graph.nodes.len(), 5);
+    let lexer = NodeRef::Group(GroupIndex(0));
+    let tokens = NodeRef::Item(GroupIndex(0), ItemIndex(0));
+    let parser = NodeRef::Group(GroupIndex(1));
+    let macros = NodeRef::Item(GroupIndex(1), ItemIndex(0));
+    let done = NodeRef::Goal(GoalIndex(0));
+    assert_eq!(tree.node("parser:macros"), Some(macros));
+    assert_eq!(tree.item_at(GroupIndex(1), ItemIndex(0)).label, "Macros");
+
+    let edges: Vec<(NodeRef, NodeRef, bool, Option<usize>)> = graph
+        .edges
+        .iter()
+        .map(|edge| (edge.from, edge.to, edge.soft, edge.alternatives))
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            (lexer, parser, false, None),
+            (tokens, macros, false, Some(0)),
+            (done, macros, true, Some(0)),
+            (macros, done, false, None),
+        ]
+    );
+    assert_eq!(graph.edges[0].label.as_deref(), Some("spans"));
+    assert_eq!(graph.requirements_of(macros).count(), 2);
+    assert_eq!(graph.dependents_of(macros).count(), 1);
+}
+
+#[test]
+fn resolves_item_labels() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "compiler/parser"
+items = [{ label = "Lexer rewrite" }, { label = "Error recovery", port = "recovery" }]
+
+[[goal]]
+name = "done"
+requires = ["compiler/parser/Lexer rewrite", "compiler/parser/Error recovery"]
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    assert_eq!(
+        tree.resolve_requirement("compiler/parser/Lexer rewrite"),
+        "compiler/parser:lexer_rewrite"
+    );
+    assert_eq!(
+        tree.resolve_requirement("compiler/parser/Error recovery"),
+        "compiler/parser:recovery"
+    );
+    assert_eq!(
+        tree.resolve_requirement("compiler/parser"),
+        "compiler/parser"
+    );
+    let froms: Vec<NodeRef> = tree
+        .graph()
+        .unwrap()
+        .edges
+        .iter()
+        .map(|edge| edge.from)
+        .collect();
+    assert_eq!(
+        froms,
+        [
+            NodeRef::Item(GroupIndex(0), ItemIndex(0)),
+            NodeRef::Item(GroupIndex(0), ItemIndex(1)),
+        ]
+    );
+
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""compiler/parser":_lexer_rewrite_out -> "done""#));
+
+    // A label that matches no item is an unknown reference, as before.
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "parser"
+items = [{ label = "Lexer rewrite" }]
+
+[[goal]]
+name = "done"
+requires = ["parser/Lexer rewrit"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "goal `done` requires unknown group or goal `parser/Lexer rewrit`; did you mean `parser/Lexer rewrite`?"
+    );
+}
+
+#[test]
+fn rejects_unknown_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[goal]]
+name = "done"
+requires = ["lexer:tokns"]
+"#,
+    )
+    .unwrap();
+    match tree.graph() {
+        Err(Error::UnknownPort {
+            owner, suggestion, ..
+        }) => {
+            assert_eq!(owner, "goal `done`");
+            assert_eq!(suggestion.as_deref(), Some("lexer:tokens"));
+        }
+        other => panic!("expected an unknown port, got {:?}", other),
+    }
+}
+
+const RELEASE: &str = r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }, { label = "Spans", port = "spans" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer:tokens"]
+items = [{ label = "Macros", port = "macros", requires = ["hygiene", "docs?"] }]
+
+[[group]]
+name = "hygiene"
+items = [{ label = "Spans" }]
+
+[[group]]
+name = "docs"
+items = [{ label = "Guide" }]
+
+[[goal]]
+name = "beta"
+requires = ["parser:macros"]
+"#;
+
+#[test]
+fn prerequisites() {
+    let tree = SkillTree::parse(RELEASE).unwrap();
+    let tokens = NodeRef::Item(GroupIndex(0), ItemIndex(0));
+    let macros = NodeRef::Item(GroupIndex(1), ItemIndex(0));
+    let hygiene = NodeRef::Group(GroupIndex(2));
+    assert_eq!(tree.prerequisites_of("beta").unwrap(), [macros]);
+    // An item needs what its group needs, and a group what its items need.
+    assert_eq!(
+        tree.prerequisites_of("parser:macros").unwrap(),
+        [tokens, hygiene]
+    );
+    assert_eq!(tree.prerequisites_of("parser").unwrap(), [tokens, hygiene]);
+    for (name, message) in &[
+        ("nothing", "no group or goal named `nothing`"),
+        ("nothing:port", "no group or goal named `nothing`"),
+        ("parser:missing", "`parser` has no item `missing`"),
+        ("parser/Missing", "`parser` has no item `Missing`"),
+        ("beta:port", "`beta` has no item `port`"),
+    ] {
+        let err = tree.prerequisites_of(name).unwrap_err();
+        assert_eq!(err.to_string(), *message);
+    }
+}
+
+#[test]
+fn transitive_requirements() {
+    let tree = SkillTree::parse(RELEASE).unwrap();
+    assert_eq!(
+        tree.all_transitive_requirements("beta").unwrap(),
+        [
+            NodeRef::Item(GroupIndex(0), ItemIndex(0)),
+            NodeRef::Item(GroupIndex(1), ItemIndex(0)),
+            NodeRef::Group(GroupIndex(2)),
+        ]
+    );
+}
@@ -1,173 +1,1586 @@
-use crate::tree::{Goal, Group, SkillTree, StatusStyle};
+use crate::diff::{DiffNode, TreeDiff};
+use crate::error::Error;
+use crate::filter::is_tagged;
+use crate::markdown::{self, Markup};
+use crate::theme::{Palette, Theme};
+use crate::tree::{
+    is_builtin_style, namespace_of, split_requirement, Collapse, Goal, Group, Item, Phase,
+    Requirement, SkillTree, StatusStyle,
+};
 use fehler::throws;
+use std::collections::{BTreeMap, HashSet};
 use std::io::Write;
 
+/// Rough width of a character, relative to the font size, and of the emoji
+/// column, in points. Used to turn `Group::width` into a line length.
+const CHAR_WIDTH: f64 = 0.55;
+const EMOJI_WIDTH: f64 = 24.0;
+
+/// Width of the progress bars under group headers without a `width`, and
+/// their height, in points.
+const PROGRESS_WIDTH: f64 = 100.0;
+const PROGRESS_HEIGHT: u32 = 4;
+
+/// Width and height of icons, in points.
+const ICON_SIZE: u32 = 20;
+
+/// Shown in the header cell that links a group's `details_href`.
+const DETAILS_EMOJI: &str = "📄";
+
+/// Colors for owners' names when `GraphvizOptions::color_owners` is set,
+/// handed out in the order the owners first appear.
+const OWNER_COLORS: &[&str] = &[
+    "#1b9e77", "#d95f02", "#7570b3", "#e7298a", "#66a61e", "#e6ab02", "#a6761d", "#666666",
+];
+
+/// Border and fill of overdue items and goals.
+const OVERDUE_COLOR: &str = "red";
+const OVERDUE_BGCOLOR: &str = "mistyrose";
+
+/// Attributes of the edges on a critical path.
+const CRITICAL_EDGE: &str = r#"color = "red", penwidth = 3"#;
+
+/// Color of edges from another namespace in a merged tree, and of their labels.
+const EXTERNAL_EDGE_COLOR: &str = "purple";
+
+/// Background of added items, group headers, and goals when drawing a diff.
+const DIFF_ADDED_COLOR: &str = "palegreen";
+/// Color of the text and borders of removed ones.
+const DIFF_REMOVED_COLOR: &str = "red";
+
+/// Shown before the label of a collapsed group.
+const COLLAPSED_EMOJI: &str = "✅";
+
+/// Name of the legend node; the leading underscore keeps it clear of the
+/// group and goal names people actually write.
+const LEGEND_NODE: &str = "_legend";
+
+/// Graph-wide settings for the generated graphviz. The defaults produce the
+/// same output as `write_graphviz`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphvizOptions {
+    /// Direction the graph flows in: `LR`, `RL`, `TB`, or `BT`.
+    pub rankdir: String,
+    pub fontname: Option<String>,
+    pub fontsize: f64,
+    /// Shape of nodes that don't set their own (groups and goals do).
+    pub node_shape: String,
+    /// Fill color of goal nodes; defaults to the theme's.
+    pub goal_color: Option<String>,
+    /// Overrides the theme set by the skill-tree itself.
+    pub theme: Option<Theme>,
+    /// How edges are drawn, e.g. `ortho`, `polyline`, or `curved`.
+    pub splines: Option<String>,
+    /// Minimum distance between ranks (columns, with `rankdir = "LR"`), in inches.
+    pub ranksep: Option<f64>,
+    /// Minimum distance between nodes in the same rank, in inches.
+    pub nodesep: Option<f64>,
+    /// Add a node explaining what each status's emoji and styling mean.
+    pub legend: bool,
+    /// Show each status's plain-text `marker` instead of its emoji, for
+    /// environments without an emoji font.
+    pub plain_text: bool,
+    /// Show how much of each group's estimated effort remains in its header.
+    pub effort: bool,
+    /// Give each owner's name its own color.
+    pub color_owners: bool,
+    /// Only draw the items with one of these tags (and the groups they are
+    /// in), as with `SkillTree::with_tags`. Everything is drawn if empty.
+    pub include_tags: Vec<String>,
+    /// Draw groups whose items are all completed as just their header,
+    /// unless the group sets `collapse` itself.
+    pub collapse_completed: bool,
+    /// Leave out items with any of these statuses, and groups left empty.
+    pub hide_statuses: Vec<String>,
+    /// Only draw items with one of these statuses, and the groups they are
+    /// in. Everything is drawn if empty.
+    pub only_statuses: Vec<String>,
+    /// List each group's items from highest to lowest priority, rather
+    /// than in the order they are written. Items without one come last.
+    pub sort_by_priority: bool,
+    /// The date, as `YYYY-MM-DD`, that items and goals due before it are
+    /// overdue as of. Nothing is highlighted as overdue if unset, so that
+    /// the output doesn't depend on when it is generated.
+    pub as_of: Option<String>,
+    /// Only draw this group, goal, or `group:port` item and what depends on
+    /// it, as with `SkillTree::subtree_from`.
+    pub dependents_of: Option<String>,
+    /// Highlight the edges on this goal's critical path, as found by
+    /// `SkillTree::critical_path`.
+    pub critical_path: Option<String>,
+    /// Highlight what changed since an older version of the tree: added
+    /// groups, items, and goals in green, removed ones struck out in red,
+    /// and items whose status changed with their old status.
+    pub diff: Option<TreeDiff>,
+    /// Open every link in a new tab (or window), so that clicking a node
+    /// in an SVG embedded in a page doesn't navigate away from it.
+    pub new_tab: bool,
+}
+
+impl Default for GraphvizOptions {
+    fn default() -> Self {
+        GraphvizOptions {
+            rankdir: "LR".to_owned(),
+            fontname: None,
+            fontsize: 16.0,
+            node_shape: "ellipse".to_owned(),
+            goal_color: None,
+            theme: None,
+            splines: None,
+            ranksep: None,
+            nodesep: None,
+            legend: false,
+            plain_text: false,
+            effort: false,
+            color_owners: false,
+            collapse_completed: false,
+            include_tags: vec![],
+            hide_statuses: vec![],
+            only_statuses: vec![],
+            sort_by_priority: false,
+            as_of: None,
+            dependents_of: None,
+            critical_path: None,
+            diff: None,
+            new_tab: false,
+        }
+    }
+}
+
 impl SkillTree {
     /// Writes graphviz representing this skill-tree to the given output.
-    #[throws(anyhow::Error)]
+    ///
+    /// The output is deterministic: the same tree always produces the same
+    /// bytes, with nodes and edges in the order they are defined and
+    /// statuses and other keyed styles sorted by name, so generated files
+    /// can be checked in and diffed.
+    #[throws(Error)]
     pub fn write_graphviz(&self, output: &mut dyn Write) {
-        write_graphviz(self, output)?
+        write_graphviz(self, &GraphvizOptions::default(), output)?
     }
 
     /// Generates a string containing graphviz content for this skill-tree.
-    #[throws(anyhow::Error)]
+    #[throws(Error)]
     pub fn to_graphviz(&self) -> String {
+        self.to_graphviz_with(&GraphvizOptions::default())?
+    }
+
+    /// Like `write_graphviz`, but with the given graph-wide settings.
+    #[throws(Error)]
+    pub fn write_graphviz_with(&self, options: &GraphvizOptions, output: &mut dyn Write) {
+        write_graphviz(self, options, output)?
+    }
+
+    /// Like `to_graphviz`, but with the given graph-wide settings.
+    #[throws(Error)]
+    pub fn to_graphviz_with(&self, options: &GraphvizOptions) -> String {
         let mut output = Vec::new();
-        write_graphviz(self, &mut output)?;
+        write_graphviz(self, options, &mut output)?;
         String::from_utf8(output)?
     }
 }
 
-#[throws(anyhow::Error)]
-fn write_graphviz(tree: &SkillTree, output: &mut dyn Write) {
+/// Parses and validates a skill-tree from TOML text and generates graphviz
+/// for it. This is the whole pipeline a web frontend needs to render a tree
+/// client-side; it touches neither the filesystem nor any other process,
+/// so it works on `wasm32-unknown-unknown`.
+#[throws(Error)]
+pub fn parse_and_render(toml: &str) -> String {
+    let tree = SkillTree::parse(toml)?;
+    tree.validate()?;
+    tree.to_graphviz()?
+}
+
+/// `parse_and_render`, exported to javascript as `parseAndRender`. Errors
+/// are thrown as their message.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen(js_name = parseAndRender)]
+pub fn parse_and_render_js(toml: &str) -> Result<String, wasm_bindgen::JsValue> {
+    parse_and_render(toml).map_err(|err| wasm_bindgen::JsValue::from_str(&err.to_string()))
+}
+
+#[throws(Error)]
+fn write_graphviz(tree: &SkillTree, options: &GraphvizOptions, output: &mut dyn Write) {
+    let resolved = tree.visible().with_resolved_requirements();
+    let tree = &resolved;
+    let with_removed;
+    let tree = match &options.diff {
+        Some(diff) if !diff.removed.is_empty() => {
+            with_removed = tree.with_removed(diff);
+            &with_removed
+        }
+        _ => tree,
+    };
+    let subtree;
+    let tree = match &options.dependents_of {
+        Some(name) => {
+            subtree = tree.subtree_from(name)?;
+            &subtree
+        }
+        None => tree,
+    };
+    let filtered;
+    let tree = if options.include_tags.is_empty()
+        && options.hide_statuses.is_empty()
+        && options.only_statuses.is_empty()
+    {
+        tree
+    } else {
+        filtered = tree.filtered(|group, item| {
+            (options.include_tags.is_empty() || is_tagged(group, item, &options.include_tags))
+                && !tree.has_status(group, item, &options.hide_statuses)
+                && (options.only_statuses.is_empty()
+                    || tree.has_status(group, item, &options.only_statuses))
+        });
+        &filtered
+    };
+    let collapsed: HashSet<&str> = tree
+        .groups()
+        .filter(|group| is_collapsed(tree, group, options))
+        .map(|group| group.name.as_str())
+        .collect();
+    let without_ports;
+    let tree = if collapsed.is_empty() {
+        tree
+    } else {
+        without_ports = tree.without_ports(&collapsed);
+        &without_ports
+    };
+    let palette = options.theme.or(tree.theme).unwrap_or_default().palette();
+
     writeln!(output, r#"digraph g {{"#)?;
-    writeln!(output, r#"graph [ rankdir = "LR" ];"#)?;
-    writeln!(output, r#"node [ fontsize="16", shape = "ellipse" ];"#)?;
-    writeln!(output, r#"edge [ ];"#)?;
+    writeln!(
+        output,
+        r#"graph [ rankdir = "{rankdir}"{splines}{ranksep}{nodesep}{bgcolor} ];"#,
+        rankdir = options.rankdir,
+        splines = attribute_setting("splines", &options.splines),
+        ranksep = attribute_setting("ranksep", &options.ranksep),
+        nodesep = attribute_setting("nodesep", &options.nodesep),
+        bgcolor = attribute_setting("bgcolor", &palette.background),
+    )?;
+    write_title(tree, &palette, output)?;
+    if !tree.graphviz_attrs.is_empty() {
+        let attrs: Vec<String> = tree
+            .graphviz_attrs
+            .iter()
+            .map(|(name, value)| format!(r#"{} = "{}""#, name, escape_quoted(value)))
+            .collect();
+        writeln!(output, r#"graph [ {} ];"#, attrs.join(", "))?;
+    }
+    writeln!(
+        output,
+        r#"node [ fontsize="{fontsize}", shape = "{shape}"{fontname}{fontcolor}{color} ];"#,
+        fontsize = options.fontsize,
+        shape = options.node_shape,
+        fontname = attribute_setting("fontname", &options.fontname),
+        fontcolor = attribute_setting("fontcolor", &palette.fontcolor),
+        color = attribute_setting("color", &palette.border_color),
+    )?;
+    match palette.edge_color {
+        Some(color) => writeln!(output, r#"edge [ color = "{}" ];"#, color)?,
+        None => writeln!(output, r#"edge [ ];"#)?,
+    }
 
-    for group in tree.groups() {
-        writeln!(output, r#""{}" ["#, group.name)?;
-        write_group_label(tree, group, output)?;
-        writeln!(output, r#"  shape = "none""#)?;
-        writeln!(output, r#"  margin = 0"#)?;
-        writeln!(output, r#"]"#)?;
+    let phases = phases(tree);
+    let unphased: Vec<&Group> = top_level_groups(tree)
+        .filter(|group| {
+            !phases
+                .iter()
+                .any(|(_, groups)| groups.iter().any(|g| std::ptr::eq(*g, *group)))
+        })
+        .collect();
+    write_groups(tree, &unphased, None, options, &palette, output)?;
+    for (phase, groups) in &phases {
+        write_phase(tree, phase, groups, options, &palette, output)?;
     }
 
-    for goal in tree.goals() {
-        writeln!(output, r#""{}" ["#, goal.name)?;
-        write_goal_label(goal, output)?;
-        writeln!(output, r#"  shape = "note""#)?;
-        writeln!(output, r#"  margin = 0"#)?;
-        writeln!(output, r#"  style = "filled""#)?;
-        writeln!(output, r#"  fillcolor = "darkgoldenrod""#)?;
-        writeln!(output, r#"]"#)?;
+    for goal in tree.goals().filter(|goal| goal.milestone.is_none()) {
+        write_goal_node(tree, goal, options, &palette, output)?;
     }
 
+    let mut milestones: Vec<&str> = vec![];
+    for milestone in tree.goals().filter_map(|goal| goal.milestone.as_deref()) {
+        if !milestones.contains(&milestone) {
+            milestones.push(milestone);
+        }
+    }
+    for milestone in milestones {
+        write_milestone(tree, milestone, options, &palette, output)?;
+    }
+
+    if options.legend {
+        write_legend(tree, options, &palette, output)?;
+    }
+
+    write_ranks(tree, output)?;
+    write_phase_order(tree, &phases, output)?;
+
+    let critical_path: HashSet<(&str, &str)> = match &options.critical_path {
+        Some(goal) => tree
+            .critical_path(goal)?
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect(),
+        None => HashSet::new(),
+    };
+    let is_critical = |requirement: &Requirement, owner: &str| {
+        !requirement.soft && critical_path.contains(&(split_requirement(&requirement.on).0, owner))
+    };
+
+    let mut junctions = 0;
     for group in tree.groups() {
-        if let Some(requires) = &group.requires {
-            for requirement in requires {
-                writeln!(
-                    output,
-                    r#"{} -> {};"#,
-                    tree.port_name(requirement, "out"),
-                    tree.port_name(&group.name, "in"),
-                )?;
+        write_requirements(
+            tree,
+            &group.requires,
+            &group.requires_any,
+            &group.name,
+            &|requirement| is_critical(requirement, &group.name),
+            &mut junctions,
+            output,
+        )?;
+
+        for (item, port) in group.items().zip(group.ports()) {
+            if !item.has_requirements() {
+                continue;
             }
+            let target = format!("{}:{}", group.name, port);
+            write_requirements(
+                tree,
+                &item.requires,
+                &item.requires_any,
+                &target,
+                &|requirement| is_critical(requirement, &group.name),
+                &mut junctions,
+                output,
+            )?;
         }
+    }
+
+    for goal in tree.goals() {
+        write_requirements(
+            tree,
+            &goal.requires,
+            &goal.requires_any,
+            &goal.name,
+            &|requirement| is_critical(requirement, &goal.name),
+            &mut junctions,
+            output,
+        )?;
+    }
+
+    writeln!(output, r#"}}"#)?;
+}
+
+#[throws(Error)]
+fn write_group_node(
+    tree: &SkillTree,
+    group: &Group,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    writeln!(output, "{} [", node_id(&group.name))?;
+    if is_collapsed(tree, group, options) {
+        write_collapsed_group_label(tree, group, options, palette, output)?;
+    } else {
+        write_group_label(tree, group, options, palette, output)?;
+    }
+    writeln!(output, r#"  shape = "none""#)?;
+    writeln!(output, r#"  margin = 0"#)?;
+    write_node_attrs(&group.graphviz_attrs, output)?;
+    writeln!(output, r#"]"#)?;
+}
 
-        for item in group.items() {
-            if let Some(requires) = &item.requires {
-                for requirement in requires {
-                    let port = item
-                        .port
-                        .as_ref()
-                        .ok_or_else(|| anyhow::format_err!("missing port for: {}", item.label))?;
-
-                    writeln!(
-                        output,
-                        r#"{} -> "{}":_{}_in;"#,
-                        tree.port_name(requirement, "out"),
-                        group.name,
-                        port,
-                    )?;
+/// Whether `group` is drawn as just its header, because all of its items
+/// are completed.
+fn is_collapsed(tree: &SkillTree, group: &Group, options: &GraphvizOptions) -> bool {
+    let collapse = group.collapse.unwrap_or(if options.collapse_completed {
+        Collapse::WhenComplete
+    } else {
+        Collapse::Never
+    });
+    let (completed, total) = tree.group_progress(group);
+    collapse == Collapse::WhenComplete && total > 0 && completed == total
+}
+
+/// Writes a one-cell table with the group's label, for a collapsed group.
+#[throws(Error)]
+fn write_collapsed_group_label(
+    tree: &SkillTree,
+    group: &Group,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let label = group.label.as_ref().unwrap_or(&group.name);
+    let Markup { html: label, href } = label_markup(label, tree.wrap_width);
+    let href = group
+        .href
+        .clone()
+        .or(href)
+        .map(|href| tree.resolve_href(&href));
+    let href = link_attributes(&href, options);
+    let header_color = group
+        .header_color
+        .as_deref()
+        .unwrap_or(palette.header_color);
+    let marker = if options.plain_text {
+        "[x]"
+    } else {
+        COLLAPSED_EMOJI
+    };
+    let (_, total) = tree.group_progress(group);
+    let label = format!(
+        "{} {} ({}/{}){}",
+        marker,
+        label,
+        total,
+        total,
+        owner_suffix(tree, &group.owner, options)
+    );
+    let label = match palette.header_fontcolor {
+        Some(color) => format!(r#"<font color="{}">{}</font>"#, color, label),
+        None => label,
+    };
+    let label = with_icon(&group.icon, &label);
+    let tooltip = group
+        .tooltip
+        .as_ref()
+        .map(|tooltip| htmlescape::encode_minimal(tooltip));
+    let tooltip = attribute_str("tooltip", &tooltip, "");
+
+    let border = attribute_str("color", &palette.border_color, "");
+    writeln!(output, r#"  label = <<table{}>"#, border)?;
+    writeln!(
+        output,
+        r#"    <tr><td bgcolor="{}" port="all"{}{}>{}</td></tr>"#,
+        header_color, href, tooltip, label
+    )?;
+    writeln!(output, r#"  </table>>"#)?;
+}
+
+/// The groups that aren't sub-groups of another. Sub-groups are written
+/// along with their parents.
+fn top_level_groups(tree: &SkillTree) -> impl Iterator<Item = &Group> {
+    tree.groups()
+        .filter(move |group| tree.parent_group(group).is_none())
+}
+
+/// The phases that have groups in them, in order, each with its top-level
+/// groups. A phase listed twice only gets the groups once.
+fn phases(tree: &SkillTree) -> Vec<(&Phase, Vec<&Group>)> {
+    let mut phases: Vec<(&Phase, Vec<&Group>)> = vec![];
+    for phase in &tree.phase {
+        if phases.iter().any(|(p, _)| p.name == phase.name) {
+            continue;
+        }
+        let groups: Vec<&Group> = top_level_groups(tree)
+            .filter(|group| group.phase.as_ref() == Some(&phase.name))
+            .collect();
+        if !groups.is_empty() {
+            phases.push((phase, groups));
+        }
+    }
+    phases
+}
+
+/// Writes `groups`, which are top-level groups in `phase` (if any), with
+/// those in an area inside a cluster for it.
+#[throws(Error)]
+fn write_groups(
+    tree: &SkillTree,
+    groups: &[&Group],
+    phase: Option<&str>,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    for group in groups.iter().filter(|group| group.area.is_none()) {
+        write_group_and_subgroups(tree, group, options, palette, &mut vec![], output)?;
+    }
+
+    let mut areas: Vec<&str> = vec![];
+    for area in groups.iter().filter_map(|group| group.area.as_deref()) {
+        if !areas.contains(&area) {
+            areas.push(area);
+        }
+    }
+    for area in areas {
+        let in_area: Vec<&Group> = groups
+            .iter()
+            .copied()
+            .filter(|group| group.area.as_deref() == Some(area))
+            .collect();
+        write_area(tree, area, phase, &in_area, options, palette, output)?;
+    }
+}
+
+/// Writes the groups of a phase inside a cluster labeled with it.
+#[throws(Error)]
+fn write_phase(
+    tree: &SkillTree,
+    phase: &Phase,
+    groups: &[&Group],
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let label = phase.label.as_deref().unwrap_or(&phase.name);
+    writeln!(
+        output,
+        r#"subgraph "cluster_phase_{}" {{"#,
+        escape_quoted(&phase.name)
+    )?;
+    writeln!(output, r#"  label = "{}""#, escape_quoted(label))?;
+    if let Some(bgcolor) = &phase.bgcolor {
+        writeln!(output, r#"  style = "filled""#)?;
+        writeln!(output, r#"  fillcolor = "{}""#, bgcolor)?;
+    }
+    if let Some(color) = palette.border_color {
+        writeln!(output, r#"  color = "{}""#, color)?;
+    }
+    if let Some(fontcolor) = palette.fontcolor {
+        writeln!(output, r#"  fontcolor = "{}""#, fontcolor)?;
+    }
+    write_groups(tree, groups, Some(&phase.name), options, palette, output)?;
+    writeln!(output, r#"}}"#)?;
+}
+
+/// Keeps the phases in order, with an invisible edge from every group in
+/// each phase, sub-groups included, to every group in the next.
+#[throws(Error)]
+fn write_phase_order(tree: &SkillTree, phases: &[(&Phase, Vec<&Group>)], output: &mut dyn Write) {
+    fn with_subgroups<'t>(tree: &'t SkillTree, groups: &[&'t Group]) -> Vec<&'t str> {
+        let mut all: Vec<&str> = groups.iter().map(|group| group.name.as_str()).collect();
+        let mut next = 0;
+        while next < all.len() {
+            for subgroup in tree.subgroups(all[next]) {
+                if !all.contains(&subgroup.name.as_str()) {
+                    all.push(&subgroup.name);
                 }
             }
+            next += 1;
         }
+        all
     }
-
-    for goal in tree.goals() {
-        if let Some(requires) = &goal.requires {
-            for requirement in requires {
+    for pair in phases.windows(2) {
+        for from in with_subgroups(tree, &pair[0].1) {
+            for to in with_subgroups(tree, &pair[1].1) {
                 writeln!(
                     output,
-                    r#"{} -> {};"#,
-                    tree.port_name(requirement, "out"),
-                    tree.port_name(&goal.name, "in"),
+                    r#"{} -> {} [ style = "invis" ];"#,
+                    node_id(from),
+                    node_id(to)
                 )?;
             }
         }
     }
+}
 
+/// Writes `groups`, all in `area`, inside a cluster, which graphviz draws
+/// as a labeled box around them. Each phase gets its own cluster for the
+/// area.
+#[throws(Error)]
+fn write_area(
+    tree: &SkillTree,
+    area: &str,
+    phase: Option<&str>,
+    groups: &[&Group],
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let style = tree.area.get(area).cloned().unwrap_or_default();
+    let label = style.label.as_deref().unwrap_or(area);
+    let bgcolor = style.bgcolor.as_deref().unwrap_or(palette.area_color);
+
+    let cluster = match phase {
+        Some(phase) => format!("{}/{}", phase, area),
+        None => area.to_owned(),
+    };
+    writeln!(
+        output,
+        r#"subgraph "cluster_{}" {{"#,
+        escape_quoted(&cluster)
+    )?;
+    writeln!(output, r#"  label = "{}""#, escape_quoted(label))?;
+    writeln!(output, r#"  style = "filled""#)?;
+    writeln!(output, r#"  fillcolor = "{}""#, bgcolor)?;
+    if let Some(color) = palette.border_color {
+        writeln!(output, r#"  color = "{}""#, color)?;
+    }
+    if let Some(fontcolor) = palette.fontcolor {
+        writeln!(output, r#"  fontcolor = "{}""#, fontcolor)?;
+    }
+    for group in groups {
+        write_group_and_subgroups(tree, group, options, palette, &mut vec![], output)?;
+    }
     writeln!(output, r#"}}"#)?;
 }
 
+/// Writes the node for `group` and, if it has sub-groups, theirs, in a
+/// cluster around them all. `ancestors` are the groups whose clusters this
+/// one is in, which a malformed tree could otherwise list again as its
+/// sub-groups.
+#[throws(Error)]
+fn write_group_and_subgroups<'t>(
+    tree: &'t SkillTree,
+    group: &'t Group,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    ancestors: &mut Vec<&'t str>,
+    output: &mut dyn Write,
+) {
+    ancestors.push(&group.name);
+    let subgroups: Vec<&Group> = tree
+        .subgroups(&group.name)
+        .filter(|subgroup| !ancestors.contains(&subgroup.name.as_str()))
+        .collect();
+    if subgroups.is_empty() {
+        write_group_node(tree, group, options, palette, output)?;
+    } else {
+        writeln!(
+            output,
+            r#"subgraph "cluster_group_{}" {{"#,
+            escape_quoted(&group.name)
+        )?;
+        writeln!(output, r#"  label = """#)?;
+        writeln!(output, r#"  style = "dashed""#)?;
+        if let Some(color) = palette.border_color {
+            writeln!(output, r#"  color = "{}""#, color)?;
+        }
+        write_group_node(tree, group, options, palette, output)?;
+        for subgroup in subgroups {
+            write_group_and_subgroups(tree, subgroup, options, palette, ancestors, output)?;
+        }
+        writeln!(output, r#"}}"#)?;
+    }
+    ancestors.pop();
+}
+
+#[throws(Error)]
+fn write_goal_node(
+    tree: &SkillTree,
+    goal: &Goal,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let style = status_style(tree, goal.status.as_ref(), palette);
+    let change = diff_change(options, &DiffNode::Goal(goal.name.clone()));
+    let removed = matches!(change, Some(Change::Removed));
+    let goal_color = match change {
+        Some(Change::Added) => DIFF_ADDED_COLOR,
+        _ => goal
+            .fillcolor
+            .as_deref()
+            .or(options.goal_color.as_deref())
+            .unwrap_or(palette.goal_color),
+    };
+    let fontcolor = if removed {
+        Some(DIFF_REMOVED_COLOR)
+    } else {
+        style.fontcolor.as_deref().or(palette.goal_fontcolor)
+    };
+    let overdue = is_overdue(tree, &goal.due, goal.status.as_ref(), options);
+    writeln!(output, "{} [", node_id(&goal.name))?;
+    if let Some(description) = &goal.description {
+        writeln!(output, r#"  tooltip = "{}""#, escape_quoted(description))?;
+    }
+    if let Some(href) = &goal.href {
+        let href = tree.resolve_href(href);
+        writeln!(output, r#"  href = "{}""#, escape_quoted(&href))?;
+        if goal.new_tab || options.new_tab {
+            writeln!(output, r#"  target = "_blank""#)?;
+        }
+    }
+    if goal.items.is_empty() {
+        write_goal_label(tree, goal, &style, options, output)?;
+        let shape = goal.shape.as_deref().unwrap_or("note");
+        writeln!(output, r#"  shape = "{}""#, shape)?;
+        writeln!(output, r#"  margin = 0"#)?;
+        let style = if removed { "filled,dashed" } else { "filled" };
+        writeln!(output, r#"  style = "{}""#, style)?;
+        writeln!(output, r#"  fillcolor = "{}""#, goal_color)?;
+        if let Some(fontcolor) = fontcolor {
+            writeln!(output, r#"  fontcolor = "{}""#, fontcolor)?;
+        }
+        if removed {
+            writeln!(output, r#"  color = "{}""#, DIFF_REMOVED_COLOR)?;
+        } else if overdue {
+            writeln!(output, r#"  color = "{}""#, OVERDUE_COLOR)?;
+            writeln!(output, r#"  penwidth = 3"#)?;
+        }
+    } else {
+        let header = goal_header(tree, goal, &style, options);
+        let header = match fontcolor {
+            Some(color) => format!(r#"<font color="{}">{}</font>"#, color, header),
+            None => header,
+        };
+        let border = if overdue {
+            format!(r#" color="{}" border="3""#, OVERDUE_COLOR)
+        } else {
+            attribute_str("color", &palette.border_color, "")
+        };
+        writeln!(output, r#"  label = <<table{}>"#, border)?;
+        writeln!(
+            output,
+            r#"    <tr><td bgcolor="{}" colspan="2">{}</td></tr>"#,
+            goal_color, header
+        )?;
+        write_item_rows(
+            tree,
+            ItemsOf::Goal(goal),
+            tree.wrap_width,
+            options,
+            palette,
+            output,
+        )?;
+        writeln!(output, r#"  </table>>"#)?;
+        writeln!(output, r#"  shape = "none""#)?;
+        writeln!(output, r#"  margin = 0"#)?;
+    }
+    write_node_attrs(&goal.graphviz_attrs, output)?;
+    writeln!(output, r#"]"#)?;
+}
+
+/// Writes the goals in `milestone` inside a cluster labeled with the
+/// milestone's name and target date.
+#[throws(Error)]
+fn write_milestone(
+    tree: &SkillTree,
+    milestone: &str,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let style = tree.milestone.get(milestone).cloned().unwrap_or_default();
+    let mut label = escape_quoted(style.label.as_deref().unwrap_or(milestone));
+    if let Some(date) = &style.date {
+        label = format!(r"{}\n{}", label, escape_quoted(date));
+    }
+    let bgcolor = style.bgcolor.as_deref().unwrap_or(palette.area_color);
+
+    writeln!(
+        output,
+        r#"subgraph "cluster_milestone_{}" {{"#,
+        escape_quoted(milestone)
+    )?;
+    writeln!(output, r#"  label = "{}""#, label)?;
+    writeln!(output, r#"  style = "filled""#)?;
+    writeln!(output, r#"  fillcolor = "{}""#, bgcolor)?;
+    if let Some(color) = palette.border_color {
+        writeln!(output, r#"  color = "{}""#, color)?;
+    }
+    if let Some(fontcolor) = palette.fontcolor {
+        writeln!(output, r#"  fontcolor = "{}""#, fontcolor)?;
+    }
+    for goal in tree
+        .goals()
+        .filter(|goal| goal.milestone.as_deref() == Some(milestone))
+    {
+        write_goal_node(tree, goal, options, palette, output)?;
+    }
+    writeln!(output, r#"}}"#)?;
+}
+
+/// Lines up the groups and goals that share a rank, and keeps the ranks in
+/// order with an invisible edge from each rank to the next.
+#[throws(Error)]
+fn write_ranks(tree: &SkillTree, output: &mut dyn Write) {
+    let mut ranks: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+    for group in tree.groups() {
+        if let Some(rank) = group.rank {
+            ranks.entry(rank).or_default().push(&group.name);
+        }
+    }
+    for goal in tree.goals() {
+        if let Some(rank) = goal.rank {
+            ranks.entry(rank).or_default().push(&goal.name);
+        }
+    }
+
+    for names in ranks.values() {
+        let names: Vec<String> = names
+            .iter()
+            .map(|name| format!("{};", node_id(name)))
+            .collect();
+        writeln!(output, r#"{{ rank = same; {} }}"#, names.join(" "))?;
+    }
+
+    let firsts: Vec<&str> = ranks.values().map(|names| names[0]).collect();
+    for pair in firsts.windows(2) {
+        writeln!(
+            output,
+            r#"{} -> {} [ style = "invis" ];"#,
+            node_id(pair[0]),
+            node_id(pair[1])
+        )?;
+    }
+}
+
+/// Writes the edges into `target`, a group, goal, or `group:port` item,
+/// for its requirements. Each set of alternatives in `requires_any` gets a
+/// small "or" junction node, with an edge into it from every alternative
+/// and one out of it to `target`. `junctions` counts the junction nodes so
+/// far, to keep their names unique. Edges for which `is_critical` holds are
+/// highlighted, and edges from another namespace are labeled with it.
+#[throws(Error)]
+fn write_requirements(
+    tree: &SkillTree,
+    requires: &Option<Vec<Requirement>>,
+    requires_any: &[Vec<Requirement>],
+    target: &str,
+    is_critical: &dyn Fn(&Requirement) -> bool,
+    junctions: &mut usize,
+    output: &mut dyn Write,
+) {
+    let namespace = namespace_of(target);
+    let external = |requirement: &Requirement| match namespace_of(&requirement.on) {
+        Some(from) if Some(from) != namespace => external_edge(from),
+        _ => String::new(),
+    };
+    let highlight = |critical: bool| if critical { CRITICAL_EDGE } else { "" };
+    let target = tree.port_name(target, "in");
+    for requirement in requires.iter().flatten() {
+        writeln!(
+            output,
+            r#"{} -> {}{};"#,
+            tree.port_name(&requirement.on, "out"),
+            target,
+            edge_attributes(
+                tree,
+                requirement,
+                &[&external(requirement), highlight(is_critical(requirement))],
+            ),
+        )?;
+    }
+
+    for alternatives in requires_any {
+        let junction = format!(r#""_any{}""#, junctions);
+        *junctions += 1;
+        writeln!(
+            output,
+            r#"{} [ shape = "circle", label = "or", fontsize = 10, width = 0.3, fixedsize = true ];"#,
+            junction,
+        )?;
+        for requirement in alternatives {
+            writeln!(
+                output,
+                r#"{} -> {}{};"#,
+                tree.port_name(&requirement.on, "out"),
+                junction,
+                edge_attributes(
+                    tree,
+                    requirement,
+                    &[
+                        r#"arrowhead = "none""#,
+                        &external(requirement),
+                        highlight(is_critical(requirement)),
+                    ],
+                ),
+            )?;
+        }
+        let critical = alternatives.iter().any(is_critical);
+        let attributes = if critical {
+            format!(" [ {} ]", CRITICAL_EDGE)
+        } else {
+            String::new()
+        };
+        writeln!(output, r#"{} -> {}{};"#, junction, target, attributes)?;
+    }
+}
+
+/// Edge attributes for a requirement on something in another namespace:
+/// drawn in `EXTERNAL_EDGE_COLOR` and labeled with the namespace at its tail.
+fn external_edge(namespace: &str) -> String {
+    format!(
+        r#"color = "{}", fontcolor = "{}", taillabel = "{}""#,
+        EXTERNAL_EDGE_COLOR,
+        EXTERNAL_EDGE_COLOR,
+        escape_quoted(namespace)
+    )
+}
+
+/// Writes the tree's title, description, and revision, if it has any, as a
+/// label at the top of the graph.
+#[throws(Error)]
+fn write_title(tree: &SkillTree, palette: &Palette, output: &mut dyn Write) {
+    let mut lines = vec![];
+    if let Some(title) = &tree.title {
+        lines.push(format!("<b>{}</b>", escape(title)));
+    }
+    if let Some(description) = &tree.description {
+        lines.push(escape(description));
+    }
+    if let Some(updated) = &tree.updated {
+        lines.push(format!("<i>Updated {}</i>", escape(updated)));
+    }
+    if lines.is_empty() {
+        return;
+    }
+
+    writeln!(
+        output,
+        r#"graph [ label = <{label}>, labelloc = "t"{fontcolor} ];"#,
+        label = lines.join("<br/>"),
+        fontcolor = attribute_setting("fontcolor", &palette.fontcolor),
+    )?;
+}
 
 fn escape(s: &str) -> String {
     htmlescape::encode_minimal(s).replace('\n', "<br/>")
 }
 
-#[throws(anyhow::Error)]
-fn write_goal_label(goal: &Goal, output: &mut dyn Write) {
+/// Puts `icon`, if there is one, before `content`. A cell holding an image
+/// can't hold anything else, so the two go in a table of their own.
+fn with_icon(icon: &Option<String>, content: &str) -> String {
+    match icon {
+        None => content.to_owned(),
+        Some(icon) => format!(
+            r#"<table border="0" cellspacing="0" cellpadding="0"><tr><td fixedsize="true" width="{size}" height="{size}"><img src="{icon}" scale="true"/></td><td>{content}</td></tr></table>"#,
+            size = ICON_SIZE,
+            icon = htmlescape::encode_minimal(icon),
+            content = content,
+        ),
+    }
+}
+
+/// Translates the Markdown in a label and wraps it at `max_chars`, if set.
+fn label_markup(label: &str, max_chars: Option<usize>) -> Markup {
+    markup_with_wrap(markdown::to_markup(label, true), max_chars)
+}
+
+/// Like `label_markup`, for an item's label, whose inline HTML is escaped
+/// unless the item sets `label_html`.
+fn item_label_markup(item: &Item, max_chars: Option<usize>) -> Markup {
+    markup_with_wrap(markdown::to_markup(&item.label, item.label_html), max_chars)
+}
+
+fn markup_with_wrap(mut markup: Markup, max_chars: Option<usize>) -> Markup {
+    if let Some(max_chars) = max_chars {
+        markup.html = wrap(&markup.html, max_chars);
+    }
+    markup
+}
+
+/// Word-wraps HTML-like label text, putting a `<br/>` in place of a space
+/// wherever a line would otherwise run past `max_chars` visible characters.
+/// Spaces inside tags aren't word breaks, and tags count as no characters
+/// and entities as one. Words longer than `max_chars` are left whole.
+fn wrap(text: &str, max_chars: usize) -> String {
+    let mut output = String::new();
+    let mut line_len = 0;
+    for (index, word) in words(text).into_iter().enumerate() {
+        let len = visible_len(word);
+        if index > 0 {
+            if line_len > 0 && line_len + 1 + len > max_chars {
+                output.push_str("<br/>");
+                line_len = 0;
+            } else {
+                output.push(' ');
+                line_len += 1;
+            }
+        }
+        output.push_str(word);
+        line_len = match word.rfind("<br") {
+            // Line breaks already in the label start a new line.
+            Some(br) => visible_len(&word[br..]),
+            None => line_len + len,
+        };
+    }
+    output
+}
+
+/// Splits HTML-like text at the spaces that aren't inside a tag.
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut in_tag = false;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            ' ' if !in_tag => {
+                words.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    words.push(&text[start..]);
+    words
+}
+
+/// The number of characters HTML-like text displays as.
+fn visible_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut in_tag = false;
+    let mut in_entity = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if in_tag => {}
+            '&' => {
+                in_entity = true;
+                len += 1;
+            }
+            ';' if in_entity => in_entity = false,
+            _ if in_entity => {}
+            _ => len += 1,
+        }
+    }
+    len
+}
+
+/// The quoted DOT ID of the group or goal `name`. Names made only of ASCII
+/// letters, digits, spaces, and `_-./` are used as they are. Any other
+/// character is written as `~` and its code point in hex, then `;`, which
+/// keeps every ID distinct and free of anything that needs escaping. What
+/// is shown is the label, which is unaffected.
+fn node_id(name: &str) -> String {
+    format!(r#""{}""#, sanitize_id(name))
+}
+
+/// The name of the port of an item whose port is `port`, for edges into
+/// it (`mode` is `in`) or out of it (`out`), as written in the table cell.
+/// Escaped like `node_id`.
+fn port_id(port: &str, mode: &str) -> String {
+    format!("_{}_{}", sanitize_id(port), mode)
+}
+
+/// Escapes the characters `node_id` doesn't allow.
+fn sanitize_id(name: &str) -> String {
+    let mut id = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || " _-./".contains(c) {
+            id.push(c);
+        } else {
+            id.push_str(&format!("~{:x};", c as u32));
+        }
+    }
+    id
+}
+
+/// Escapes `s` for use inside a double-quoted graphviz string.
+fn escape_quoted(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Writes user-supplied attributes into a node definition. They come after
+/// the generated ones, so they win.
+#[throws(Error)]
+fn write_node_attrs(attrs: &BTreeMap<String, String>, output: &mut dyn Write) {
+    for (name, value) in attrs {
+        writeln!(output, r#"  {} = "{}""#, name, escape_quoted(value))?;
+    }
+}
+
+#[throws(Error)]
+fn write_goal_label(
+    tree: &SkillTree,
+    goal: &Goal,
+    style: &StatusStyle,
+    options: &GraphvizOptions,
+    output: &mut dyn Write,
+) {
+    let header = goal_header(tree, goal, style, options);
+    writeln!(output, r#"  label = <{}>"#, header)?;
+}
+
+/// The goal's label, styled by its status and preceded by the status's emoji.
+fn goal_header(
+    tree: &SkillTree,
+    goal: &Goal,
+    style: &StatusStyle,
+    options: &GraphvizOptions,
+) -> String {
     let label = goal.label.as_ref().unwrap_or(&goal.name);
-    let label = escape(label);
-    writeln!(output, r#"  label = "{label}""#, label = label)?;
+    let label = label_markup(label, tree.wrap_width).html;
+    let marker = match goal.status {
+        Some(_) => status_marker(style, None, options),
+        None => String::new(),
+    };
+    let separator = if marker.is_empty() { "" } else { " " };
+    format!(
+        "{}{}{}{}{}{}",
+        marker,
+        separator,
+        style.start_tag,
+        label,
+        style.end_tag,
+        due_suffix(&goal.due)
+    )
 }
 
-#[throws(anyhow::Error)]
-fn write_group_label(tree: &SkillTree, group: &Group, output: &mut dyn Write) {
-    writeln!(output, r#"  label = <<table>"#)?;
+#[throws(Error)]
+fn write_group_label(
+    tree: &SkillTree,
+    group: &Group,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let change = diff_change(options, &DiffNode::Group(group.name.clone()));
+    let removed = matches!(change, Some(Change::Removed));
+    let border_color = if removed {
+        Some(DIFF_REMOVED_COLOR)
+    } else {
+        palette.border_color
+    };
+    let border = attribute_str("color", &border_color, "");
+    writeln!(output, r#"  label = <<table{}>"#, border)?;
 
     let label = group.label.as_ref().unwrap_or(&group.name);
-    let label = escape(label);
-    let group_href = attribute_str("href", &group.href, "");
-    let header_color = group
-        .header_color
+    let Markup { html: label, href } = label_markup(label, tree.wrap_width);
+    let group_href = group
+        .href
+        .clone()
+        .or(href)
+        .map(|href| tree.resolve_href(&href));
+    let group_href = link_attributes(&group_href, options);
+    let header_color = match change {
+        Some(Change::Added) => DIFF_ADDED_COLOR,
+        _ => group
+            .header_color
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or(palette.header_color),
+    };
+    let label = match tree.group_progress(group) {
+        (_, 0) => label,
+        (completed, total) => format!("{} ({}/{})", label, completed, total),
+    };
+    let effort = tree.group_effort(group);
+    let label = if options.effort && effort.total > 0.0 {
+        format!(
+            "{} · {} of {} left",
+            label,
+            round_effort(effort.remaining),
+            round_effort(effort.total)
+        )
+    } else {
+        label
+    };
+    let label = format!("{}{}", label, owner_suffix(tree, &group.owner, options));
+    let label = if removed {
+        format!(
+            r#"<font color="{}"><s>{}</s></font>"#,
+            DIFF_REMOVED_COLOR, label
+        )
+    } else {
+        label
+    };
+    let label = match palette.header_fontcolor {
+        Some(color) => format!(r#"<font color="{}">{}</font>"#, color, label),
+        None => label,
+    };
+    let label = with_icon(&group.icon, &label);
+
+    let tooltip = group
+        .tooltip
         .as_ref()
-        .map(String::as_str)
-        .unwrap_or("darkgoldenrod");
+        .map(|tooltip| htmlescape::encode_minimal(tooltip));
+    let tooltip = attribute_str("tooltip", &tooltip, "");
+
+    // A cell can only link one place, so the details link gets a cell of
+    // its own, over the emoji column.
+    let (details, colspan) = match &group.details_href {
+        Some(details_href) => (
+            format!(
+                r#"<td bgcolor="{}"{} tooltip="Details">{}</td>"#,
+                header_color,
+                link_attributes(
+                    &Some(htmlescape::encode_minimal(&tree.resolve_href(details_href))),
+                    options
+                ),
+                DETAILS_EMOJI,
+            ),
+            "",
+        ),
+        None => (String::new(), r#" colspan="2""#),
+    };
 
     writeln!(
         output,
-        r#"    <tr><td bgcolor="{header_color}" port="all" colspan="2"{group_href}>{label}</td></tr>"#,
+        r#"    <tr>{details}<td bgcolor="{header_color}" port="all"{colspan}{group_href}{tooltip}>{label}</td></tr>"#,
+        details = details,
+        colspan = colspan,
         group_href = group_href,
+        tooltip = tooltip,
         label = label,
         header_color = header_color
     )?;
+    write_progress_bar(tree, group, palette, output)?;
 
-    for item in &group.items {
-        let item_status = item
-            .status
-            .as_ref()
-            .or(group.status.as_ref())
-            .or(tree.default_status.as_ref());
+    // `width` is in points; items get what's left of it after the emoji.
+    let fits_width = group.width.map(|width| {
+        let chars = (width - EMOJI_WIDTH) / (options.fontsize * CHAR_WIDTH);
+        chars.max(1.0) as usize
+    });
+    let max_chars = match (fits_width, tree.wrap_width) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    write_item_rows(
+        tree,
+        ItemsOf::Group(group),
+        max_chars,
+        options,
+        palette,
+        output,
+    )?;
+    writeln!(output, r#"  </table>>"#)?;
+}
+
+/// Writes a row with a bar whose filled part shows how much of the group's
+/// work is done. Groups without items get no bar.
+#[throws(Error)]
+fn write_progress_bar(tree: &SkillTree, group: &Group, palette: &Palette, output: &mut dyn Write) {
+    if group.items.is_empty() {
+        return;
+    }
+    let width = group.width.unwrap_or(PROGRESS_WIDTH).round();
+    let done = (width * tree.group_completion(group)).round();
+    let mut cells = String::new();
+    for (cell_width, color) in &[
+        (done, palette.progress_color),
+        (width - done, palette.progress_remaining_color),
+    ] {
+        if *cell_width >= 1.0 {
+            cells.push_str(&format!(
+                r#"<td bgcolor="{}" width="{}" height="{}"></td>"#,
+                color, cell_width, PROGRESS_HEIGHT
+            ));
+        }
+    }
+    writeln!(
+        output,
+        r#"    <tr><td colspan="2" cellpadding="0"><table border="0" cellspacing="0" cellpadding="0"><tr>{}</tr></table></td></tr>"#,
+        cells
+    )?;
+}
+
+/// How a group, item, or goal changed, according to `GraphvizOptions::diff`.
+enum Change {
+    Added,
+    Removed,
+    /// The status changed, from this one.
+    Status(Option<String>),
+}
+
+fn diff_change(options: &GraphvizOptions, node: &DiffNode) -> Option<Change> {
+    let diff = options.diff.as_ref()?;
+    if diff.added.contains(node) {
+        Some(Change::Added)
+    } else if diff.removed.contains(node) {
+        Some(Change::Removed)
+    } else {
+        diff.status_changes
+            .iter()
+            .find(|change| &change.node == node)
+            .map(|change| Change::Status(change.from.clone()))
+    }
+}
+
+/// The group or goal whose items `write_item_rows` writes.
+#[derive(Copy, Clone)]
+enum ItemsOf<'a> {
+    Group(&'a Group),
+    Goal(&'a Goal),
+}
 
-        let mut style = match item_status.and_then(|x| tree.status.get(x)) {
-            Some(style) => style.clone(),
-            None => StatusStyle::default(),
+/// Writes a table row for each item, with its status's emoji (or its own) in
+/// the first column and its label in the second.
+#[throws(Error)]
+fn write_item_rows(
+    tree: &SkillTree,
+    items_of: ItemsOf<'_>,
+    max_chars: Option<usize>,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    let (items, ports): (_, Vec<Option<String>>) = match items_of {
+        ItemsOf::Group(group) => {
+            // Made-up ports are only given to the rows that edges attach to.
+            let required: HashSet<&str> = tree
+                .requirements()
+                .into_iter()
+                .map(|(_, _, requirement)| requirement.on.as_str())
+                .collect();
+            let ports = group.items().zip(group.ports()).map(|(item, port)| {
+                let attached = item.port.is_some()
+                    || item.has_requirements()
+                    || required.contains(format!("{}:{}", group.name, port).as_str());
+                Some(port).filter(|_| attached)
+            });
+            (&group.items, ports.collect())
+        }
+        ItemsOf::Goal(goal) => (
+            &goal.items,
+            goal.items.iter().map(|item| item.port.clone()).collect(),
+        ),
+    };
+    let mut items: Vec<(&Item, Option<String>)> = items.iter().zip(ports).collect();
+    if options.sort_by_priority {
+        items.sort_by_key(|(item, _)| (item.priority.is_none(), item.priority.clone()));
+    }
+    for (item, port) in items {
+        let (status, change) = match items_of {
+            ItemsOf::Group(group) => (
+                tree.item_status(group, item),
+                diff_change(
+                    options,
+                    &DiffNode::Item {
+                        group: group.name.clone(),
+                        label: item.label.clone(),
+                    },
+                ),
+            ),
+            ItemsOf::Goal(_) => (tree.goal_item_status(item), None),
+        };
+        let mut style = status_style(tree, status, palette);
+        let Markup { html: label, href } = item_label_markup(item, max_chars);
+        let priority = item
+            .priority
+            .as_ref()
+            .map(|priority| (priority, tree.priority_style(priority)));
+        let badge = match &priority {
+            Some((name, priority)) => {
+                let badge = priority.badge.as_ref().unwrap_or(name);
+                format!("<b>{}</b> ", htmlescape::encode_minimal(badge))
+            }
+            None => String::new(),
+        };
+        if let Some(bgcolor) = priority
+            .as_ref()
+            .and_then(|(_, style)| style.bgcolor.clone())
+        {
+            style.bgcolor = Some(bgcolor);
+        }
+        let progress = match item.progress {
+            Some(progress) => format!(" ({}%)", progress),
+            None => String::new(),
+        };
+        let due = due_suffix(&item.due);
+        let owner = owner_suffix(tree, &item.owner, options);
+        let overdue = is_overdue(tree, &item.due, status, options);
+        if overdue {
+            style.bgcolor = Some(OVERDUE_BGCOLOR.to_owned());
+        }
+        let mut border_color = if overdue {
+            Some(OVERDUE_COLOR.to_owned())
+        } else {
+            priority.and_then(|(_, style)| style.color)
+        };
+        let was = match &change {
+            Some(Change::Added) => {
+                style.bgcolor = Some(DIFF_ADDED_COLOR.to_owned());
+                String::new()
+            }
+            Some(Change::Removed) => {
+                style.fontcolor = Some(DIFF_REMOVED_COLOR.to_owned());
+                style.start_tag = "<s>".to_owned();
+                style.end_tag = "</s>".to_owned();
+                border_color = Some(DIFF_REMOVED_COLOR.to_owned());
+                String::new()
+            }
+            Some(Change::Status(from)) => format!(
+                " <b>(was {})</b>",
+                htmlescape::encode_minimal(from.as_deref().unwrap_or("unset"))
+            ),
+            None => String::new(),
         };
+        let border = attribute_str("color", &border_color, "");
 
         let fontcolor = attribute_str("fontcolor", &style.fontcolor, "");
         let bgcolor = attribute_str("bgcolor", &style.bgcolor, "");
-        let href = attribute_str("href", &item.href, "");
-        if item.href.is_some() && style.start_tag == "" {
+        let owner_name = match items_of {
+            ItemsOf::Group(group) => &group.name,
+            ItemsOf::Goal(goal) => &goal.name,
+        };
+        let item_href = tree.item_href(owner_name, item);
+        let linked = item_href.is_some();
+        let href = item_href.or_else(|| href.map(|href| tree.resolve_href(&href)));
+        let href = link_attributes(&href, options);
+        let tooltip = tree
+            .item_tooltip(status, item)
+            .map(|tooltip| htmlescape::encode_minimal(&tooltip));
+        let tooltip = attribute_str("tooltip", &tooltip, "");
+        if linked && style.start_tag == "" {
             style.start_tag = "<u>".to_owned();
             style.end_tag = "</u>".to_owned();
         }
-        let port = item.port.as_ref().map(|port| format!("_{}", port));
-        let port_in = attribute_str("port", &port, "_in");
-        let port_out = attribute_str("port", &port, "_out");
+        let port_in = attribute_str("port", &port.as_ref().map(|port| port_id(port, "in")), "");
+        let port_out = attribute_str("port", &port.as_ref().map(|port| port_id(port, "out")), "");
+        let extra: String = item
+            .graphviz_attrs
+            .iter()
+            .map(|(name, value)| format!(r#" {}="{}""#, name, htmlescape::encode_minimal(value)))
+            .collect();
         writeln!(
             output,
             "    \
              <tr>\
-             <td{bgcolor}{port_in}>{emoji}</td>\
-             <td{fontcolor}{bgcolor}{href}{port_out}>\
-             {start_tag}{label}{end_tag}\
+             <td{bgcolor}{border}{tooltip}{port_in}>{emoji}</td>\
+             <td{fontcolor}{bgcolor}{border}{href}{tooltip}{port_out}{extra}>\
+             {badge}{start_tag}{label}{end_tag}{progress}{due}{owner}{was}\
              </td>\
              </tr>",
             fontcolor = fontcolor,
             bgcolor = bgcolor,
-            emoji = style.emoji.as_ref().map_or("", String::as_ref),
+            border = border,
+            emoji = with_icon(
+                &item.icon,
+                &status_marker(&style, item.emoji.as_deref(), options)
+            ),
             href = href,
+            tooltip = tooltip,
             port_in = port_in,
             port_out = port_out,
-            label = item.label,
+            extra = extra,
+            badge = badge,
+            label = label,
+            start_tag = style.start_tag,
+            end_tag = style.end_tag,
+            progress = progress,
+            due = due,
+            owner = owner,
+            was = was,
+        )?;
+    }
+}
+
+/// Writes a node with a table listing every status, each rendered the way
+/// items with that status are.
+#[throws(Error)]
+fn write_legend(
+    tree: &SkillTree,
+    options: &GraphvizOptions,
+    palette: &Palette,
+    output: &mut dyn Write,
+) {
+    writeln!(output, r#""{}" ["#, LEGEND_NODE)?;
+    let border = attribute_str("color", &palette.border_color, "");
+    writeln!(output, r#"  label = <<table{}>"#, border)?;
+
+    let label = match palette.header_fontcolor {
+        Some(color) => format!(r#"<font color="{}">Legend</font>"#, color),
+        None => "Legend".to_owned(),
+    };
+    writeln!(
+        output,
+        r#"    <tr><td bgcolor="{}" colspan="2">{}</td></tr>"#,
+        palette.header_color, label,
+    )?;
+
+    for name in tree.status.keys() {
+        let style = status_style(tree, Some(name), palette);
+        let fontcolor = attribute_str("fontcolor", &style.fontcolor, "");
+        let bgcolor = attribute_str("bgcolor", &style.bgcolor, "");
+        writeln!(
+            output,
+            "    \
+             <tr>\
+             <td{bgcolor}>{emoji}</td>\
+             <td{fontcolor}{bgcolor}>{start_tag}{label}{end_tag}</td>\
+             </tr>",
+            fontcolor = fontcolor,
+            bgcolor = bgcolor,
+            emoji = status_marker(&style, None, options),
+            label = escape(name),
             start_tag = style.start_tag,
             end_tag = style.end_tag,
         )?;
     }
 
     writeln!(output, r#"  </table>>"#)?;
+    writeln!(output, r#"  shape = "none""#)?;
+    writeln!(output, r#"  margin = 0"#)?;
+    writeln!(output, r#"]"#)?;
+}
+
+/// What to show in the first column for an item with the given style: its
+/// own emoji or its status's, or in plain-text mode, its status's marker.
+fn status_marker(
+    style: &StatusStyle,
+    item_emoji: Option<&str>,
+    options: &GraphvizOptions,
+) -> String {
+    let marker = if options.plain_text {
+        style.marker.as_deref().map(htmlescape::encode_minimal)
+    } else {
+        item_emoji.or(style.emoji.as_deref()).map(str::to_owned)
+    };
+    marker.unwrap_or_default()
+}
+
+/// The style to draw an item with the given status in, after the theme has
+/// had its say.
+fn status_style(tree: &SkillTree, status: Option<&String>, palette: &Palette) -> StatusStyle {
+    let mut style = match status.and_then(|x| tree.status_style(x)) {
+        Some(style) => style.clone(),
+        None => StatusStyle::default(),
+    };
+    if let (Some(status), Some(bgcolor)) = (status, palette.item_bgcolor) {
+        if is_builtin_style(tree.canonical_status(status), &style) {
+            style.bgcolor = Some(bgcolor.to_owned());
+        }
+    }
+    style
+}
+
+fn due_suffix(due: &Option<String>) -> String {
+    match due {
+        Some(due) => format!(" (due {})", htmlescape::encode_minimal(due)),
+        None => String::new(),
+    }
+}
+
+/// Whether something due on `due` is past due and not yet completed.
+fn is_overdue(
+    tree: &SkillTree,
+    due: &Option<String>,
+    status: Option<&String>,
+    options: &GraphvizOptions,
+) -> bool {
+    match (due, &options.as_of) {
+        (Some(due), Some(as_of)) => due < as_of && !tree.is_completed(status),
+        _ => false,
+    }
+}
+
+/// Formats `owner` to follow a label, in its own color if owners are
+/// color-coded.
+fn owner_suffix(tree: &SkillTree, owner: &Option<String>, options: &GraphvizOptions) -> String {
+    let owner = match owner {
+        Some(owner) => owner,
+        None => return String::new(),
+    };
+    let name = format!("<i>{}</i>", htmlescape::encode_minimal(owner));
+    if !options.color_owners {
+        return format!(" {}", name);
+    }
+    let mut owners: Vec<&str> = vec![];
+    for group in tree.groups() {
+        let items = group.items().map(|item| &item.owner);
+        for owner in std::iter::once(&group.owner).chain(items).flatten() {
+            if !owners.contains(&owner.as_str()) {
+                owners.push(owner);
+            }
+        }
+    }
+    let index = owners.iter().position(|o| o == owner).unwrap_or(0);
+    let color = OWNER_COLORS[index % OWNER_COLORS.len()];
+    format!(r#" <font color="{}">{}</font>"#, color, name)
+}
+
+/// Rounds an effort to one decimal place, so sums of fractions print tidily.
+fn round_effort(effort: f64) -> f64 {
+    (effort * 10.0).round() / 10.0
 }
 
 fn attribute_str(label: &str, text: &Option<impl AsRef<str>>, suffix: &str) -> String {
@@ -177,17 +1590,79 @@ fn attribute_str(label: &str, text: &Option<impl AsRef<str>>, suffix: &str) -> S
     }
 }
 
+/// Formats the `href` attribute of a table cell linking to `href`, if
+/// any, with a `target` that opens it in a new tab if `options.new_tab`
+/// is set.
+fn link_attributes(href: &Option<String>, options: &GraphvizOptions) -> String {
+    let mut attributes = attribute_str("href", href, "");
+    if href.is_some() && options.new_tab {
+        attributes.push_str(r#" target="_blank""#);
+    }
+    attributes
+}
+
+/// Formats the attributes of the edge for `requirement`: its label, and a
+/// style that depends on the status of the item or group it refers to.
+/// Soft requirements are always dashed and don't constrain the layout.
+/// `extra` attributes come last, skipping empty ones.
+fn edge_attributes(tree: &SkillTree, requirement: &Requirement, extra: &[&str]) -> String {
+    let style = tree
+        .requirement_status(&requirement.on)
+        .and_then(|status| tree.status_style(status));
+    let mut attributes = vec![];
+    if let Some(label) = &requirement.label {
+        attributes.push(format!(r#"label = "{}""#, escape_quoted(label)));
+    }
+    if requirement.soft {
+        attributes.push(r#"style = "dashed", constraint = "false""#.to_owned());
+    }
+    if let Some(style) = style {
+        if let (Some(edge_style), false) = (&style.edge_style, requirement.soft) {
+            attributes.push(format!(r#"style = "{}""#, edge_style));
+        }
+        if let Some(edge_color) = &style.edge_color {
+            attributes.push(format!(r#"color = "{}""#, edge_color));
+        }
+    }
+    attributes.extend(
+        extra
+            .iter()
+            .filter(|attribute| !attribute.is_empty())
+            .map(|attribute| attribute.to_string()),
+    );
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [ {} ]", attributes.join(", "))
+    }
+}
+
+/// Formats an optional graph-wide setting, for use after other settings.
+fn attribute_setting(name: &str, value: &Option<impl std::fmt::Display>) -> String {
+    match value {
+        None => String::new(),
+        Some(value) => format!(r#", {} = "{}""#, name, value),
+    }
+}
+
 impl SkillTree {
     fn port_name(&self, requires: &str, mode: &str) -> String {
         if let Some(index) = requires.find(":") {
             let name = &requires[..index];
-            let port = &requires[index + 1..];
-            format!(r#""{}":_{}_{}"#, name, port, mode)
+            let port = port_id(&requires[index + 1..], mode);
+            if port.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                format!("{}:{}", node_id(name), port)
+            } else {
+                format!(r#"{}:"{}""#, node_id(name), port)
+            }
         } else if self.is_goal(requires) {
             // Goals don't have ports, so we don't need a `:all`
-            format!(r#""{}""#, requires)
+            node_id(requires)
         } else {
-            format!(r#""{}":all"#, requires)
+            format!("{}:all", node_id(requires))
         }
     }
 }
+
+#[cfg(test)]
+mod test;
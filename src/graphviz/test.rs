@@ -0,0 +1,1519 @@
+use crate::{Error, GraphvizOptions, SkillTree, Theme};
+
+const TREE: &str = r#"
+[[group]]
+name = "a"
+items = []
+
+[[goal]]
+name = "done"
+requires = ["a"]
+"#;
+
+#[test]
+fn default_options_match_write_graphviz() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert_eq!(
+        dot,
+        tree.to_graphviz_with(&GraphvizOptions::default()).unwrap()
+    );
+    assert!(dot.contains(r#"graph [ rankdir = "LR" ];"#));
+    assert!(dot.contains(r#"node [ fontsize="16", shape = "ellipse" ];"#));
+}
+
+#[test]
+fn applies_options() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let options = GraphvizOptions {
+        rankdir: "TB".to_owned(),
+        fontname: Some("Helvetica".to_owned()),
+        fontsize: 12.5,
+        goal_color: Some("lightblue".to_owned()),
+        splines: Some("ortho".to_owned()),
+        ranksep: Some(1.5),
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#"graph [ rankdir = "TB", splines = "ortho", ranksep = "1.5" ];"#));
+    assert!(dot.contains(r#"node [ fontsize="12.5", shape = "ellipse", fontname = "Helvetica" ];"#));
+    assert!(dot.contains(r#"fillcolor = "lightblue""#));
+}
+
+#[test]
+fn applies_theme() {
+    let tree = SkillTree::parse(&format!("theme = \"dark\"\n{}", TREE)).unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r##"bgcolor = "#1e1e1e""##));
+
+    // Options take precedence over the tree.
+    let options = GraphvizOptions {
+        theme: Some(Theme::Light),
+        ..GraphvizOptions::default()
+    };
+    let light = tree.to_graphviz_with(&options).unwrap();
+    assert_eq!(
+        light,
+        SkillTree::parse(TREE).unwrap().to_graphviz().unwrap()
+    );
+}
+
+#[test]
+fn legend() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    assert!(!tree.to_graphviz().unwrap().contains("Legend"));
+
+    let options = GraphvizOptions {
+        legend: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#""_legend" ["#));
+    assert!(dot.contains(
+        "<tr><td bgcolor=\"cornsilk\">🙋</td>\
+         <td fontcolor=\"red\" bgcolor=\"cornsilk\">Unassigned</td></tr>"
+    ));
+    // Statuses are listed in a stable order.
+    let blocked = dot.find(">Blocked<").unwrap();
+    let complete = dot.find(">Complete<").unwrap();
+    assert!(blocked < complete);
+}
+
+#[test]
+fn deterministic_output() {
+    let text = r#"
+[status.Todo]
+emoji = "📝"
+aliases = ["Open", "New"]
+
+[status.Doing]
+emoji = "🚧"
+
+[status.Done]
+emoji = "✅"
+aliases = ["Closed"]
+
+[area.front]
+label = "Front end"
+
+[area.back]
+label = "Back end"
+
+[priority.P0]
+color = "red"
+
+[priority.P1]
+color = "orange"
+
+[[group]]
+name = "a"
+area = "front"
+items = [{ label = "x", status = "Open", priority = "P0" }]
+
+[[group]]
+name = "b"
+area = "back"
+requires = ["a"]
+items = [{ label = "y", status = "Closed", priority = "P1" }]
+"#;
+    let options = GraphvizOptions {
+        legend: true,
+        ..GraphvizOptions::default()
+    };
+    let render = || {
+        let tree = SkillTree::parse(text).unwrap();
+        (
+            tree.to_graphviz_with(&options).unwrap(),
+            tree.to_toml_string().unwrap(),
+        )
+    };
+    let first = render();
+    for _ in 0..10 {
+        assert_eq!(render(), first);
+    }
+}
+
+#[test]
+fn title() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    assert!(!tree.to_graphviz().unwrap().contains("labelloc"));
+
+    let tree = SkillTree::parse(&format!(
+        "title = \"Async & you\"\ndescription = \"The plan\"\nupdated = \"2020-03-01\"\n{}",
+        TREE
+    ))
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(
+        r#"graph [ label = <<b>Async &amp; you</b><br/>The plan<br/><i>Updated 2020-03-01</i>>, labelloc = "t" ];"#
+    ));
+}
+
+#[test]
+fn graphviz_attrs() {
+    let tree = SkillTree::parse(
+        r#"
+graphviz_attrs = { concentrate = "true" }
+
+[[group]]
+name = "a"
+graphviz_attrs = { tooltip = "say \"hi\"" }
+items = [{ label = "x", graphviz_attrs = { title = "a & b" } }]
+
+[[goal]]
+name = "done"
+requires = ["a"]
+graphviz_attrs = { fillcolor = "red" }
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"graph [ concentrate = "true" ];"#));
+    assert!(dot.contains(r#"  tooltip = "say \"hi\"""#));
+    assert!(dot.contains(r#"title="a &amp; b">"#));
+    // Supplied after the goal's own fillcolor, so it wins.
+    let own = dot.find(r#"fillcolor = "darkgoldenrod""#).unwrap();
+    let supplied = dot.find(r#"fillcolor = "red""#).unwrap();
+    assert!(own < supplied);
+}
+
+#[test]
+fn edges_styled_by_status() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "x", port = "x", status = "Blocked" },
+    { label = "y", port = "y", status = "Complete" },
+]
+
+[[group]]
+name = "b"
+requires = ["a"]
+items = [{ label = "z", port = "z", requires = ["a:x"] }]
+
+[[goal]]
+name = "done"
+requires = ["a:y"]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""a":all -> "b":all;"#));
+    assert!(dot.contains(r#""a":_x_out -> "b":_z_in [ style = "dotted", color = "grey" ];"#));
+    assert!(dot.contains(r#""a":_y_out -> "done";"#));
+}
+
+#[test]
+fn soft_edges_differ_from_incomplete_ones() {
+    for palette in &["default", "colorblind"] {
+        let tree = SkillTree::parse(&format!(
+            r#"
+palette = "{}"
+
+[[group]]
+name = "a"
+items = [{{ label = "x", port = "x" }}, {{ label = "y", port = "y" }}]
+
+[[group]]
+name = "b"
+items = [{{ label = "z", requires = ["a:x", "a:y?"] }}]
+"#,
+            palette
+        ))
+        .unwrap();
+        let dot = tree.to_graphviz().unwrap();
+        let edge = |port: &str| {
+            let start = format!(r#""a":_{}_out -> "b":_z_in"#, port);
+            dot.lines()
+                .find(|line| line.starts_with(&start))
+                .unwrap()
+                .to_owned()
+        };
+        let (hard, soft) = (edge("x"), edge("y"));
+        assert!(hard.contains(r#"style = "dotted""#), "{}", hard);
+        assert!(soft.contains(r#"style = "dashed""#), "{}", soft);
+    }
+}
+
+#[test]
+fn made_up_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "Type check" }, { label = "unused" }]
+
+[[group]]
+name = "b"
+items = [{ label = "Code gen", requires = ["a:type_check"] }]
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""a":_type_check_out -> "b":_code_gen_in"#));
+    assert!(dot.contains(r#" port="_type_check_out">Type check</td>"#));
+    assert!(dot.contains(r#" port="_code_gen_in">"#));
+    assert!(!dot.contains("_unused_"));
+}
+
+#[test]
+fn sanitizes_node_ids() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "Café \"au lait\""
+rank = 1
+items = [{ label = "Milk", port = "milk" }]
+
+[[goal]]
+name = "Two\nlines"
+rank = 2
+requires = ["Café \"au lait\":milk", "Café \"au lait\""]
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    let cafe = r#""Caf~e9; ~22;au lait~22;""#;
+    let two_lines = r#""Two~a;lines""#;
+    assert!(dot.contains(&format!("{} [", cafe)));
+    assert!(dot.contains(&format!("{} [", two_lines)));
+    assert!(dot.contains(&format!("{}:_milk_out -> {}", cafe, two_lines)));
+    assert!(dot.contains(&format!("{}:all -> {}", cafe, two_lines)));
+    assert!(dot.contains(&format!("{} -> {} [ style = \"invis\" ];", cafe, two_lines)));
+    // What is shown is still the name itself.
+    assert!(dot.contains(">Café &quot;au lait&quot; (0/1)<"));
+}
+
+#[test]
+fn sanitizes_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "Milk", port = "warm \"milk\"" }, { label = "Tea", port = "tea:hot" }]
+
+[[goal]]
+name = "done"
+requires = ['a:warm "milk"']
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"port="_warm ~22;milk~22;_out""#));
+    assert!(dot.contains(r#"port="_tea~3a;hot_in""#));
+    assert!(dot.contains(r#""a":"_warm ~22;milk~22;_out" -> "done""#));
+}
+
+#[test]
+fn edge_labels() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[group]]
+name = "b"
+requires = [{ on = "a", label = "needs \"stable\" API" }]
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""a":all -> "b":all [ label = "needs \"stable\" API" ];"#));
+}
+
+#[test]
+fn soft_requirements() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[group]]
+name = "b"
+requires = ["a?"]
+items = []
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""a":all -> "b":all [ style = "dashed", constraint = "false" ];"#));
+}
+
+#[test]
+fn requires_any() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "impl-a"
+items = []
+
+[[group]]
+name = "impl-b"
+items = []
+
+[[goal]]
+name = "done"
+requires_any = [["impl-a", "impl-b"]]
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""_any0" [ shape = "circle", label = "or""#));
+    assert!(dot.contains(r#""impl-a":all -> "_any0" [ arrowhead = "none" ];"#));
+    assert!(dot.contains(r#""impl-b":all -> "_any0" [ arrowhead = "none" ];"#));
+    assert!(dot.contains(r#""_any0" -> "done";"#));
+}
+
+#[test]
+fn areas() {
+    let tree = SkillTree::parse(
+        r#"
+[area.front]
+label = "Front end"
+bgcolor = "lightblue"
+
+[[group]]
+name = "lexer"
+area = "front"
+items = []
+
+[[group]]
+name = "codegen"
+area = "back"
+items = []
+
+[[group]]
+name = "parser"
+area = "front"
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    let front = dot.find(r#"subgraph "cluster_front" {"#).unwrap();
+    let back = dot.find(r#"subgraph "cluster_back" {"#).unwrap();
+    assert!(front < back);
+    assert!(dot.contains(r#"label = "Front end""#));
+    assert!(dot.contains(r#"fillcolor = "lightblue""#));
+    // Areas without a style use their name and the theme's color.
+    assert!(dot[back..].starts_with(
+        "subgraph \"cluster_back\" {\n  label = \"back\"\n  style = \"filled\"\n  fillcolor = \"whitesmoke\"\n\"codegen\" ["
+    ));
+    let parser = dot.find(r#""parser" ["#).unwrap();
+    assert!(front < parser && parser < back);
+}
+
+#[test]
+fn ranks() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+rank = 2
+items = []
+
+[[group]]
+name = "b"
+rank = 1
+items = []
+
+[[group]]
+name = "c"
+rank = 2
+items = []
+
+[[goal]]
+name = "done"
+rank = 3
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"{ rank = same; "b"; }"#));
+    assert!(dot.contains(r#"{ rank = same; "a"; "c"; }"#));
+    assert!(dot.contains(r#""b" -> "a" [ style = "invis" ];"#));
+    assert!(dot.contains(r#""a" -> "done" [ style = "invis" ];"#));
+}
+
+#[test]
+fn wraps_html() {
+    use super::wrap;
+    assert_eq!(wrap("one two three four", 9), "one two<br/>three<br/>four");
+    // Tags and entities are measured by what they display.
+    assert_eq!(
+        wrap(r#"<font color="red">a&amp;b</font> cd ef"#, 6),
+        r#"<font color="red">a&amp;b</font> cd<br/>ef"#
+    );
+    assert_eq!(wrap("extraordinarily long", 5), "extraordinarily<br/>long");
+    assert_eq!(wrap("ab<br/>cd ef", 5), "ab<br/>cd ef");
+}
+
+#[test]
+fn group_width_wraps_items() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+width = 112.0
+items = [{ label = "a rather long item label" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">a rather<br/>long item<br/>label<"));
+}
+
+#[test]
+fn wrap_width() {
+    let tree = SkillTree::parse(
+        r#"
+wrap_width = 10
+
+[[group]]
+name = "a"
+label = "Group with a long name"
+items = [{ label = "a rather long item label" }]
+
+[[goal]]
+name = "done"
+label = "Everything is done"
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">Group with<br/>a long<br/>name (0/1)<"));
+    assert!(dot.contains(">a rather<br/>long item<br/>label<"));
+    assert!(dot.contains("label = <Everything<br/>is done>"));
+}
+
+#[test]
+fn markdown_labels() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+label = "The **parser**"
+items = [
+    { label = "See [the RFC](https://example.com/rfc)" },
+    { label = "`fn` items", href = "https://example.com" },
+]
+
+[[goal]]
+name = "done"
+label = "*Ship* it & celebrate"
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">The <b>parser</b> (0/2)<"));
+    assert!(
+        dot.contains(r#" href="https://example.com/rfc" tooltip="Unassigned">See <u>the RFC</u><"#)
+    );
+    assert!(dot
+        .contains(r#" href="https://example.com" tooltip="Unassigned"><u><font face="monospace">fn</font> items</u><"#));
+    assert!(dot.contains("label = <<i>Ship</i> it &amp; celebrate>"));
+}
+
+#[test]
+fn item_labels_escape_html() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "Box<dyn Fn> &amp; *closures*" },
+    { label = "<s>Old</s> &amp; new", label_html = true },
+]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">Box&lt;dyn Fn&gt; &amp;amp; <i>closures</i><"));
+    assert!(dot.contains("><s>Old</s> &amp; new<"));
+}
+
+#[test]
+fn descriptions() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", description = "Why \"x\" & more" }]
+
+[[goal]]
+name = "done"
+description = "All \"done\""
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#" tooltip="Unassigned: Why &quot;x&quot; &amp; more">x<"#));
+    assert!(dot.contains(r#"  tooltip = "All \"done\"""#));
+}
+
+#[test]
+fn status_tooltips() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "x", status = "Complete", description = "Landed" },
+    { label = "y", status = "Blocked" },
+]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert_eq!(dot.matches(r#" tooltip="Complete: Landed">"#).count(), 2);
+    assert_eq!(dot.matches(r#" tooltip="Blocked">"#).count(), 2);
+}
+
+#[test]
+fn group_tooltip_and_details() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+href = "https://example.com/issue"
+details_href = "https://example.com/design"
+tooltip = "The <a> group"
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(
+        r#"<tr><td bgcolor="darkgoldenrod" href="https://example.com/design" tooltip="Details">📄</td><td bgcolor="darkgoldenrod" port="all" href="https://example.com/issue" tooltip="The &lt;a&gt; group">a</td></tr>"#
+    ));
+
+    // Without a details link the header spans both columns, as before.
+    let tree = SkillTree::parse(TREE).unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"<tr><td bgcolor="darkgoldenrod" port="all" colspan="2">a</td></tr>"#));
+}
+
+#[test]
+fn icons() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+icon = "logo.png"
+items = [{ label = "x", icon = "https://example.com/x.svg" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(
+        r#"<td fixedsize="true" width="20" height="20"><img src="logo.png" scale="true"/></td><td>a (0/1)</td>"#
+    ));
+    assert!(dot.contains(r#"<img src="https://example.com/x.svg" scale="true"/></td><td>🙋</td>"#));
+}
+
+#[test]
+fn item_emoji() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", emoji = "🔥" }, { label = "y" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"<td bgcolor="cornsilk" tooltip="Unassigned">🔥</td>"#));
+    assert!(dot.contains(r#"<td bgcolor="cornsilk" tooltip="Unassigned">🙋</td>"#));
+}
+
+#[test]
+fn plain_text_markers() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Done]
+emoji = "✅"
+marker = "<done>"
+
+[[group]]
+name = "a"
+items = [
+    { label = "x", status = "Done", emoji = "🔥" },
+    { label = "y", status = "Done" },
+]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        plain_text: true,
+        legend: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(!dot.contains('✅') && !dot.contains('🔥'));
+    assert_eq!(
+        dot.matches(r#"<td tooltip="Done">&lt;done&gt;</td>"#)
+            .count(),
+        2
+    );
+    assert!(dot.contains("<td>&lt;done&gt;</td><td>Done</td>"));
+}
+
+#[test]
+fn status_aliases() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Complete]
+emoji = "✅"
+bgcolor = "palegreen"
+aliases = ["Done", "Shipped"]
+
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Done" }, { label = "y", status = "Shipped" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert_eq!(
+        dot.matches(r#"<td bgcolor="palegreen" tooltip="Complete">✅</td>"#)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn goal_status_and_style() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[goal]]
+name = "done"
+requires = ["a"]
+status = "Complete"
+fillcolor = "gold"
+shape = "star"
+
+[[goal]]
+name = "later"
+requires = ["done"]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains("label = <☑️ <s>done</s>>"));
+    assert!(dot.contains(r#"shape = "star""#));
+    assert!(dot.contains(r#"fillcolor = "gold""#));
+    assert!(dot.contains("label = <later>"));
+    assert!(dot.contains(r#"shape = "note""#));
+}
+
+#[test]
+fn goal_items() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[goal]]
+name = "release"
+requires = ["a"]
+items = [{ label = "Docs", status = "Complete" }, { label = "Blog post" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"<tr><td bgcolor="darkgoldenrod" colspan="2">release</td></tr>"#));
+    assert!(dot.contains("<s>Docs</s>"));
+    assert!(dot.contains(r#"<td bgcolor="cornsilk" tooltip="Unassigned">🙋</td>"#));
+    assert!(!dot.contains(r#"shape = "note""#));
+    assert!(dot.contains(r#""a":all -> "release""#));
+}
+
+#[test]
+fn milestones() {
+    let tree = SkillTree::parse(
+        r#"
+[milestone.beta]
+label = "Beta"
+date = "2020-06-01"
+
+[[group]]
+name = "a"
+items = []
+
+[[goal]]
+name = "parse"
+requires = ["a"]
+milestone = "beta"
+
+[[goal]]
+name = "check"
+requires = ["a"]
+milestone = "beta"
+
+[[goal]]
+name = "ship"
+requires = ["parse", "check"]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    let start = dot.find(r#"subgraph "cluster_milestone_beta" {"#).unwrap();
+    let cluster = &dot[start..start + dot[start..].find("\n}").unwrap()];
+    assert!(cluster.contains(r#"label = "Beta\n2020-06-01""#));
+    assert!(cluster.contains(r#""parse" ["#));
+    assert!(cluster.contains(r#""check" ["#));
+    assert!(!cluster.contains(r#""ship" ["#));
+    assert!(dot[..start].contains(r#""ship" ["#));
+}
+
+#[test]
+fn goal_links() {
+    let tree = SkillTree::parse(
+        r#"
+[[goal]]
+name = "stretch"
+href = "https://example.com/stretch"
+new_tab = true
+shape = "doubleoctagon"
+
+[[goal]]
+name = "committed"
+href = "https://example.com/committed"
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"href = "https://example.com/stretch""#));
+    assert!(dot.contains(r#"href = "https://example.com/committed""#));
+    assert_eq!(dot.matches(r#"target = "_blank""#).count(), 1);
+    assert!(dot.contains(r#"shape = "doubleoctagon""#));
+
+    let output = toml::to_string(&tree).unwrap();
+    assert_eq!(output.matches("new_tab = true").count(), 1);
+    assert!(!output.contains("new_tab = false"));
+}
+
+#[test]
+fn completion_rollup() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Todo]
+
+[status.Done]
+completed = true
+
+[status.Released]
+completed = true
+
+[[group]]
+name = "parser"
+label = "Parser"
+default_status = "Todo"
+items = [
+    { label = "a", status = "Done" },
+    { label = "b", status = "Released" },
+    { label = "c" },
+]
+
+[[group]]
+name = "empty"
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">Parser (2/3)<"));
+    assert!(dot.contains(">empty<"));
+}
+
+#[test]
+fn progress_bars() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Complete" }, { label = "y" }, { label = "z" }, { label = "w" }]
+
+[[group]]
+name = "b"
+width = 200
+items = [{ label = "x", status = "Complete" }]
+
+[[group]]
+name = "c"
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(
+        r#"<tr><td bgcolor="forestgreen" width="25" height="4"></td><td bgcolor="lightgrey" width="75" height="4"></td></tr>"#
+    ));
+    assert!(dot.contains(r#"<tr><td bgcolor="forestgreen" width="200" height="4"></td></tr>"#));
+    assert_eq!(
+        dot.matches(r#"<td colspan="2" cellpadding="0">"#).count(),
+        2
+    );
+}
+
+#[test]
+fn item_progress() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Complete" }, { label = "y", progress = 40 }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">y (40%)</td>"));
+    // All of the first item and 40% of the second, out of two.
+    assert!(dot.contains(r#"<td bgcolor="forestgreen" width="70" height="4"></td>"#));
+}
+
+#[test]
+fn effort_in_headers() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", effort = 2, status = "Complete" }, { label = "y", effort = 1.5 }]
+
+[[group]]
+name = "b"
+items = [{ label = "z" }]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        effort: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(">a (1/2) · 1.5 of 3.5 left<"));
+    assert!(dot.contains(">b (0/1)<"));
+    assert!(!tree.to_graphviz().unwrap().contains(" left<"));
+}
+
+#[test]
+fn owners() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+owner = "alice"
+items = [{ label = "x", owner = "bob" }, { label = "y", assignee = "alice" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">a (0/2) <i>alice</i><"));
+    assert!(dot.contains(">x <i>bob</i><"));
+    assert!(dot.contains(">y <i>alice</i><"));
+
+    let options = GraphvizOptions {
+        color_owners: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r##">a (0/2) <font color="#1b9e77"><i>alice</i></font><"##));
+    assert!(dot.contains(r##">x <font color="#d95f02"><i>bob</i></font><"##));
+    assert!(dot.contains(r##">y <font color="#1b9e77"><i>alice</i></font><"##));
+}
+
+#[test]
+fn due_dates() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "late", due = "2024-08-01" },
+    { label = "done", due = "2024-08-01", status = "Complete" },
+    { label = "upcoming", due = "2024-10-01" },
+]
+
+[[goal]]
+name = "ship"
+due = "2024-08-15"
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        as_of: Some("2024-09-01".to_owned()),
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#"<td bgcolor="mistyrose" color="red" tooltip="Unassigned">🙋</td>"#));
+    assert!(dot.contains(
+        r#" bgcolor="mistyrose" color="red" tooltip="Unassigned">late (due 2024-08-01)</td>"#
+    ));
+    assert!(dot.contains(
+        r#"<td bgcolor="cornsilk" tooltip="Complete"><s>done</s> (due 2024-08-01)</td>"#
+    ));
+    assert!(
+        dot.contains(r#" bgcolor="cornsilk" tooltip="Unassigned">upcoming (due 2024-10-01)</td>"#)
+    );
+    assert!(dot.contains("label = <ship (due 2024-08-15)>"));
+    assert_eq!(dot.matches(r#" color="red""#).count(), 2);
+    assert!(dot.contains(r#"  color = "red""#));
+
+    // Without `as_of`, the dates are shown but nothing is overdue, so the
+    // output doesn't change from one day to the next.
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#" bgcolor="cornsilk" tooltip="Unassigned">late (due 2024-08-01)</td>"#));
+    assert!(dot.contains("label = <ship (due 2024-08-15)>"));
+    assert!(!dot.contains("mistyrose"));
+    assert!(!dot.contains(r#" color="red""#));
+    assert!(!dot.contains(r#"color = "red""#));
+}
+
+#[test]
+fn priorities() {
+    let tree = SkillTree::parse(
+        r#"
+[priority.P2]
+badge = "⬇"
+bgcolor = "lightyellow"
+
+[[group]]
+name = "a"
+items = [
+    { label = "low", priority = "P2" },
+    { label = "none" },
+    { label = "urgent", priority = "P0" },
+]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"color="red" tooltip="Unassigned"><b>P0</b> urgent</td>"#));
+    assert!(dot.contains(r#" bgcolor="lightyellow" tooltip="Unassigned"><b>⬇</b> low</td>"#));
+    assert!(dot.find(">none<").unwrap() < dot.find("urgent<").unwrap());
+
+    let options = GraphvizOptions {
+        sort_by_priority: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    let urgent = dot.find("urgent<").unwrap();
+    let low = dot.find("low<").unwrap();
+    let none = dot.find(">none<").unwrap();
+    assert!(urgent < low && low < none);
+}
+
+#[test]
+fn include_tags() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", tags = ["wasm"] }, { label = "y" }]
+
+[[group]]
+name = "b"
+items = [{ label = "z" }]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        include_tags: vec!["wasm".to_owned()],
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(">x<"));
+    assert!(!dot.contains(">y<"));
+    assert!(!dot.contains(r#""b" ["#));
+}
+
+#[test]
+fn filters_by_status() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Complete]
+aliases = ["Done"]
+
+[status.Unassigned]
+
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Done" }, { label = "y" }]
+
+[[group]]
+name = "b"
+items = [{ label = "z", status = "Complete" }]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        hide_statuses: vec!["Complete".to_owned()],
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(!dot.contains(">x<"));
+    assert!(dot.contains(">y<"));
+    assert!(!dot.contains(r#""b" ["#));
+
+    let options = GraphvizOptions {
+        only_statuses: vec!["Done".to_owned()],
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains("x<"));
+    assert!(!dot.contains(">y<"));
+    assert!(dot.contains("z<"));
+}
+
+#[test]
+fn collapses_completed_groups() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Complete]
+completed = true
+
+[status.Unassigned]
+
+[[group]]
+name = "a"
+label = "Parser"
+items = [{ label = "x", port = "x", status = "Complete" }, { label = "y", status = "Complete" }]
+
+[[group]]
+name = "b"
+collapse = "never"
+items = [{ label = "z", status = "Complete" }]
+
+[[group]]
+name = "c"
+items = [{ label = "w", port = "w", requires = ["a:x"] }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(!dot.contains("✅"));
+
+    let options = GraphvizOptions {
+        collapse_completed: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(">✅ Parser (2/2)<"));
+    assert!(!dot.contains(">x<"));
+    assert!(dot.contains("z<"));
+    assert!(dot.contains(r#""a":all -> "c":_w_in;"#));
+    assert!(!dot.contains(r#""a":_x_out"#));
+}
+
+#[test]
+fn collapse_per_group() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Complete]
+completed = true
+
+[[group]]
+name = "a"
+collapse = "when-complete"
+items = [{ label = "x", status = "Complete" }]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(">✅ a (1/1)<"));
+    assert!(!dot.contains(">x<"));
+}
+
+#[test]
+fn dependents_of() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[group]]
+name = "b"
+items = []
+
+[[group]]
+name = "c"
+requires = ["b"]
+items = []
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        dependents_of: Some("b".to_owned()),
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(!dot.contains(r#""a" ["#));
+    assert!(dot.contains(r#""b" ["#));
+    assert!(dot.contains(r#""c" ["#));
+}
+
+#[test]
+fn critical_path() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x" }, { label = "y" }]
+
+[[group]]
+name = "b"
+items = [{ label = "z" }]
+
+[[group]]
+name = "c"
+requires = ["a", "b"]
+items = [{ label = "w" }]
+
+[[goal]]
+name = "done"
+requires = ["c"]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        critical_path: Some("done".to_owned()),
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#""a":all -> "c":all [ color = "red", penwidth = 3 ];"#));
+    assert!(dot.contains(r#""b":all -> "c":all;"#));
+    assert!(dot.contains(r#""c":all -> "done" [ color = "red", penwidth = 3 ];"#));
+
+    let options = GraphvizOptions {
+        critical_path: Some("c".to_owned()),
+        ..GraphvizOptions::default()
+    };
+    assert!(tree.to_graphviz_with(&options).is_err());
+}
+
+#[test]
+fn highlights_diff() {
+    let old = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x" }, { label = "gone" }]
+
+[[group]]
+name = "old"
+items = [{ label = "y" }]
+
+[[goal]]
+name = "beta"
+"#,
+    )
+    .unwrap();
+    let new = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Complete" }, { label = "fresh" }]
+
+[[group]]
+name = "b"
+items = [{ label = "z" }]
+"#,
+    )
+    .unwrap();
+    let options = GraphvizOptions {
+        diff: Some(old.diff(&new)),
+        ..GraphvizOptions::default()
+    };
+    let dot = new.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#"<td bgcolor="palegreen" port="all" colspan="2">b (0/1)</td>"#));
+    assert!(dot.contains(r#"bgcolor="palegreen" tooltip="Unassigned">fresh</td>"#));
+    assert!(dot.contains(r#"<td fontcolor="red" bgcolor="cornsilk" color="red" tooltip="Unassigned"><s>gone</s></td>"#));
+    assert!(dot.contains("<s>x</s> <b>(was Unassigned)</b>"));
+    assert!(dot.contains(r#"<font color="red"><s>old</s></font>"#));
+    assert!(dot.contains(r#"  style = "filled,dashed""#));
+}
+
+#[test]
+fn labels_external_requirements() {
+    let compiler = SkillTree::parse(
+        r#"
+namespace = "compiler"
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [{ label = "Expressions" }]
+"#,
+    )
+    .unwrap();
+    let tooling = SkillTree::parse(
+        r#"
+namespace = "tooling"
+
+[[group]]
+name = "ide"
+requires = ["compiler/lexer:tokens"]
+items = [{ label = "Highlighting" }]
+"#,
+    )
+    .unwrap();
+    let dot = SkillTree::merge(&[compiler, tooling])
+        .unwrap()
+        .to_graphviz()
+        .unwrap();
+    assert!(dot.contains(r#""compiler/lexer":all -> "compiler/parser":all;"#));
+    assert!(dot.contains(
+        r#""compiler/lexer":_tokens_out -> "tooling/ide":all [ style = "dotted", color = "purple", fontcolor = "purple", taillabel = "compiler" ];"#
+    ));
+}
+
+#[test]
+fn opens_links_in_new_tab() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+href = "a.html"
+details_href = "a-design.html"
+items = [{ label = "x", href = "x.html" }, { label = "y" }]
+
+[[goal]]
+name = "g"
+href = "g.html"
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(!dot.contains("_blank"));
+
+    let options = GraphvizOptions {
+        new_tab: true,
+        ..GraphvizOptions::default()
+    };
+    let dot = tree.to_graphviz_with(&options).unwrap();
+    assert!(dot.contains(r#"href="a.html" target="_blank""#));
+    assert!(dot.contains(r#"href="a-design.html" target="_blank""#));
+    assert!(dot.contains(r#"href="x.html" target="_blank""#));
+    assert!(dot.contains(r#"target = "_blank""#));
+    assert_eq!(dot.matches("_blank").count(), 4);
+}
+
+#[test]
+fn subgroups() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "frontend"
+items = [{ label = "Shell" }]
+
+[[group.group]]
+name = "forms"
+items = [{ label = "Inputs" }]
+
+[[group.group.group]]
+name = "validation"
+items = [{ label = "Rules" }]
+
+[[group]]
+name = "backend"
+requires = ["validation"]
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    let clusters: Vec<&str> = dot
+        .lines()
+        .filter(|line| line.starts_with("subgraph") || *line == "}" || line.ends_with(" ["))
+        .collect();
+    assert_eq!(
+        clusters,
+        [
+            r#"subgraph "cluster_group_frontend" {"#,
+            r#""frontend" ["#,
+            r#"subgraph "cluster_group_forms" {"#,
+            r#""forms" ["#,
+            r#""validation" ["#,
+            "}",
+            "}",
+            r#""backend" ["#,
+            "}",
+        ]
+    );
+    assert!(dot.contains(r#""validation":all -> "backend":all;"#));
+}
+
+#[test]
+fn phases() {
+    let tree = SkillTree::parse(
+        r#"
+[[phase]]
+name = "q1"
+label = "Q1 2021"
+
+[[phase]]
+name = "q2"
+bgcolor = "lightyellow"
+
+[[group]]
+name = "lexer"
+phase = "q1"
+area = "front"
+items = []
+
+[[group.group]]
+name = "tokens"
+items = []
+
+[[group]]
+name = "parser"
+phase = "q2"
+area = "front"
+requires = ["lexer"]
+items = []
+
+[[group]]
+name = "docs"
+items = []
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    let structure: Vec<&str> = dot
+        .lines()
+        .filter(|line| line.starts_with("subgraph") || line.ends_with(" [") || *line == "}")
+        .collect();
+    assert_eq!(
+        structure,
+        [
+            r#""docs" ["#,
+            r#"subgraph "cluster_phase_q1" {"#,
+            r#"subgraph "cluster_q1/front" {"#,
+            r#"subgraph "cluster_group_lexer" {"#,
+            r#""lexer" ["#,
+            r#""tokens" ["#,
+            "}",
+            "}",
+            "}",
+            r#"subgraph "cluster_phase_q2" {"#,
+            r#"subgraph "cluster_q2/front" {"#,
+            r#""parser" ["#,
+            "}",
+            "}",
+            "}",
+        ]
+    );
+    assert!(dot.contains(r#"  label = "Q1 2021""#));
+    assert!(dot.contains(r#"  fillcolor = "lightyellow""#));
+    assert!(dot.contains(r#""lexer" -> "parser" [ style = "invis" ];"#));
+    assert!(dot.contains(r#""tokens" -> "parser" [ style = "invis" ];"#));
+    assert!(!dot.contains(r#""docs" -> "#));
+}
+
+#[test]
+fn hides_archived() {
+    let mut tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens" }, { label = "Old tokens", archived = true }]
+
+[[group]]
+name = "old-parser"
+archived = true
+requires = ["lexer"]
+items = []
+
+[[goal]]
+name = "v0"
+archived = true
+requires = ["old-parser"]
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains("Tokens"));
+    assert!(!dot.contains("Old tokens"));
+    assert!(!dot.contains("old-parser"));
+    assert!(!dot.contains("v0"));
+
+    tree.include_archived = true;
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains("Old tokens"));
+    assert!(dot.contains(r#""lexer":all -> "old-parser":all;"#));
+    assert!(dot.contains("v0"));
+}
+
+#[test]
+fn escapes_backslashes_in_quoted_strings() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x" }]
+
+[[goal]]
+name = "done"
+description = 'path C:\'
+requires = [{ on = "a", label = 'say "hi" \o/' }]
+graphviz_attrs = { comment = 'C:\"' }
+"#,
+    )
+    .unwrap();
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"tooltip = "path C:\\""#));
+    assert!(dot.contains(r#"label = "say \"hi\" \\o/""#));
+    assert!(dot.contains(r#"comment = "C:\\\"""#));
+}
+
+#[test]
+fn parse_and_render() {
+    let text = r#"
+[[group]]
+name = "a"
+items = [{ label = "late", due = "2000-01-01" }]
+
+[[goal]]
+name = "done"
+requires = ["a"]
+"#;
+    let dot = crate::parse_and_render(text).unwrap();
+    assert_eq!(dot, SkillTree::parse(text).unwrap().to_graphviz().unwrap());
+    // Nothing depends on today's date.
+    assert!(!dot.contains("mistyrose"));
+
+    match crate::parse_and_render("[[goal]]\nname = \"done\"\nrequires = [\"b\"]\n") {
+        Err(Error::UnknownReference { .. }) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn parse_and_render_js() {
+    let text = "[[group]]\nname = \"a\"\nitems = []\n";
+    assert_eq!(
+        crate::parse_and_render_js(text).unwrap(),
+        crate::parse_and_render(text).unwrap()
+    );
+}
@@ -0,0 +1,21 @@
+//! Converting between skill-trees and the formats of other tools that
+//! track work.
+
+pub mod github_project;
+pub mod jira;
+
+/// Turns a column or issue key into something usable as a group name or
+/// port, such as `in-progress` for `In Progress` with a `-` separator.
+/// Text without any letters or digits is kept as it is.
+fn slug(text: &str, separator: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        text.to_owned()
+    } else {
+        words.join(separator)
+    }
+}
@@ -0,0 +1,124 @@
+//! Imports a GitHub Projects board, as exported by
+//! `gh project item-list <number> --owner <owner> --format json`.
+
+use super::slug;
+use crate::builder::{GroupBuilder, ItemBuilder};
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::throws;
+use serde_derive::Deserialize;
+
+/// The column that a board puts finished cards in.
+const DONE_COLUMN: &str = "Done";
+
+/// What the groups of an imported tree are made from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The board's columns, i.e. the values of its `Status` field.
+    Status,
+    /// The values of its `Iteration` field.
+    Iteration,
+}
+
+#[derive(Deserialize)]
+struct Export {
+    items: Vec<ExportItem>,
+}
+
+#[derive(Deserialize)]
+struct ExportItem {
+    title: String,
+    #[serde(default)]
+    content: Option<Content>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    iteration: Option<Iteration>,
+    #[serde(default)]
+    assignees: Vec<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    /// Missing for draft issues.
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Iteration {
+    title: String,
+}
+
+/// Converts a board export into a skill-tree with a group per column or
+/// iteration, in the order they first appear, and an item per card. Cards
+/// link to their issue or pull request and are tagged with its labels.
+/// Cards in the `Done` column are `Complete`; others are `Assigned` if
+/// anyone is assigned to them and `Unassigned` otherwise. Cards without a
+/// column or iteration go in a group of their own, as on the board.
+#[throws(Error)]
+pub fn import(json: &str, group_by: GroupBy) -> SkillTree {
+    let export: Export = serde_json::from_str(json)?;
+
+    let mut groups: Vec<(String, Vec<ItemBuilder>)> = vec![];
+    for card in export.items {
+        let column = match group_by {
+            GroupBy::Status => card
+                .status
+                .clone()
+                .unwrap_or_else(|| "No Status".to_owned()),
+            GroupBy::Iteration => card
+                .iteration
+                .as_ref()
+                .map_or_else(|| "No Iteration".to_owned(), |it| it.title.clone()),
+        };
+        let index = match groups.iter().position(|(name, _)| *name == column) {
+            Some(index) => index,
+            None => {
+                groups.push((column, vec![]));
+                groups.len() - 1
+            }
+        };
+        groups[index].1.push(card_item(card));
+    }
+
+    let mut builder = SkillTree::builder();
+    for (column, items) in groups {
+        let group = items.into_iter().fold(
+            GroupBuilder::new(slug(&column, "-")).label(column),
+            GroupBuilder::item,
+        );
+        builder = builder.group(group);
+    }
+    builder.build()
+}
+
+fn card_item(card: ExportItem) -> ItemBuilder {
+    let status = if card.status.as_deref() == Some(DONE_COLUMN) {
+        "Complete"
+    } else if card.assignees.is_empty() {
+        "Unassigned"
+    } else {
+        "Assigned"
+    };
+    let mut item = ItemBuilder::new(card.title).status(status);
+    if let Some(content) = card.content {
+        if let Some(url) = content.url {
+            item = item.href(url);
+        }
+        if let Some(body) = content.body.filter(|body| !body.trim().is_empty()) {
+            item = item.description(body);
+        }
+    }
+    if !card.assignees.is_empty() {
+        item = item.owner(card.assignees.join(", "));
+    }
+    card.labels.into_iter().fold(item, ItemBuilder::tag)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,110 @@
+use super::{import, GroupBy};
+
+const EXPORT: &str = r#"{
+  "items": [
+    {
+      "assignees": ["octocat"],
+      "content": {
+        "body": "Tokens and spans",
+        "number": 1,
+        "repository": "o/r",
+        "title": "Lexer",
+        "type": "Issue",
+        "url": "https://github.com/o/r/issues/1"
+      },
+      "id": "PVTI_1",
+      "labels": ["frontend"],
+      "repository": "https://github.com/o/r",
+      "status": "Done",
+      "title": "Lexer",
+      "iteration": { "title": "Iteration 1", "startDate": "2024-01-01", "duration": 14 }
+    },
+    {
+      "assignees": ["hubot"],
+      "content": { "body": "", "title": "Parser", "type": "DraftIssue" },
+      "id": "PVTI_2",
+      "status": "In Progress",
+      "title": "Parser"
+    },
+    {
+      "content": { "number": 3, "title": "Codegen", "type": "Issue", "url": "https://github.com/o/r/issues/3" },
+      "id": "PVTI_3",
+      "title": "Codegen",
+      "iteration": { "title": "Iteration 1", "startDate": "2024-01-01", "duration": 14 }
+    },
+    {
+      "content": { "number": 4, "title": "Docs", "type": "Issue", "url": "https://github.com/o/r/issues/4" },
+      "id": "PVTI_4",
+      "status": "Done",
+      "title": "Docs"
+    }
+  ],
+  "totalCount": 4
+}"#;
+
+#[test]
+fn groups_by_status() {
+    let tree = import(EXPORT, GroupBy::Status).unwrap();
+    tree.validate().unwrap();
+    let groups: Vec<(&str, Option<&str>, usize)> = tree
+        .groups()
+        .map(|group| {
+            (
+                group.name.as_str(),
+                group.label.as_deref(),
+                group.items.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("done", Some("Done"), 2),
+            ("in-progress", Some("In Progress"), 1),
+            ("no-status", Some("No Status"), 1),
+        ]
+    );
+
+    let lexer = &tree.group[0].items[0];
+    assert_eq!(lexer.label, "Lexer");
+    assert_eq!(
+        lexer.href.as_deref(),
+        Some("https://github.com/o/r/issues/1")
+    );
+    assert_eq!(lexer.description.as_deref(), Some("Tokens and spans"));
+    assert_eq!(lexer.owner.as_deref(), Some("octocat"));
+    assert_eq!(lexer.tags, ["frontend"]);
+    assert_eq!(lexer.status.as_deref(), Some("Complete"));
+
+    let parser = &tree.group[1].items[0];
+    assert_eq!(parser.href, None);
+    assert_eq!(parser.description, None);
+    assert_eq!(parser.status.as_deref(), Some("Assigned"));
+    assert_eq!(tree.group[2].items[0].status.as_deref(), Some("Unassigned"));
+}
+
+#[test]
+fn groups_by_iteration() {
+    let tree = import(EXPORT, GroupBy::Iteration).unwrap();
+    let groups: Vec<(&str, Vec<&str>)> = tree
+        .groups()
+        .map(|group| {
+            let items = group.items().map(|item| item.label.as_str()).collect();
+            (group.name.as_str(), items)
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("iteration-1", vec!["Lexer", "Codegen"]),
+            ("no-iteration", vec!["Parser", "Docs"]),
+        ]
+    );
+    // Statuses still come from the column.
+    assert_eq!(tree.group[1].items[1].status.as_deref(), Some("Complete"));
+}
+
+#[test]
+fn rejects_other_json() {
+    assert!(import(r#"{ "cards": [] }"#, GroupBy::Status).is_err());
+}
@@ -0,0 +1,484 @@
+//! Imports Jira issue CSV exports, and exports skill-trees as CSV for
+//! Jira's CSV importer.
+
+use super::slug;
+use crate::builder::{GroupBuilder, ItemBuilder};
+use crate::csv;
+use crate::error::Error;
+use crate::tree::{split_requirement, Item, Requirement, SkillTree};
+use fehler::{throw, throws};
+use std::collections::{HashMap, HashSet};
+
+/// Columns naming an issue's parent (its epic), in the order they are tried
+/// when grouping by parent.
+const PARENT_COLUMNS: &[&str] = &["Parent", "Parent id", "Epic Link"];
+
+/// The column listing the issues that block an issue, repeated once per link.
+const BLOCKED_BY_COLUMN: &str = "Inward issue link (Blocks)";
+/// The column listing the issues that an issue blocks.
+const BLOCKS_COLUMN: &str = "Outward issue link (Blocks)";
+
+/// Jira's priorities, from most to least urgent, with the item priority
+/// each corresponds to.
+const PRIORITIES: &[(&str, &str)] = &[
+    ("Highest", "P0"),
+    ("High", "P1"),
+    ("Medium", "P2"),
+    ("Low", "P3"),
+    ("Lowest", "P3"),
+];
+
+/// The group for issues that aren't grouped under anything.
+const UNGROUPED: &str = "Ungrouped";
+
+/// How `import` arranges issues.
+#[derive(Clone, Debug, Default)]
+pub struct ImportOptions {
+    /// The column to group issues by, such as `Sprint` or `Component/s`.
+    /// By default issues are grouped by their parent (epic), which becomes
+    /// the group instead of an item.
+    pub group_by: Option<String>,
+    /// The Jira site, such as `https://example.atlassian.net`, for linking
+    /// items to their issues.
+    pub base_url: Option<String>,
+}
+
+/// The rows of an export. Columns are matched ignoring case, and may be
+/// repeated, as `Labels` is.
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// The non-empty values of every column called `name` in `row`.
+    fn values<'a>(&'a self, row: &'a [String], name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.header
+            .iter()
+            .zip(row)
+            .filter(move |(column, _)| column.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn value<'a>(&'a self, row: &'a [String], name: &'a str) -> Option<&'a str> {
+        self.values(row, name).next()
+    }
+
+    fn has_column(&self, name: &str) -> bool {
+        self.header
+            .iter()
+            .any(|column| column.trim().eq_ignore_ascii_case(name))
+    }
+}
+
+/// An issue, as a row of an export.
+struct Issue<'a> {
+    row: &'a [String],
+    key: Option<&'a str>,
+    id: Option<&'a str>,
+    summary: &'a str,
+    /// What it is grouped by: its parent's key or id, by default.
+    group: Option<&'a str>,
+    blocked_by: Vec<&'a str>,
+    blocks: Vec<&'a str>,
+}
+
+impl<'a> Issue<'a> {
+    fn new(table: &'a Table, row: &'a [String], options: &'a ImportOptions) -> Issue<'a> {
+        let group = match &options.group_by {
+            Some(column) => table.value(row, column),
+            None => PARENT_COLUMNS
+                .iter()
+                .find_map(|column| table.value(row, column)),
+        };
+        Issue {
+            row,
+            key: table.value(row, "Issue key"),
+            id: table.value(row, "Issue id"),
+            summary: table.value(row, "Summary").unwrap_or_default(),
+            group,
+            blocked_by: table.values(row, BLOCKED_BY_COLUMN).collect(),
+            blocks: table.values(row, BLOCKS_COLUMN).collect(),
+        }
+    }
+
+    /// How other issues refer to this one.
+    fn name(&self) -> &'a str {
+        self.key.or(self.id).unwrap_or(self.summary)
+    }
+}
+
+/// Converts a Jira issue CSV export into a skill-tree with a group per
+/// epic (or per value of `options.group_by`), in the order they first
+/// appear, and an item per issue. Issues in the `Done` status category are
+/// `Complete`, those in progress `Assigned`, and others `Assigned` or
+/// `Unassigned` depending on whether anyone is assigned to them; issues
+/// with the `Blocked` status are `Blocked`. "Blocks" links between issues in
+/// different groups become requirements, and labels, priorities, and story
+/// points carry over.
+#[throws(Error)]
+pub fn import(text: &str, options: &ImportOptions) -> SkillTree {
+    let mut records = csv::parse(text).into_iter();
+    let table = Table {
+        header: records.next().unwrap_or_default(),
+        rows: records.collect(),
+    };
+    if !table.has_column("Summary") {
+        throw!(Error::Parse {
+            path: None,
+            message: "not a Jira export: there is no `Summary` column".to_owned(),
+            line_col: None,
+        });
+    }
+
+    let issues: Vec<Issue> = table
+        .rows
+        .iter()
+        .map(|row| Issue::new(&table, row, options))
+        .collect();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, issue) in issues.iter().enumerate() {
+        for name in issue.key.iter().chain(&issue.id) {
+            index.entry(name).or_insert(i);
+        }
+        index.entry(issue.name()).or_insert(i);
+    }
+
+    // Issues that others are grouped under become those groups; the rest
+    // are items, in groups listed in the order they first appear.
+    let parents: HashSet<usize> = issues
+        .iter()
+        .filter_map(|issue| index.get(issue.group?).copied())
+        .collect();
+    let mut groups: Vec<(Option<usize>, Option<&str>, Vec<usize>)> = vec![];
+    for (i, issue) in issues.iter().enumerate() {
+        if parents.contains(&i) {
+            continue;
+        }
+        let parent = issue.group.and_then(|group| index.get(group).copied());
+        let value = parent.map_or(issue.group, |parent| Some(issues[parent].name()));
+        match groups.iter_mut().find(|(_, v, _)| *v == value) {
+            Some((_, _, items)) => items.push(i),
+            None => groups.push((parent, value, vec![i])),
+        }
+    }
+
+    // Where each issue ended up, as a requirement on it would say.
+    let group_name = |parent: Option<usize>, value: Option<&str>| match (parent, value) {
+        (Some(parent), _) => slug(issues[parent].summary, "-"),
+        (None, Some(value)) => slug(value, "-"),
+        (None, None) => slug(UNGROUPED, "-"),
+    };
+    let mut location: HashMap<usize, String> = HashMap::new();
+    for (parent, value, items) in &groups {
+        let name = group_name(*parent, *value);
+        for &i in items {
+            location.insert(i, format!("{}:{}", name, slug(issues[i].name(), "_")));
+        }
+        if let Some(parent) = parent {
+            location.insert(*parent, name);
+        }
+    }
+
+    let mut requires: Vec<Vec<&str>> = vec![vec![]; issues.len()];
+    let mut link = |blocker: usize, blocked: usize| {
+        let on = match (location.get(&blocker), location.get(&blocked)) {
+            (Some(on), Some(of)) if split_requirement(on).0 != split_requirement(of).0 => on,
+            // Unknown issues, and links within a group, which can't require itself.
+            _ => return,
+        };
+        if !requires[blocked].contains(&on.as_str()) {
+            requires[blocked].push(on);
+        }
+    };
+    for (i, issue) in issues.iter().enumerate() {
+        for name in &issue.blocked_by {
+            if let Some(&blocker) = index.get(name) {
+                link(blocker, i);
+            }
+        }
+        for name in &issue.blocks {
+            if let Some(&blocked) = index.get(name) {
+                link(i, blocked);
+            }
+        }
+    }
+
+    let mut builder = SkillTree::builder();
+    for (parent, value, items) in groups {
+        let name = group_name(parent, value);
+        let mut group = GroupBuilder::new(name);
+        match parent {
+            Some(parent) => {
+                group = group.label(issues[parent].summary);
+                if let Some(href) = issue_href(&issues[parent], options) {
+                    group = group.href(href);
+                }
+                for &on in &requires[parent] {
+                    group = group.requires(on);
+                }
+            }
+            None => group = group.label(value.unwrap_or(UNGROUPED)),
+        }
+        for i in items {
+            group = group.item(issue_item(&table, &issues[i], &requires[i], options));
+        }
+        builder = builder.group(group);
+    }
+    builder.build()
+}
+
+fn issue_item(
+    table: &Table,
+    issue: &Issue,
+    requires: &[&str],
+    options: &ImportOptions,
+) -> ItemBuilder {
+    let row = issue.row;
+    let mut item = ItemBuilder::new(issue.summary)
+        .port(slug(issue.name(), "_"))
+        .status(issue_status(table, row));
+    if let Some(href) = issue_href(issue, options) {
+        item = item.href(href);
+    }
+    if let Some(description) = table.value(row, "Description") {
+        item = item.description(description);
+    }
+    if let Some(assignee) = table.value(row, "Assignee") {
+        item = item.owner(assignee);
+    }
+    let priority = table.value(row, "Priority").and_then(|priority| {
+        PRIORITIES
+            .iter()
+            .find(|(jira, _)| jira.eq_ignore_ascii_case(priority))
+    });
+    if let Some((_, priority)) = priority {
+        item = item.priority(*priority);
+    }
+    let story_points = table
+        .value(row, "Story Points")
+        .or_else(|| table.value(row, "Custom field (Story Points)"));
+    if let Some(effort) = story_points.and_then(|points| points.parse().ok()) {
+        item = item.effort(effort);
+    }
+    for label in table.values(row, "Labels") {
+        item = item.tag(label);
+    }
+    requires.iter().fold(item, |item, &on| item.requires(on))
+}
+
+fn issue_status(table: &Table, row: &[String]) -> &'static str {
+    let status = table.value(row, "Status").unwrap_or_default();
+    if status.eq_ignore_ascii_case("Blocked") {
+        return "Blocked";
+    }
+    let category = table.value(row, "Status Category").unwrap_or(status);
+    match category.to_lowercase().as_str() {
+        "done" | "closed" | "resolved" => "Complete",
+        "in progress" | "in review" => "Assigned",
+        _ if table.value(row, "Assignee").is_some() => "Assigned",
+        _ => "Unassigned",
+    }
+}
+
+/// The link to `issue` on the Jira site, if there is a site and the issue
+/// has a key.
+fn issue_href(issue: &Issue, options: &ImportOptions) -> Option<String> {
+    let base_url = options.base_url.as_deref()?;
+    Some(format!(
+        "{}/browse/{}",
+        base_url.trim_end_matches('/'),
+        issue.key?
+    ))
+}
+
+/// A row of an export, before the repeated columns are laid out.
+struct Row<'t> {
+    issue_type: &'static str,
+    summary: &'t str,
+    description: Option<&'t str>,
+    status: &'static str,
+    assignee: Option<&'t str>,
+    priority: Option<&'static str>,
+    story_points: Option<f64>,
+    parent: Option<usize>,
+    labels: &'t [String],
+    requires: Vec<&'t Requirement>,
+}
+
+/// Writes the tree as CSV for Jira's CSV importer: an epic per group and
+/// goal, and a task under it for each of their items. Issues are numbered
+/// from 1 in the `Issue Id` column, which `Parent id` and the "Blocks" link
+/// columns refer to. Soft requirements and alternatives are left out, since
+/// Jira has nothing like them.
+pub fn export(tree: &SkillTree) -> String {
+    let resolved = tree.with_resolved_requirements();
+    let tree = &resolved;
+    // The number of each group and goal, and of each item with a port, by
+    // how requirements refer to them.
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut rows: Vec<Row> = vec![];
+    for group in tree.groups() {
+        let (completed, total) = tree.group_progress(group);
+        rows.push(epic_row(
+            group.label.as_ref().unwrap_or(&group.name),
+            total > 0 && completed == total,
+            &group.tags,
+            group.all_requirements(),
+        ));
+        ids.insert(group.name.clone(), rows.len());
+        let parent = rows.len();
+        for (item, port) in group.items().zip(group.ports()) {
+            rows.push(task_row(tree, item, tree.item_status(group, item), parent));
+            ids.insert(format!("{}:{}", group.name, port), rows.len());
+        }
+    }
+    for goal in tree.goals() {
+        let completed = match &goal.status {
+            Some(status) => tree.is_completed(Some(status)),
+            None => {
+                !goal.items.is_empty()
+                    && goal
+                        .items()
+                        .all(|item| tree.is_completed(tree.goal_item_status(item)))
+            }
+        };
+        rows.push(epic_row(
+            goal.label.as_ref().unwrap_or(&goal.name),
+            completed,
+            &[],
+            goal.all_requirements(),
+        ));
+        ids.insert(goal.name.clone(), rows.len());
+        let parent = rows.len();
+        for item in goal.items() {
+            rows.push(task_row(tree, item, tree.goal_item_status(item), parent));
+        }
+    }
+
+    let links: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut links: Vec<String> = vec![];
+            for requirement in &row.requires {
+                if let Some(id) = ids.get(&requirement.on) {
+                    let id = id.to_string();
+                    if !links.contains(&id) {
+                        links.push(id);
+                    }
+                }
+            }
+            links
+        })
+        .collect();
+    let label_columns = rows.iter().map(|row| row.labels.len()).max().unwrap_or(0);
+    let link_columns = links.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut output = String::new();
+    let mut header: Vec<String> = [
+        "Issue Id",
+        "Issue Type",
+        "Summary",
+        "Description",
+        "Status",
+        "Assignee",
+        "Priority",
+        "Story Points",
+        "Parent id",
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect();
+    header.extend(vec!["Labels".to_owned(); label_columns]);
+    header.extend(vec![BLOCKED_BY_COLUMN.to_owned(); link_columns]);
+    csv::write_record(&mut output, &header);
+
+    for (index, (row, links)) in rows.iter().zip(links).enumerate() {
+        let mut fields = vec![
+            (index + 1).to_string(),
+            row.issue_type.to_owned(),
+            row.summary.to_owned(),
+            row.description.unwrap_or_default().to_owned(),
+            row.status.to_owned(),
+            row.assignee.unwrap_or_default().to_owned(),
+            row.priority.unwrap_or_default().to_owned(),
+            row.story_points
+                .map(|points| points.to_string())
+                .unwrap_or_default(),
+            row.parent
+                .map(|parent| parent.to_string())
+                .unwrap_or_default(),
+        ];
+        let labels = row
+            .labels
+            .iter()
+            .cloned()
+            .chain(std::iter::repeat(String::new()));
+        fields.extend(labels.take(label_columns));
+        let links = links.into_iter().chain(std::iter::repeat(String::new()));
+        fields.extend(links.take(link_columns));
+        csv::write_record(&mut output, &fields);
+    }
+    output
+}
+
+fn epic_row<'t>(
+    summary: &'t str,
+    completed: bool,
+    labels: &'t [String],
+    requires: impl Iterator<Item = &'t Requirement>,
+) -> Row<'t> {
+    Row {
+        issue_type: "Epic",
+        summary,
+        description: None,
+        status: if completed { "Done" } else { "To Do" },
+        assignee: None,
+        priority: None,
+        story_points: None,
+        parent: None,
+        labels,
+        requires: requires.filter(|requirement| !requirement.soft).collect(),
+    }
+}
+
+fn task_row<'t>(
+    tree: &'t SkillTree,
+    item: &'t Item,
+    status: Option<&String>,
+    parent: usize,
+) -> Row<'t> {
+    let style = status.and_then(|status| tree.status_style(status));
+    let status = match style {
+        Some(style) if style.completed => "Done",
+        Some(style) if style.in_progress => "In Progress",
+        _ => "To Do",
+    };
+    let priority = item.priority.as_deref().and_then(|priority| {
+        PRIORITIES
+            .iter()
+            .find(|(_, ours)| *ours == priority)
+            .map(|(jira, _)| *jira)
+    });
+    Row {
+        issue_type: "Task",
+        summary: &item.label,
+        description: item.description.as_deref(),
+        status,
+        assignee: item.owner.as_deref(),
+        priority,
+        story_points: item.effort,
+        parent: Some(parent),
+        labels: &item.tags,
+        requires: item
+            .requires
+            .iter()
+            .flatten()
+            .filter(|requirement| !requirement.soft)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,136 @@
+use super::{export, import, ImportOptions};
+use crate::SkillTree;
+
+const EXPORT: &str = "\u{feff}Summary,Issue key,Issue id,Issue Type,Status,Status Category,Assignee,Priority,Labels,Labels,Custom field (Story Points),Parent,Inward issue link (Blocks),Outward issue link (Blocks),Description\r
+Frontend,CC-1,10001,Epic,In Progress,In Progress,,,,,,,,,\r
+Lexer,CC-2,10002,Story,Done,Done,alice,High,syntax,,3,CC-1,,CC-3,\r
+Parser,CC-3,10003,Story,In Review,In Progress,bob,Medium,syntax,grammar,5,CC-1,,,\"Expressions, then \"\"statements\"\"\nand items\"\r
+Codegen,CC-4,10004,Task,To Do,To Do,,Lowest,,,,,CC-1,,\r
+Docs,CC-5,10005,Task,Blocked,To Do,carol,,,,,,CC-3,,\r
+";
+
+#[test]
+fn imports_issues() {
+    let options = ImportOptions {
+        base_url: Some("https://example.atlassian.net/".to_owned()),
+        ..ImportOptions::default()
+    };
+    let tree = import(EXPORT, &options).unwrap();
+    tree.validate().unwrap();
+
+    let groups: Vec<(&str, Option<&str>, Vec<&str>)> = tree
+        .groups()
+        .map(|group| {
+            let items = group.items().map(|item| item.label.as_str()).collect();
+            (group.name.as_str(), group.label.as_deref(), items)
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("frontend", Some("Frontend"), vec!["Lexer", "Parser"]),
+            ("ungrouped", Some("Ungrouped"), vec!["Codegen", "Docs"]),
+        ]
+    );
+
+    let frontend = &tree.group[0];
+    assert_eq!(
+        frontend.href.as_deref(),
+        Some("https://example.atlassian.net/browse/CC-1")
+    );
+    let lexer = &frontend.items[0];
+    assert_eq!(lexer.port.as_deref(), Some("cc_2"));
+    assert_eq!(lexer.status.as_deref(), Some("Complete"));
+    assert_eq!(lexer.owner.as_deref(), Some("alice"));
+    assert_eq!(lexer.priority.as_deref(), Some("P1"));
+    assert_eq!(lexer.effort, Some(3.0));
+    assert_eq!(lexer.tags, ["syntax"]);
+
+    let parser = &frontend.items[1];
+    assert_eq!(parser.status.as_deref(), Some("Assigned"));
+    assert_eq!(parser.tags, ["syntax", "grammar"]);
+    assert_eq!(
+        parser.description.as_deref(),
+        Some("Expressions, then \"statements\"\nand items")
+    );
+    // CC-2 blocks CC-3, but both are in the same group.
+    assert_eq!(parser.requires, None);
+
+    let codegen = &tree.group[1].items[0];
+    assert_eq!(codegen.status.as_deref(), Some("Unassigned"));
+    assert_eq!(codegen.priority.as_deref(), Some("P3"));
+    // A link to an epic is a requirement on its group.
+    assert_eq!(codegen.requires.as_ref().unwrap()[0].on, "frontend");
+    let docs = &tree.group[1].items[1];
+    assert_eq!(docs.status.as_deref(), Some("Blocked"));
+    assert_eq!(docs.requires.as_ref().unwrap()[0].on, "frontend:cc_3");
+}
+
+#[test]
+fn groups_by_column() {
+    let options = ImportOptions {
+        group_by: Some("Issue Type".to_owned()),
+        ..ImportOptions::default()
+    };
+    let tree = import(EXPORT, &options).unwrap();
+    let groups: Vec<&str> = tree.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["epic", "story", "task"]);
+    assert_eq!(tree.group[0].href, None);
+
+    assert!(import("Key,Title\r\nA,B\r\n", &ImportOptions::default()).is_err());
+}
+
+#[test]
+fn exports_and_imports_back() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens", status = "Complete", owner = "alice", tags = ["a", "b"] }]
+
+[[group]]
+name = "parser"
+requires = ["lexer"]
+items = [
+    { label = "Exprs, stmts", port = "exprs", requires = ["lexer:tokens", "docs?"], priority = "P0", effort = 2.5 },
+    { label = "Items", status = "Assigned" },
+]
+
+[[group]]
+name = "docs"
+items = [{ label = "Guide" }]
+
+[[goal]]
+name = "ship"
+requires = ["parser"]
+"#,
+    )
+    .unwrap();
+    let csv = export(&tree);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "Issue Id,Issue Type,Summary,Description,Status,Assignee,Priority,Story Points,Parent id,Labels,Labels,Inward issue link (Blocks)"
+    );
+    assert_eq!(lines[1], "1,Epic,lexer,,Done,,,,,,,");
+    assert_eq!(lines[2], "2,Task,Tokens,,Done,alice,,,1,a,b,");
+    assert_eq!(lines[3], "3,Epic,parser,,To Do,,,,,,,1");
+    assert_eq!(
+        lines[4],
+        "4,Task,\"Exprs, stmts\",,To Do,,Highest,2.5,3,,,2"
+    );
+    assert_eq!(lines[5], "5,Task,Items,,In Progress,,,,3,,,");
+    assert_eq!(lines[8], "8,Epic,ship,,To Do,,,,,,,3");
+    assert_eq!(lines.len(), 9);
+
+    let imported = import(&csv, &ImportOptions::default()).unwrap();
+    imported.validate().unwrap();
+    let groups: Vec<&str> = imported.groups().map(|group| group.name.as_str()).collect();
+    // The goal has no items, so nothing is grouped under it.
+    assert_eq!(groups, ["lexer", "parser", "docs", "ungrouped"]);
+    assert_eq!(imported.group[1].requires.as_ref().unwrap()[0].on, "lexer");
+    let exprs = &imported.group[1].items[0];
+    assert_eq!(exprs.requires.as_ref().unwrap()[0].on, "lexer:2");
+    assert_eq!(exprs.priority.as_deref(), Some("P0"));
+    assert_eq!(exprs.effort, Some(2.5));
+}
@@ -0,0 +1,432 @@
+//! A small layered layout engine that renders a skill-tree straight to SVG,
+//! without needing graphviz to be installed.
+//!
+//! Nodes are assigned to columns by the length of their longest chain of
+//! requirements (so everything flows left to right, like `rankdir = "LR"`),
+//! ordered within each column by the average position of their
+//! prerequisites, and connected with curved edges.
+
+use crate::error::Error;
+use crate::tree::{split_requirement, Goal, Group, ItemIndex, Requirement, SkillTree, StatusStyle};
+use fehler::throws;
+use std::collections::HashMap;
+use std::io::Write;
+use svg::node::element::{Definitions, Link, Marker, Path, Rectangle, Text, Title};
+use svg::node::Text as TextNode;
+use svg::{Document, Node as _};
+
+const FONT_SIZE: f64 = 14.0;
+const CHAR_WIDTH: f64 = 8.0;
+const ROW_HEIGHT: f64 = 24.0;
+const CELL_PADDING: f64 = 6.0;
+const EMOJI_WIDTH: f64 = 28.0;
+const MIN_NODE_WIDTH: f64 = 80.0;
+const COLUMN_GAP: f64 = 80.0;
+const NODE_GAP: f64 = 30.0;
+const MARGIN: f64 = 10.0;
+
+impl SkillTree {
+    /// Writes an SVG rendering of this skill-tree to the given output, using
+    /// the built-in layout engine rather than graphviz.
+    #[throws(Error)]
+    pub fn write_svg(&self, output: &mut dyn Write) {
+        svg::write(output, &render_document(self))?;
+    }
+
+    /// Generates a string containing an SVG rendering of this skill-tree,
+    /// using the built-in layout engine rather than graphviz.
+    pub fn render_svg(&self) -> String {
+        render_document(self).to_string()
+    }
+}
+
+enum NodeKind<'t> {
+    Group(&'t Group),
+    Goal(&'t Goal),
+}
+
+struct Node<'t> {
+    name: &'t str,
+    kind: NodeKind<'t>,
+    requires: Vec<&'t str>,
+    width: f64,
+    height: f64,
+    x: f64,
+    y: f64,
+}
+
+impl Node<'_> {
+    /// Vertical position of the row for `port`, or of the node's center.
+    fn port_y(&self, port: Option<&str>) -> f64 {
+        if let (NodeKind::Group(group), Some(port)) = (&self.kind, port) {
+            if let Some(ItemIndex(index)) = group.item_with_port(port) {
+                return self.y + ROW_HEIGHT * (index as f64 + 1.5);
+            }
+        }
+        self.y + self.height / 2.0
+    }
+}
+
+fn render_document(tree: &SkillTree) -> Document {
+    let resolved = tree.visible().with_resolved_requirements();
+    let tree = &resolved;
+    let mut nodes = measure_nodes(tree);
+    let index_of: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.name, index))
+        .collect();
+
+    let columns = assign_columns(&nodes, &index_of);
+    let (width, height) = position_nodes(&mut nodes, &index_of, &columns);
+
+    let mut document = Document::new()
+        .set("viewBox", (0.0, 0.0, width, height))
+        .set("width", width)
+        .set("height", height)
+        .set("font-family", "sans-serif")
+        .set("font-size", FONT_SIZE)
+        .add(
+            Definitions::new().add(
+                Marker::new()
+                    .set("id", "arrow")
+                    .set("viewBox", (0, 0, 10, 10))
+                    .set("refX", 10)
+                    .set("refY", 5)
+                    .set("markerWidth", 8)
+                    .set("markerHeight", 8)
+                    .set("orient", "auto")
+                    .add(Path::new().set("d", "M 0 0 L 10 5 L 0 10 z")),
+            ),
+        );
+
+    for group in tree.groups() {
+        let target = &nodes[index_of[group.name.as_str()]];
+        for requirement in group.all_requirements() {
+            if let Some(edge) = edge(&nodes, &index_of, requirement, target, None) {
+                document.append(edge);
+            }
+        }
+        for (item, port) in group.items().zip(group.ports()) {
+            for requirement in item.all_requirements() {
+                let port = Some(port.as_str());
+                if let Some(edge) = edge(&nodes, &index_of, requirement, target, port) {
+                    document.append(edge);
+                }
+            }
+        }
+    }
+
+    for goal in tree.goals() {
+        let target = &nodes[index_of[goal.name.as_str()]];
+        for requirement in goal.all_requirements() {
+            if let Some(edge) = edge(&nodes, &index_of, requirement, target, None) {
+                document.append(edge);
+            }
+        }
+    }
+
+    for node in &nodes {
+        match node.kind {
+            NodeKind::Group(group) => draw_group(tree, group, node, &mut document),
+            NodeKind::Goal(goal) => draw_goal(tree, goal, node, &mut document),
+        }
+    }
+
+    document
+}
+
+fn measure_nodes(tree: &SkillTree) -> Vec<Node<'_>> {
+    let mut nodes = Vec::new();
+
+    for group in tree.groups() {
+        let label = group.label.as_ref().unwrap_or(&group.name);
+        let header_width = text_width(label) + 2.0 * CELL_PADDING;
+        let items_width = group
+            .items()
+            .map(|item| EMOJI_WIDTH + text_width(&item.label_text()) + 2.0 * CELL_PADDING)
+            .fold(0.0, f64::max);
+
+        let mut requires: Vec<&str> = group
+            .all_requirements()
+            .filter(|r| !r.soft)
+            .map(|r| split_requirement(&r.on).0)
+            .collect();
+        for item in group.items() {
+            requires.extend(
+                item.all_requirements()
+                    .filter(|r| !r.soft)
+                    .map(|r| split_requirement(&r.on).0),
+            );
+        }
+
+        nodes.push(Node {
+            name: &group.name,
+            kind: NodeKind::Group(group),
+            requires,
+            width: header_width.max(items_width).max(MIN_NODE_WIDTH),
+            height: ROW_HEIGHT * (group.items.len() as f64 + 1.0),
+            x: 0.0,
+            y: 0.0,
+        });
+    }
+
+    for goal in tree.goals() {
+        let label = goal.label.as_ref().unwrap_or(&goal.name);
+        nodes.push(Node {
+            name: &goal.name,
+            kind: NodeKind::Goal(goal),
+            requires: goal
+                .all_requirements()
+                .filter(|r| !r.soft)
+                .map(|r| split_requirement(&r.on).0)
+                .collect(),
+            width: (text_width(label) + 4.0 * CELL_PADDING).max(MIN_NODE_WIDTH),
+            height: ROW_HEIGHT * 1.5,
+            x: 0.0,
+            y: 0.0,
+        });
+    }
+
+    nodes
+}
+
+/// Assigns each node to the column one past its furthest prerequisite,
+/// returning the node indices in each column. Back edges of cycles are ignored.
+fn assign_columns(nodes: &[Node<'_>], index_of: &HashMap<&str, usize>) -> Vec<Vec<usize>> {
+    fn visit(
+        index: usize,
+        nodes: &[Node<'_>],
+        index_of: &HashMap<&str, usize>,
+        column: &mut Vec<Option<usize>>,
+        visiting: &mut Vec<bool>,
+    ) -> usize {
+        if let Some(c) = column[index] {
+            return c;
+        }
+        visiting[index] = true;
+        let mut c = 0;
+        for requirement in &nodes[index].requires {
+            if let Some(&prerequisite) = index_of.get(requirement) {
+                if !visiting[prerequisite] {
+                    c = c.max(visit(prerequisite, nodes, index_of, column, visiting) + 1);
+                }
+            }
+        }
+        visiting[index] = false;
+        column[index] = Some(c);
+        c
+    }
+
+    let mut column = vec![None; nodes.len()];
+    let mut visiting = vec![false; nodes.len()];
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+    for index in 0..nodes.len() {
+        let c = visit(index, nodes, index_of, &mut column, &mut visiting);
+        if columns.len() <= c {
+            columns.resize(c + 1, Vec::new());
+        }
+        columns[c].push(index);
+    }
+    columns
+}
+
+/// Positions every node and returns the overall width and height of the drawing.
+fn position_nodes(
+    nodes: &mut [Node<'_>],
+    index_of: &HashMap<&str, usize>,
+    columns: &[Vec<usize>],
+) -> (f64, f64) {
+    let mut x = MARGIN;
+    let mut heights = Vec::new();
+
+    for column in columns {
+        // Order the nodes in this column by the average position of their
+        // prerequisites, which keeps most edges from crossing.
+        let mut column = column.clone();
+        let barycenter = |index: usize| {
+            let ys: Vec<f64> = nodes[index]
+                .requires
+                .iter()
+                .filter_map(|r| index_of.get(r))
+                .map(|&p| nodes[p].y + nodes[p].height / 2.0)
+                .collect();
+            if ys.is_empty() {
+                f64::MAX
+            } else {
+                ys.iter().sum::<f64>() / ys.len() as f64
+            }
+        };
+        let keys: Vec<f64> = column.iter().map(|&index| barycenter(index)).collect();
+        let mut order: Vec<usize> = (0..column.len()).collect();
+        order.sort_by(|&a, &b| keys[a].partial_cmp(&keys[b]).unwrap());
+        column = order.into_iter().map(|i| column[i]).collect();
+
+        let mut y = MARGIN;
+        let mut width: f64 = 0.0;
+        for &index in &column {
+            nodes[index].x = x;
+            nodes[index].y = y;
+            y += nodes[index].height + NODE_GAP;
+            width = width.max(nodes[index].width);
+        }
+        heights.push((column, y - NODE_GAP));
+        x += width + COLUMN_GAP;
+    }
+
+    // Center each column vertically against the tallest one.
+    let height = heights.iter().map(|(_, h)| *h).fold(0.0, f64::max);
+    for (column, column_height) in &heights {
+        let offset = (height - column_height) / 2.0;
+        for &index in column {
+            nodes[index].y += offset;
+        }
+    }
+
+    (x - COLUMN_GAP + MARGIN, height + MARGIN)
+}
+
+fn edge(
+    nodes: &[Node<'_>],
+    index_of: &HashMap<&str, usize>,
+    requirement: &Requirement,
+    target: &Node<'_>,
+    target_port: Option<&str>,
+) -> Option<Path> {
+    let (name, port) = split_requirement(&requirement.on);
+
+    // Requirements that don't name a node are left out of the drawing.
+    let source = &nodes[*index_of.get(name)?];
+
+    let (x1, y1) = (source.x + source.width, source.port_y(port));
+    let (x2, y2) = (target.x, target.port_y(target_port));
+    let bend = (x2 - x1).abs() / 2.0;
+    let mut path = Path::new()
+        .set("fill", "none")
+        .set("stroke", "black")
+        .set("marker-end", "url(#arrow)")
+        .set(
+            "d",
+            format!(
+                "M {} {} C {} {}, {} {}, {} {}",
+                x1,
+                y1,
+                x1 + bend,
+                y1,
+                x2 - bend,
+                y2,
+                x2,
+                y2
+            ),
+        );
+    if requirement.soft {
+        path = path.set("stroke-dasharray", "4 2");
+    }
+    Some(path)
+}
+
+fn draw_group(tree: &SkillTree, group: &Group, node: &Node<'_>, document: &mut Document) {
+    let label = group.label.as_ref().unwrap_or(&group.name);
+    let header_color = group.header_color.as_deref().unwrap_or("darkgoldenrod");
+
+    let (rect, text) = cell(node.x, node.y, node.width, header_color, label);
+    document.append(rect);
+    let href = group.href.as_deref().map(|href| tree.resolve_href(href));
+    append_text(document, text, &href);
+
+    for (index, item) in group.items().enumerate() {
+        let status = tree.item_status(group, item);
+        let style = match status.and_then(|x| tree.status_style(x)) {
+            Some(style) => style.clone(),
+            None => StatusStyle::default(),
+        };
+        let bgcolor = style.bgcolor.as_deref().unwrap_or("white");
+        let y = node.y + ROW_HEIGHT * (index as f64 + 1.0);
+        let href = tree.item_href(&group.name, item);
+        let tooltip = tree.item_tooltip(status, item);
+
+        let emoji = item
+            .emoji
+            .as_deref()
+            .or(style.emoji.as_deref())
+            .unwrap_or("");
+        let (rect, text) = cell(node.x, y, EMOJI_WIDTH, bgcolor, emoji);
+        document.append(with_title(rect, &tooltip));
+        document.append(text);
+
+        let (rect, mut text) = cell(
+            node.x + EMOJI_WIDTH,
+            y,
+            node.width - EMOJI_WIDTH,
+            bgcolor,
+            &item.label_text(),
+        );
+        if let Some(color) = style.start_tag_color().or(style.fontcolor.as_deref()) {
+            text.assign("fill", color);
+        }
+        if style.start_tag.contains("<s>") {
+            text.assign("text-decoration", "line-through");
+        } else if style.start_tag.contains("<u>") || (href.is_some() && style.start_tag.is_empty())
+        {
+            text.assign("text-decoration", "underline");
+        }
+        if style.start_tag.contains("<i>") {
+            text.assign("font-style", "italic");
+        }
+        document.append(with_title(rect, &tooltip));
+        append_text(document, text, &href);
+    }
+}
+
+fn draw_goal(tree: &SkillTree, goal: &Goal, node: &Node<'_>, document: &mut Document) {
+    let label = goal.label.as_ref().unwrap_or(&goal.name);
+    let fill = goal.fillcolor.as_deref().unwrap_or("darkgoldenrod");
+    let (rect, text) = cell(node.x, node.y, node.width, fill, label);
+    document.append(rect.set("height", node.height).set("rx", 4));
+    let text = text.set("y", node.y + node.height / 2.0 + FONT_SIZE / 3.0);
+    let href = goal.href.as_deref().map(|href| tree.resolve_href(href));
+    append_text(document, text, &href);
+}
+
+/// Gives `rect` a `<title>`, which viewers show as a tooltip, if there is one.
+fn with_title(rect: Rectangle, title: &Option<String>) -> Rectangle {
+    match title {
+        Some(title) => rect.add(Title::new().add(TextNode::new(htmlescape::encode_minimal(title)))),
+        None => rect,
+    }
+}
+
+/// Draws a bordered, filled cell containing a line of text.
+fn cell(x: f64, y: f64, width: f64, fill: &str, text: &str) -> (Rectangle, Text) {
+    let rect = Rectangle::new()
+        .set("x", x)
+        .set("y", y)
+        .set("width", width)
+        .set("height", ROW_HEIGHT)
+        .set("fill", fill)
+        .set("stroke", "black");
+    let text = Text::new()
+        .set("x", x + CELL_PADDING)
+        .set("y", y + ROW_HEIGHT / 2.0 + FONT_SIZE / 3.0)
+        .add(TextNode::new(htmlescape::encode_minimal(text)));
+    (rect, text)
+}
+
+/// Appends `text` to the document, wrapped in a link to `href` if there is one.
+fn append_text(document: &mut Document, text: Text, href: &Option<String>) {
+    match href {
+        Some(href) => document.append(
+            Link::new()
+                .set("href", htmlescape::encode_minimal(href))
+                .add(text),
+        ),
+        None => document.append(text),
+    }
+}
+
+fn text_width(text: &str) -> f64 {
+    text.chars().count() as f64 * CHAR_WIDTH
+}
+
+#[cfg(test)]
+mod test;
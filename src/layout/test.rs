@@ -0,0 +1,71 @@
+use crate::SkillTree;
+
+#[test]
+fn escapes_tooltips() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", description = "R&D <fast>" }]
+"#,
+    )
+    .unwrap();
+    let svg = tree.render_svg();
+    assert!(svg.contains("R&amp;D &lt;fast&gt;"));
+    assert!(!svg.contains("<fast>"));
+}
+
+#[test]
+fn smoke() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+label = "Lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer?"]
+items = [{ label = "Grammar", requires = ["lexer:tokens"] }]
+
+[[goal]]
+name = "ship"
+label = "Ship it"
+requires = ["parser"]
+"#,
+    )
+    .unwrap();
+    let svg = tree.render_svg();
+    let mut output = vec![];
+    tree.write_svg(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), svg);
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    for label in &["Lexer", "Tokens", "parser", "Grammar", "Ship it"] {
+        assert!(svg.contains(&format!("\n{}\n", label)), "missing {}", label);
+    }
+    // One edge per requirement, with the soft one dashed.
+    assert_eq!(svg.matches(r#"marker-end="url(#arrow)""#).count(), 3);
+    assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+}
+
+#[test]
+fn decodes_only_html_labels() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "a &amp; b" },
+    { label = "c &amp; d", label_html = true },
+]
+"#,
+    )
+    .unwrap();
+    let svg = tree.render_svg();
+    // Written as is, so escaped once more in the SVG.
+    assert!(svg.contains("\na &amp;amp; b\n"));
+    assert!(svg.contains("\nc &amp; d\n"));
+}
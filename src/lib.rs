@@ -1,3 +1,54 @@
+mod batch;
+mod builder;
+mod critical_path;
+mod csv;
+mod date;
+mod diagnostic;
+mod diff;
+mod edit;
+mod effort;
+mod error;
+mod export;
+mod filter;
+#[cfg(feature = "github")]
+mod github;
+mod graph;
 mod graphviz;
+pub mod interop;
+#[cfg(feature = "svg")]
+mod layout;
+mod markdown;
+mod merge;
+mod migrate;
+mod readiness;
+mod render;
+mod search;
+mod stats;
+mod term;
+mod theme;
 mod tree;
+mod validate;
+mod watch;
+pub use batch::{find_skill_trees, render_dir};
+pub use builder::{GoalBuilder, GroupBuilder, ItemBuilder, SkillTreeBuilder};
+pub use date::today;
+pub use diagnostic::Span;
+pub use diff::{DiffNode, RequirementChange, StatusChange, TreeDiff};
+pub use effort::{Effort, EffortSummary};
+pub use error::Error;
+pub use export::{FULL_RENDER_JS, VIZ_JS};
+#[cfg(feature = "github")]
+pub use github::{GitHubApi, IssueRef, IssueState, IssueTracker};
+pub use graph::{Edge, Graph, NodeRef};
+#[cfg(feature = "wasm")]
+pub use graphviz::parse_and_render_js;
+pub use graphviz::{parse_and_render, GraphvizOptions};
+pub use migrate::LATEST_VERSION;
+pub use readiness::{PendingItem, Readiness};
+pub use render::OutputFormat;
+pub use search::Match;
+pub use stats::{GoalStats, GroupStats, Progress, Stats};
+pub use theme::Theme;
 pub use tree::*;
+pub use validate::{Severity, ValidationIssue};
+pub use watch::watch;
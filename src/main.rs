@@ -1,41 +1,488 @@
 use anyhow::Context;
 use fehler::throws;
-use skill_tree::SkillTree;
+use skill_tree::interop::github_project::{self, GroupBy};
+use skill_tree::interop::jira;
+use skill_tree::{Error, GraphvizOptions, OutputFormat, Severity, SkillTree};
 use std::fs::File;
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
+/// Used in place of a path to mean stdin (for input) or stdout (for output).
+const STDIO: &str = "-";
+
+/// How stdin is referred to in diagnostics.
+const STDIN_NAME: &str = "<stdin>";
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "skill-tree")]
-struct Opts {
+enum Opts {
+    /// Renders a skill tree to a file (or stdout).
+    Render(RenderOpts),
+
+    /// Renders a skill tree, and renders it again whenever it changes.
+    Watch(RenderOpts),
+
+    /// Renders every `*.toml` skill tree under a directory, mirroring the
+    /// directory structure in the output directory. Files that another
+    /// tree includes, or uses as its `status_file`, are skipped.
+    RenderDir {
+        #[structopt(name = "input_dir", parse(from_os_str))]
+        input_dir: PathBuf,
+
+        /// Directory to write the rendered trees to.
+        #[structopt(short, long, parse(from_os_str))]
+        output: PathBuf,
+
+        /// Format of the generated output; see `render --help`.
+        #[structopt(
+            long,
+            default_value = "dot",
+            possible_values = &["dot", "html", "png", "svg", "pdf", "term"]
+        )]
+        format: String,
+
+        /// Draw (or count) archived groups, items, and goals too.
+        #[structopt(long)]
+        include_archived: bool,
+    },
+
+    /// Checks a skill tree for problems, reporting all of them.
+    Validate {
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+    },
+
+    /// Counts the items with each status, and how complete each group and
+    /// goal is.
+    Stats {
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+
+        /// Print the counts as JSON rather than as a table.
+        #[structopt(long)]
+        json: bool,
+
+        /// Draw (or count) archived groups, items, and goals too.
+        #[structopt(long)]
+        include_archived: bool,
+    },
+
+    /// Lists the items that aren't done by what they wait for: nothing
+    /// (ready to start now), one unfinished item, or several.
+    Ready {
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+
+        /// Print the lists as JSON rather than as text.
+        #[structopt(long)]
+        json: bool,
+
+        /// Draw (or count) archived groups, items, and goals too.
+        #[structopt(long)]
+        include_archived: bool,
+    },
+
+    /// Rewrites a TOML skill tree written for an older version of the
+    /// format for the latest one.
+    Migrate {
+        /// The skill tree to upgrade, or `-` to read it from stdin.
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+
+        /// Where to write the upgraded tree; defaults to (or, with `-`, is)
+        /// stdout. May be the skill tree itself.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Converts a GitHub Projects board, as exported by `gh project
+    /// item-list --format json`, into a skill tree, written as TOML.
+    ImportGithubProject {
+        /// The exported board, or `-` to read it from stdin.
+        #[structopt(name = "export", parse(from_os_str))]
+        export: PathBuf,
+
+        /// Make a group of each iteration, rather than of each column.
+        #[structopt(long)]
+        by_iteration: bool,
+
+        /// Where to write the skill tree; defaults to (or, with `-`, is) stdout.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Converts a Jira issue CSV export into a skill tree, written as TOML.
+    ImportJira {
+        /// The exported issues, or `-` to read them from stdin.
+        #[structopt(name = "export", parse(from_os_str))]
+        export: PathBuf,
+
+        /// Group issues by this column, such as `Sprint`, rather than by epic.
+        #[structopt(long)]
+        group_by: Option<String>,
+
+        /// The Jira site, such as `https://example.atlassian.net`, to link
+        /// items to their issues.
+        #[structopt(long)]
+        base_url: Option<String>,
+
+        /// Where to write the skill tree; defaults to (or, with `-`, is) stdout.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Writes a skill tree as CSV for Jira's CSV importer: an epic per group
+    /// and goal, with a task for each item.
+    ExportJira {
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+
+        /// Where to write the CSV; defaults to (or, with `-`, is) stdout.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Sets the status of each item linked to a GitHub issue from that
+    /// issue, and writes the updated tree as TOML. Uses the token in
+    /// `GITHUB_TOKEN`, if set.
+    #[cfg(feature = "github")]
+    SyncGithub {
+        #[structopt(name = "skill_tree", parse(from_os_str))]
+        skill_tree: PathBuf,
+
+        /// Where to write the updated tree; defaults to (or, with `-`, is) stdout.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+struct RenderOpts {
+    /// The skill tree to render, or `-` to read it from stdin.
     #[structopt(name = "skill_tree", parse(from_os_str))]
     skill_tree: PathBuf,
 
-    #[structopt(name = "output_path", parse(from_os_str))]
-    output_path: PathBuf,
+    /// Where to write the output; defaults to (or, with `-`, is) stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// Format of the generated output: a graphviz `dot` file, a
+    /// self-contained `html` page, an image rendered by graphviz, or a
+    /// `term`inal outline.
+    #[structopt(
+        long,
+        default_value = "dot",
+        possible_values = &["dot", "html", "png", "svg", "pdf", "term"]
+    )]
+    format: String,
+
+    /// Draw archived groups, items, and goals too.
+    #[structopt(long)]
+    include_archived: bool,
 }
 
 #[throws(anyhow::Error)]
 fn main() {
-    let opts: Opts = Opts::from_args();
+    match Opts::from_args() {
+        Opts::Render(opts) => {
+            let skill_tree = with_archived(load_and_check(&opts.skill_tree), opts.include_archived);
+            write_output_file(&skill_tree, &opts.format, opts.output.as_deref())?;
+        }
+        Opts::Watch(opts) => {
+            if opts.skill_tree == Path::new(STDIO) {
+                anyhow::bail!("cannot watch stdin");
+            }
+            skill_tree::watch(&opts.skill_tree, |skill_tree| {
+                let skill_tree = match skill_tree {
+                    Ok(skill_tree) => skill_tree,
+                    Err(err) => {
+                        report(&err, &opts.skill_tree);
+                        return true;
+                    }
+                };
+                if check(&skill_tree, &opts.skill_tree) {
+                    let skill_tree = with_archived(skill_tree, opts.include_archived);
+                    match write_output_file(&skill_tree, &opts.format, opts.output.as_deref()) {
+                        Ok(()) => eprintln!("rendered `{}`", opts.skill_tree.display()),
+                        Err(err) => eprintln!("error: {:#}", err),
+                    }
+                }
+                true
+            });
+        }
+        Opts::RenderDir {
+            input_dir,
+            output,
+            format,
+            include_archived,
+        } => {
+            let extension = if format == "term" { "txt" } else { &format };
+            let mut failed = false;
+            let count = skill_tree::render_dir(
+                &input_dir,
+                &output,
+                extension,
+                |input_path, skill_tree, output_path| -> anyhow::Result<()> {
+                    if check(skill_tree, input_path) {
+                        let skill_tree = with_archived(skill_tree.clone(), include_archived);
+                        write_output_file(&skill_tree, &format, Some(output_path))?;
+                    } else {
+                        failed = true;
+                    }
+                    Ok(())
+                },
+            )?;
+            eprintln!(
+                "rendered {} skill trees from `{}`",
+                count,
+                input_dir.display()
+            );
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Opts::Validate { skill_tree } => {
+            load_and_check(&skill_tree);
+        }
+        Opts::Stats {
+            skill_tree,
+            json,
+            include_archived,
+        } => {
+            let stats = with_archived(load_and_check(&skill_tree), include_archived).stats();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print!("{}", stats);
+            }
+        }
+        Opts::Ready {
+            skill_tree,
+            json,
+            include_archived,
+        } => {
+            let readiness =
+                with_archived(load_and_check(&skill_tree), include_archived).readiness()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&readiness)?);
+            } else {
+                print!("{}", readiness);
+            }
+        }
+        Opts::ImportGithubProject {
+            export,
+            by_iteration,
+            output,
+        } => {
+            let json = read_input(&export)?;
+            let group_by = if by_iteration {
+                GroupBy::Iteration
+            } else {
+                GroupBy::Status
+            };
+            let skill_tree = github_project::import(&json, group_by)?;
+            write_toml(&skill_tree, output.as_deref())?;
+        }
+        Opts::ImportJira {
+            export,
+            group_by,
+            base_url,
+            output,
+        } => {
+            let options = jira::ImportOptions { group_by, base_url };
+            let skill_tree = jira::import(&read_input(&export)?, &options)?;
+            write_toml(&skill_tree, output.as_deref())?;
+        }
+        Opts::Migrate { skill_tree, output } => {
+            let migrated = SkillTree::migrate_to_latest(&read_input(&skill_tree)?)?;
+            write_text(&migrated, output.as_deref())?;
+        }
+        Opts::ExportJira { skill_tree, output } => {
+            let csv = jira::export(&load_and_check(&skill_tree));
+            write_text(&csv, output.as_deref())?;
+        }
+        #[cfg(feature = "github")]
+        Opts::SyncGithub { skill_tree, output } => {
+            let synced = load_and_check(&skill_tree)
+                .synced_with_issues(&mut skill_tree::GitHubApi::from_env())?;
+            write_toml(&synced, output.as_deref())?;
+        }
+    }
+}
+
+/// Loads the skill tree at `path` and checks it for problems, printing all
+/// of them. Exits the process if there are any errors.
+fn load_and_check(path: &Path) -> SkillTree {
+    if path == Path::new(STDIO) {
+        return read_stdin_and_check();
+    }
 
-    // Load the skill tree
-    let skill_tree = SkillTree::load(&opts.skill_tree)
-        .with_context(|| format!("loading skill tree from `{}`", opts.skill_tree.display()))?;
+    let skill_tree = match SkillTree::load(path) {
+        Ok(skill_tree) => skill_tree,
+        Err(err) => {
+            report(&err, path);
+            std::process::exit(1);
+        }
+    };
 
-    // Validate it for errors.
-    skill_tree.validate()?;
+    if !check(&skill_tree, path) {
+        std::process::exit(1);
+    }
 
-    // Write out the dot file
-    write_dot_file(&skill_tree, &opts)?;
+    skill_tree
 }
 
-#[throws(anyhow::Error)]
-fn write_dot_file(skill_tree: &SkillTree, opts: &Opts) {
-    let dot_path = &opts.output_path;
-    let mut dot_file =
-        File::create(dot_path).with_context(|| format!("creating `{}`", dot_path.display()))?;
+/// Like `load_and_check`, but parses TOML from stdin.
+fn read_stdin_and_check() -> SkillTree {
+    let mut source = String::new();
+    let skill_tree = io::stdin()
+        .read_to_string(&mut source)
+        .map_err(Error::from)
+        .and_then(|_| SkillTree::parse(&source));
+    let skill_tree = match skill_tree {
+        Ok(skill_tree) => skill_tree,
+        Err(err) => {
+            eprint!("{}", err.render_diagnostic(STDIN_NAME, &source));
+            std::process::exit(1);
+        }
+    };
+
+    if !check_source(&skill_tree, STDIN_NAME, &source) {
+        std::process::exit(1);
+    }
+
     skill_tree
-        .write_graphviz(&mut dot_file)
-        .with_context(|| format!("writing to `{}`", dot_path.display()))?;
+}
+
+/// Sets `include_archived` on a loaded tree if `--include-archived` was given.
+fn with_archived(mut skill_tree: SkillTree, include_archived: bool) -> SkillTree {
+    skill_tree.include_archived |= include_archived;
+    skill_tree
+}
+
+/// Prints every problem with `skill_tree`, which was loaded from `path`.
+/// Returns false if any of them are errors.
+fn check(skill_tree: &SkillTree, path: &Path) -> bool {
+    let source = std::fs::read_to_string(path).unwrap_or_default();
+    check_source(skill_tree, &path.display().to_string(), &source)
+}
+
+fn check_source(skill_tree: &SkillTree, file_name: &str, source: &str) -> bool {
+    let issues = skill_tree.check();
+    for issue in &issues {
+        eprint!("{}", issue.render_diagnostic(file_name, source));
+    }
+    !issues.iter().any(|issue| issue.severity == Severity::Error)
+}
+
+/// Prints `err` to stderr along with the line of the skill tree it is
+/// about, when that can be found.
+fn report(err: &Error, path: &Path) {
+    let mut err = err;
+    let mut notes = Vec::new();
+    while let Error::Include {
+        include,
+        from,
+        source,
+    } = err
+    {
+        notes.push(format!(
+            "note: while including `{}` from `{}`",
+            include,
+            from.display()
+        ));
+        err = source;
+    }
+
+    // Parse errors know which (possibly included) file they came from.
+    let path = match err {
+        Error::Parse {
+            path: Some(path), ..
+        } => path,
+        _ => path,
+    };
+    match std::fs::read_to_string(path) {
+        Ok(source) => eprint!(
+            "{}",
+            err.render_diagnostic(&path.display().to_string(), &source)
+        ),
+        Err(_) => eprintln!("error: {}", err),
+    }
+    for note in notes.iter().rev() {
+        eprintln!("{}", note);
+    }
+}
+
+#[throws(anyhow::Error)]
+fn write_output_file(skill_tree: &SkillTree, format: &str, output_path: Option<&Path>) {
+    let output_path = match output_path {
+        Some(output_path) if output_path != Path::new(STDIO) => output_path,
+        _ => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            write_output(skill_tree, format, &mut stdout)?;
+            return;
+        }
+    };
+
+    if let Ok(image_format) = format.parse::<OutputFormat>() {
+        skill_tree
+            .render_with(&graphviz_options(), image_format, output_path)
+            .with_context(|| format!("rendering to `{}`", output_path.display()))?;
+        return;
+    }
+
+    let mut output_file = File::create(output_path)
+        .with_context(|| format!("creating `{}`", output_path.display()))?;
+    write_output(skill_tree, format, &mut output_file)
+        .with_context(|| format!("writing to `{}`", output_path.display()))?;
+}
+
+/// Reads the file at `path`, or stdin if it is `-`.
+#[throws(anyhow::Error)]
+fn read_input(path: &Path) -> String {
+    if path == Path::new(STDIO) {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("reading `{}`", path.display()))?
+    }
+}
+
+/// Writes `skill_tree` as TOML to `output_path`, or to stdout.
+#[throws(anyhow::Error)]
+fn write_toml(skill_tree: &SkillTree, output_path: Option<&Path>) {
+    write_text(&skill_tree.to_toml_string()?, output_path)?;
+}
+
+/// Writes `text` to `output_path`, or to stdout.
+#[throws(anyhow::Error)]
+fn write_text(text: &str, output_path: Option<&Path>) {
+    match output_path {
+        Some(path) if path != Path::new(STDIO) => std::fs::write(path, text)
+            .with_context(|| format!("writing to `{}`", path.display()))?,
+        _ => print!("{}", text),
+    }
+}
+
+#[throws(anyhow::Error)]
+fn write_output(skill_tree: &SkillTree, format: &str, output: &mut dyn Write) {
+    let options = graphviz_options();
+    match format {
+        "html" => skill_tree.write_html_with(&options, output)?,
+        "term" => skill_tree.write_terminal(output)?,
+        "dot" => skill_tree.write_graphviz_with(&options, output)?,
+        _ => skill_tree.write_rendered_with(&options, format.parse()?, output)?,
+    }
+}
+
+/// The settings for rendering with graphviz: the defaults, but with items
+/// past their due date highlighted as of today.
+fn graphviz_options() -> GraphvizOptions {
+    GraphvizOptions {
+        as_of: Some(skill_tree::today()),
+        ..GraphvizOptions::default()
+    }
 }
@@ -0,0 +1,144 @@
+//! The small subset of Markdown that labels may use: `**bold**`, `*italic*`
+//! (or `_italic_`), `` `code` ``, and `[links](https://example.com)`. It is
+//! translated to graphviz's HTML-like label markup. As in Markdown, inline
+//! HTML tags and entities can be passed through; everything else is escaped.
+
+/// A label translated to graphviz markup.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Markup {
+    pub(crate) html: String,
+    /// Target of the first link in the label. Graphviz can only link whole
+    /// table cells, so this is used for the cell the label is in.
+    pub(crate) href: Option<String>,
+}
+
+/// Translates `text`, passing inline HTML tags and entities through if
+/// `raw_html` is set and escaping them like any other text if not.
+pub(crate) fn to_markup(text: &str, raw_html: bool) -> Markup {
+    let mut markup = Markup::default();
+    render(text, raw_html, &mut markup);
+    markup
+}
+
+fn render(text: &str, raw_html: bool, markup: &mut Markup) {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        rest = match c {
+            '\\' if after.starts_with(|c: char| c.is_ascii_punctuation()) => {
+                push_escaped(&after[..1], &mut markup.html);
+                &after[1..]
+            }
+            '`' => match after.find('`') {
+                Some(end) => {
+                    markup.html.push_str(r#"<font face="monospace">"#);
+                    push_escaped(&after[..end], &mut markup.html);
+                    markup.html.push_str("</font>");
+                    &after[end + 1..]
+                }
+                None => literal(c, after, markup),
+            },
+            '*' if after.starts_with('*') => {
+                match emphasis(&after[1..], "**", "b", raw_html, markup) {
+                    Some(rest) => rest,
+                    None => literal(c, after, markup),
+                }
+            }
+            '*' => match emphasis(after, "*", "i", raw_html, markup) {
+                Some(rest) => rest,
+                None => literal(c, after, markup),
+            },
+            '_' if !ends_in_word(text, rest) => match emphasis(after, "_", "i", raw_html, markup) {
+                Some(rest) => rest,
+                None => literal(c, after, markup),
+            },
+            '[' => match link(after, raw_html, markup) {
+                Some(rest) => rest,
+                None => literal(c, after, markup),
+            },
+            '<' if raw_html && after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') => {
+                match after.find('>') {
+                    Some(end) => {
+                        markup.html.push('<');
+                        markup.html.push_str(&after[..=end]);
+                        &after[end + 1..]
+                    }
+                    None => literal(c, after, markup),
+                }
+            }
+            '&' if raw_html && is_entity(after) => {
+                let end = after.find(';').unwrap();
+                markup.html.push('&');
+                markup.html.push_str(&after[..=end]);
+                &after[end + 1..]
+            }
+            _ => literal(c, after, markup),
+        };
+    }
+}
+
+/// Renders the text up to the closing `delimiter` wrapped in `tag`, if
+/// there is one, returning what follows it.
+fn emphasis<'t>(
+    after: &'t str,
+    delimiter: &str,
+    tag: &str,
+    raw_html: bool,
+    markup: &mut Markup,
+) -> Option<&'t str> {
+    let end = after.find(delimiter).filter(|&end| end > 0)?;
+    markup.html.push_str(&format!("<{}>", tag));
+    render(&after[..end], raw_html, markup);
+    markup.html.push_str(&format!("</{}>", tag));
+    Some(&after[end + delimiter.len()..])
+}
+
+/// Renders a `[text](href)` link whose `[` has been consumed.
+fn link<'t>(after: &'t str, raw_html: bool, markup: &mut Markup) -> Option<&'t str> {
+    let text_end = after.find("](")?;
+    let href_end = after[text_end..].find(')')? + text_end;
+    let href = &after[text_end + 2..href_end];
+
+    if markup.href.is_none() {
+        markup.href = Some(href.to_owned());
+    }
+    markup.html.push_str("<u>");
+    render(&after[..text_end], raw_html, markup);
+    markup.html.push_str("</u>");
+    Some(&after[href_end + 1..])
+}
+
+/// Pushes `c` as plain text and returns the rest.
+fn literal<'t>(c: char, after: &'t str, markup: &mut Markup) -> &'t str {
+    let mut buf = [0; 4];
+    push_escaped(c.encode_utf8(&mut buf), &mut markup.html);
+    after
+}
+
+fn push_escaped(text: &str, html: &mut String) {
+    html.push_str(&htmlescape::encode_minimal(text).replace('\n', "<br/>"));
+}
+
+/// Whether the text after a `&` is the rest of an entity like `&amp;` or `&#39;`.
+fn is_entity(after: &str) -> bool {
+    match after.find(';') {
+        Some(end) => {
+            let name = &after[..end];
+            !name.is_empty()
+                && (name.chars().all(|c| c.is_ascii_alphanumeric())
+                    || (name.starts_with('#')
+                        && name[1..].chars().all(|c| c.is_ascii_alphanumeric())))
+        }
+        None => false,
+    }
+}
+
+/// Whether the character before `rest` in `text` is part of a word, so that
+/// underscores in names like `snake_case` aren't taken for emphasis.
+fn ends_in_word(text: &str, rest: &str) -> bool {
+    let before = &text[..text.len() - rest.len()];
+    matches!(before.chars().last(), Some(c) if c.is_alphanumeric())
+}
+
+#[cfg(test)]
+mod test;
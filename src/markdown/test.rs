@@ -0,0 +1,42 @@
+use super::to_markup;
+
+fn html(text: &str) -> String {
+    to_markup(text, true).html
+}
+
+#[test]
+fn formats() {
+    assert_eq!(html("**bold** and *it*"), "<b>bold</b> and <i>it</i>");
+    assert_eq!(
+        html("_it_ but snake_case_name"),
+        "<i>it</i> but snake_case_name"
+    );
+    assert_eq!(
+        html("`a<b>` **x *y* z**"),
+        r#"<font face="monospace">a&lt;b&gt;</font> <b>x <i>y</i> z</b>"#
+    );
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(html("a < b & c"), "a &lt; b &amp; c");
+    assert_eq!(html(r"\*not italic\*"), "*not italic*");
+    assert_eq!(html("unclosed *star"), "unclosed *star");
+    // Inline HTML and entities pass through, as in Markdown.
+    assert_eq!(html("<s>done</s> &amp; dusted"), "<s>done</s> &amp; dusted");
+    // Unless they aren't wanted.
+    assert_eq!(
+        to_markup("*<s>done</s>* &amp; dusted", false).html,
+        "<i>&lt;s&gt;done&lt;/s&gt;</i> &amp;amp; dusted"
+    );
+}
+
+#[test]
+fn links() {
+    let markup = to_markup(
+        "see [the *RFC*](https://example.com/rfc) and [more](x)",
+        true,
+    );
+    assert_eq!(markup.html, "see <u>the <i>RFC</i></u> and <u>more</u>");
+    assert_eq!(markup.href.as_deref(), Some("https://example.com/rfc"));
+}
@@ -0,0 +1,105 @@
+//! Combines skill-trees maintained separately into one.
+
+use crate::error::Error;
+use crate::tree::{namespace_of, Requirement, SkillTree};
+use fehler::{throw, throws};
+use std::collections::HashMap;
+
+impl SkillTree {
+    /// Combines the groups and goals of `trees` into one tree, in which
+    /// any of them can require any other. The title and other tree-wide
+    /// settings come from the first tree. Statuses, areas, priorities, and
+    /// milestones are combined too; where several trees define one with
+    /// the same name, the first definition is used. The groups and goals of
+    /// a tree with a `namespace` are renamed to `namespace/name` first, so
+    /// that trees with a namespace each can't collide. Fails if two trees
+    /// define a group or goal with the same name.
+    #[throws(Error)]
+    pub fn merge(trees: &[SkillTree]) -> SkillTree {
+        let trees: Vec<SkillTree> = trees.iter().map(SkillTree::namespaced).collect();
+        // Where each name was first defined: the tree's index, and a
+        // description for the error.
+        let mut defined: HashMap<&str, (usize, String)> = HashMap::new();
+        for (index, tree) in trees.iter().enumerate() {
+            let groups = tree.groups().map(|group| (&group.name, "group"));
+            let goals = tree.goals().map(|goal| (&goal.name, "goal"));
+            for (name, kind) in groups.chain(goals) {
+                let definition = format!("{} in tree #{}", kind, index + 1);
+                match defined.get(name.as_str()) {
+                    // Duplicates within one tree are for `check` to report.
+                    Some((first, _)) if *first == index => {}
+                    Some((_, first)) => throw!(Error::DuplicateName {
+                        name: name.clone(),
+                        first: first.clone(),
+                        second: definition,
+                    }),
+                    None => {
+                        defined.insert(name, (index, definition));
+                    }
+                }
+            }
+        }
+
+        let mut trees = trees.iter().cloned();
+        let mut merged = trees.next().unwrap_or_default();
+        for tree in trees {
+            merged.merge_included(tree);
+        }
+        merged
+    }
+
+    /// Returns a copy of the tree with its groups and goals renamed to
+    /// `namespace/name`, if it has a namespace. They keep their old names
+    /// as labels. Parents and requirements within the tree are renamed to
+    /// match; requirements that already name a namespace are left as they
+    /// are. Requirements written as `group/item label` are resolved to
+    /// ports first, so they aren't mistaken for ones that do.
+    fn namespaced(&self) -> SkillTree {
+        let mut tree = self.with_resolved_requirements();
+        let namespace = match tree.namespace.take() {
+            Some(namespace) => namespace,
+            None => return tree,
+        };
+        let rename = |name: &mut String, label: &mut Option<String>| {
+            let qualified = format!("{}/{}", namespace, name);
+            let name = std::mem::replace(name, qualified);
+            label.get_or_insert(name);
+        };
+        let qualify = |requirement: &mut Requirement| {
+            if namespace_of(&requirement.on).is_none() {
+                requirement.on = format!("{}/{}", namespace, requirement.on);
+            }
+        };
+        for group in &mut tree.group {
+            rename(&mut group.name, &mut group.label);
+            if let Some(parent) = &mut group.parent {
+                *parent = format!("{}/{}", namespace, parent);
+            }
+            group
+                .requires
+                .iter_mut()
+                .flatten()
+                .chain(group.requires_any.iter_mut().flatten())
+                .for_each(qualify);
+            for item in &mut group.items {
+                item.requires
+                    .iter_mut()
+                    .flatten()
+                    .chain(item.requires_any.iter_mut().flatten())
+                    .for_each(qualify);
+            }
+        }
+        for goal in tree.goal.iter_mut().flatten() {
+            rename(&mut goal.name, &mut goal.label);
+            goal.requires
+                .iter_mut()
+                .flatten()
+                .chain(goal.requires_any.iter_mut().flatten())
+                .for_each(qualify);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,101 @@
+use crate::{Error, SkillTree};
+
+const COMPILER: &str = r#"
+title = "Compiler"
+
+[status.Shipped]
+completed = true
+
+[[group]]
+name = "parser"
+items = [{ label = "Expressions", port = "exprs" }]
+"#;
+
+const TOOLING: &str = r#"
+title = "Tooling"
+
+[status.Shipped]
+bgcolor = "green"
+
+[[group]]
+name = "ide"
+requires = ["parser:exprs"]
+items = [{ label = "Completion" }]
+
+[[goal]]
+name = "release"
+requires = ["ide"]
+"#;
+
+#[test]
+fn combines_trees() {
+    let compiler = SkillTree::parse(COMPILER).unwrap();
+    let tooling = SkillTree::parse(TOOLING).unwrap();
+    let merged = SkillTree::merge(&[compiler, tooling]).unwrap();
+    assert_eq!(merged.title.as_deref(), Some("Compiler"));
+    let groups: Vec<&str> = merged.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["parser", "ide"]);
+    assert_eq!(merged.goals().count(), 1);
+    // The first definition of a status wins.
+    assert!(merged.status["Shipped"].completed);
+    // Requirements across the trees resolve.
+    merged.validate().unwrap();
+}
+
+#[test]
+fn rejects_collisions() {
+    let compiler = SkillTree::parse(COMPILER).unwrap();
+    match SkillTree::merge(&[compiler.clone(), compiler]) {
+        Err(Error::DuplicateName {
+            name,
+            first,
+            second,
+        }) => {
+            assert_eq!(name, "parser");
+            assert_eq!(first, "group in tree #1");
+            assert_eq!(second, "group in tree #2");
+        }
+        other => panic!("expected a duplicate name, got {:?}", other),
+    }
+    assert_eq!(SkillTree::merge(&[]).unwrap().groups().count(), 0);
+}
+
+#[test]
+fn namespaces() {
+    let compiler = SkillTree::parse(&format!("namespace = \"compiler\"\n{}", COMPILER)).unwrap();
+    let tooling = TOOLING.replace("parser:exprs", "compiler/parser:exprs");
+    let tooling = SkillTree::parse(&format!("namespace = \"tooling\"\n{}", tooling)).unwrap();
+    let merged = SkillTree::merge(&[compiler.clone(), tooling]).unwrap();
+    let groups: Vec<&str> = merged.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, ["compiler/parser", "tooling/ide"]);
+    assert_eq!(
+        merged.groups().next().unwrap().label.as_deref(),
+        Some("parser")
+    );
+    // References within a tree get its namespace; explicit ones are kept.
+    assert_eq!(
+        merged.goals().next().unwrap().requires.as_ref().unwrap()[0].on,
+        "tooling/ide"
+    );
+    assert!(merged.namespace.is_none());
+    merged.validate().unwrap();
+
+    // Items referred to by label are resolved before being namespaced.
+    let labeled = SkillTree::parse(&format!(
+        "namespace = \"labeled\"\n{}\n[[goal]]\nname = \"parsed\"\nrequires = [\"parser/Expressions\"]",
+        COMPILER
+    ))
+    .unwrap();
+    let merged = SkillTree::merge(&[labeled]).unwrap();
+    assert_eq!(
+        merged.goals().next().unwrap().requires.as_ref().unwrap()[0].on,
+        "labeled/parser:exprs"
+    );
+    merged.validate().unwrap();
+
+    // Groups with the same name in different namespaces don't collide.
+    let mut other = SkillTree::parse(COMPILER).unwrap();
+    other.namespace = Some("other".to_owned());
+    let merged = SkillTree::merge(&[compiler, other]).unwrap();
+    assert_eq!(merged.groups().count(), 2);
+}
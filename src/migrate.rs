@@ -0,0 +1,113 @@
+//! Checks which version of the format a skill-tree is written for.
+//!
+//! - Version 2: `version` was added. Nothing else changed, so version 1
+//!   trees (those without a `version`) are read as they are.
+//!
+//! When a version changes the format, the upgrade from the version before
+//! it goes here, applied as trees are parsed.
+
+use crate::error::Error;
+use crate::tree::SkillTree;
+use fehler::{throw, throws};
+use serde_derive::Deserialize;
+
+/// The version of the format this crate reads and writes, as set by a
+/// tree's top-level `version`. Trees without one are taken to be version 1.
+pub const LATEST_VERSION: u32 = 2;
+
+/// Just the `version` of a document, to report it when the rest can't be
+/// read.
+#[derive(Deserialize)]
+struct Versioned {
+    version: Option<u32>,
+}
+
+impl SkillTree {
+    /// Parses a skill-tree from TOML, failing if it is written for a
+    /// version of the format this crate doesn't know.
+    #[throws(Error)]
+    pub(crate) fn from_toml(text: &str) -> SkillTree {
+        checked(toml::from_str(text), || {
+            toml::from_str::<Versioned>(text).ok()
+        })?
+    }
+
+    /// Like `from_toml`, but for JSON.
+    #[throws(Error)]
+    pub(crate) fn from_json(text: &str) -> SkillTree {
+        checked(serde_json::from_str(text), || {
+            serde_json::from_str::<Versioned>(text).ok()
+        })?
+    }
+
+    /// Like `from_toml`, but for YAML.
+    #[cfg(feature = "serde_yaml")]
+    #[throws(Error)]
+    pub(crate) fn from_yaml(text: &str) -> SkillTree {
+        checked(serde_yaml::from_str(text), || {
+            serde_yaml::from_str::<Versioned>(text).ok()
+        })?
+    }
+
+    /// Rewrites the TOML skill-tree `text` for the latest version of the
+    /// format, setting its `version`. Unlike parsing it and calling
+    /// `to_toml_string`, this doesn't flatten sub-groups or add defaults,
+    /// but comments and formatting are lost and keys come out sorted.
+    #[throws(Error)]
+    pub fn migrate_to_latest(text: &str) -> String {
+        let mut document: toml::Value = toml::from_str(text)?;
+        let version = match document.get("version") {
+            Some(version) => Some(version.clone().try_into::<u32>()?),
+            None => None,
+        };
+        check_version(version)?;
+        if let toml::Value::Table(document) = &mut document {
+            document.insert(
+                "version".to_owned(),
+                toml::Value::Integer(LATEST_VERSION.into()),
+            );
+        }
+        document.to_string()
+    }
+}
+
+/// Checks the version of a parsed tree. If it couldn't be parsed, an
+/// unsupported version found by `versioned` (which only reads `version`)
+/// is reported rather than the error, as it is likely the cause.
+#[throws(Error)]
+fn checked<E>(
+    parsed: Result<SkillTree, E>,
+    versioned: impl FnOnce() -> Option<Versioned>,
+) -> SkillTree
+where
+    Error: From<E>,
+{
+    match parsed {
+        Ok(tree) => {
+            check_version(tree.version)?;
+            tree
+        }
+        Err(err) => {
+            if let Some(Versioned { version }) = versioned() {
+                check_version(version)?;
+            }
+            throw!(err)
+        }
+    }
+}
+
+/// Fails if `version` isn't one this crate knows.
+#[throws(Error)]
+fn check_version(version: Option<u32>) {
+    if let Some(version) = version {
+        if !(1..=LATEST_VERSION).contains(&version) {
+            throw!(Error::UnsupportedVersion {
+                version,
+                latest: LATEST_VERSION,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,82 @@
+use crate::{Error, SkillTree, LATEST_VERSION};
+
+const V1: &str = r#"
+updated = 2020-03-01
+
+[[group]]
+name = "lexer"
+assignee = "alice"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = ["lexer?"]
+items = []
+
+[[group.group]]
+name = "macros"
+items = [{ label = "Hygiene", requires = ["lexer:tokens"] }]
+"#;
+
+#[test]
+fn reads_v1_unchanged() {
+    let text = V1.replacen("updated = 2020-03-01", r#"updated = "2020-03-01""#, 1);
+    let tree = SkillTree::parse(&text).unwrap();
+    assert_eq!(tree.version, None);
+    assert_eq!(tree.group("lexer").unwrap().owner.as_deref(), Some("alice"));
+    assert!(tree.check().is_empty());
+
+    let tree = SkillTree::parse(&format!("version = 2\n{}", text)).unwrap();
+    assert_eq!(tree.version, Some(2));
+    assert!(tree.check().is_empty());
+}
+
+#[test]
+fn checks_json_versions() {
+    let tree = SkillTree::parse_json(r#"{ "group": [{ "name": "a", "items": [] }] }"#).unwrap();
+    assert!(tree.check().is_empty());
+    assert!(SkillTree::parse_json(r#"{ "version": 3, "group": [] }"#).is_err());
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let err = SkillTree::parse("version = 99\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "this tree is written for version 99 of the format, but only versions 1 to 2 are \
+         supported"
+    );
+    for text in &["version = 0\n", "version = 3\n"] {
+        match SkillTree::parse(text) {
+            Err(Error::UnsupportedVersion { latest, .. }) => assert_eq!(latest, LATEST_VERSION),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(SkillTree::migrate_to_latest(text).is_err());
+    }
+}
+
+#[test]
+fn reports_version_over_parse_errors() {
+    // A newer version may well change the format; say so rather than
+    // failing on the first thing that changed.
+    match SkillTree::parse("version = 3\ngroup = \"renamed\"\n") {
+        Err(Error::UnsupportedVersion { version: 3, .. }) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match SkillTree::parse("version = 2\ngroup = \"renamed\"\n") {
+        Err(Error::Parse { .. }) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn migrate_to_latest() {
+    let text = SkillTree::migrate_to_latest(V1).unwrap();
+    assert!(text.starts_with("updated = 2020-03-01\nversion = 2\n"));
+    assert!(text.contains(r#"assignee = "alice""#));
+    assert!(text.contains(r#"requires = ["lexer?"]"#));
+    // Sub-groups stay where they were written.
+    assert!(text.contains("[[group.group]]"));
+    // Already up to date, so only the formatting can change.
+    assert_eq!(SkillTree::migrate_to_latest(&text).unwrap(), text);
+}
@@ -0,0 +1,134 @@
+//! Sorts the items that aren't done yet by how much stands in their way,
+//! for planning what to work on next.
+
+use crate::error::Error;
+use crate::graph::NodeRef;
+use crate::tree::{GroupIndex, ItemIndex, SkillTree};
+use fehler::throws;
+use serde_derive::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+/// An item that isn't completed, and the unfinished items it waits for.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PendingItem {
+    pub group: String,
+    pub label: String,
+    pub status: Option<String>,
+    /// The unfinished items this one requires, directly or indirectly, as
+    /// `group/item label`, in the order they are defined.
+    pub blocked_by: Vec<String>,
+}
+
+/// The items that aren't completed, in the order they are defined, bucketed
+/// by how many unfinished items they wait for.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Readiness {
+    /// Items whose prerequisites are all completed, which can start now.
+    pub ready: Vec<PendingItem>,
+    /// Items waiting for exactly one unfinished item.
+    pub blocked_by_one: Vec<PendingItem>,
+    /// Items waiting for more than one.
+    pub deeply_blocked: Vec<PendingItem>,
+}
+
+impl SkillTree {
+    /// Buckets the items of every group that aren't completed by what they
+    /// wait for: everything they require, directly or indirectly, that is
+    /// an unfinished item. Requiring a group means waiting for its items.
+    /// Completed items count as done even if what they require isn't, and
+    /// soft requirements don't count, nor does anything archived (unless
+    /// `include_archived` is set). Fails if a requirement doesn't resolve.
+    #[throws(Error)]
+    pub fn readiness(&self) -> Readiness {
+        self.visible().bucket_pending()?
+    }
+
+    #[throws(Error)]
+    fn bucket_pending(&self) -> Readiness {
+        let graph = self.graph()?;
+        let completed = |group: GroupIndex, item: ItemIndex| {
+            let group = self.group_at(group);
+            let item = &group.items[item.0];
+            self.is_completed(self.item_status(group, item))
+        };
+
+        let mut readiness = Readiness::default();
+        for node in &graph.nodes {
+            let (g, i) = match *node {
+                NodeRef::Item(g, i) if !completed(g, i) => (g, i),
+                _ => continue,
+            };
+            let mut blockers = BTreeSet::new();
+            let mut seen = HashSet::new();
+            let mut pending = graph.prerequisites(*node);
+            while let Some(node) = pending.pop() {
+                if !seen.insert(node) {
+                    continue;
+                }
+                match node {
+                    NodeRef::Item(g, i) => {
+                        if !completed(g, i) {
+                            blockers.insert((g, i));
+                            pending.extend(graph.prerequisites(node));
+                        }
+                    }
+                    NodeRef::Group(g) => pending.extend(
+                        (0..self.group_at(g).items.len()).map(|i| NodeRef::Item(g, ItemIndex(i))),
+                    ),
+                    NodeRef::Goal(_) => pending.extend(graph.prerequisites(node)),
+                }
+            }
+            // An item on a cycle waits for itself.
+            blockers.remove(&(g, i));
+
+            let group = self.group_at(g);
+            let item = self.item_at(g, i);
+            let pending = PendingItem {
+                group: group.name.clone(),
+                label: item.label.clone(),
+                status: self.item_status(group, item).cloned(),
+                blocked_by: blockers
+                    .into_iter()
+                    .map(|(g, i)| format!("{}/{}", self.group_at(g).name, self.item_at(g, i).label))
+                    .collect(),
+            };
+            match pending.blocked_by.len() {
+                0 => readiness.ready.push(pending),
+                1 => readiness.blocked_by_one.push(pending),
+                _ => readiness.deeply_blocked.push(pending),
+            }
+        }
+        readiness
+    }
+}
+
+/// A plain-text report: each bucket with its items, and what the blocked
+/// ones are waiting for.
+impl fmt::Display for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buckets = [
+            ("READY NOW", &self.ready),
+            ("BLOCKED BY ONE", &self.blocked_by_one),
+            ("DEEPLY BLOCKED", &self.deeply_blocked),
+        ];
+        for (index, (title, items)) in buckets.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{} ({})", title, items.len())?;
+            for item in items.iter() {
+                write!(f, "  {}/{}", item.group, item.label)?;
+                match item.blocked_by.as_slice() {
+                    [] => writeln!(f)?,
+                    [blocker] => writeln!(f, "  (waiting for {})", blocker)?,
+                    blockers => writeln!(f, "  (waiting for {} items)", blockers.len())?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,75 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+items = [
+    { label = "Tokens", status = "Complete" },
+    { label = "Spans" },
+]
+
+[[group]]
+name = "parser"
+items = [
+    { label = "Grammar", requires = ["lexer/Tokens"] },
+    { label = "Errors", requires = ["lexer/Spans"] },
+    { label = "Recovery", requires = ["parser/Errors", "docs?"] },
+]
+
+[[group]]
+name = "docs"
+requires = ["release"]
+items = [{ label = "Guide" }]
+
+[[goal]]
+name = "release"
+requires = ["parser"]
+"#;
+
+fn names(items: &[crate::PendingItem]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| format!("{}/{}", item.group, item.label))
+        .collect()
+}
+
+#[test]
+fn buckets_items() {
+    let readiness = SkillTree::parse(TREE).unwrap().readiness().unwrap();
+    assert_eq!(names(&readiness.ready), ["lexer/Spans", "parser/Grammar"]);
+    assert_eq!(names(&readiness.blocked_by_one), ["parser/Errors"]);
+    assert_eq!(readiness.blocked_by_one[0].blocked_by, ["lexer/Spans"]);
+    assert_eq!(
+        names(&readiness.deeply_blocked),
+        ["parser/Recovery", "docs/Guide"]
+    );
+    assert_eq!(
+        readiness.deeply_blocked[1].blocked_by,
+        [
+            "lexer/Spans",
+            "parser/Grammar",
+            "parser/Errors",
+            "parser/Recovery"
+        ]
+    );
+}
+
+#[test]
+fn display() {
+    let readiness = SkillTree::parse(TREE).unwrap().readiness().unwrap();
+    assert_eq!(
+        readiness.to_string(),
+        "\
+READY NOW (2)
+  lexer/Spans
+  parser/Grammar
+
+BLOCKED BY ONE (1)
+  parser/Errors  (waiting for lexer/Spans)
+
+DEEPLY BLOCKED (2)
+  parser/Recovery  (waiting for 2 items)
+  docs/Guide  (waiting for 4 items)
+"
+    );
+}
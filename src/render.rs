@@ -0,0 +1,129 @@
+use crate::error::Error;
+use crate::graphviz::GraphvizOptions;
+use crate::tree::SkillTree;
+use fehler::{throw, throws};
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// Environment variable that can be used to point at a specific `dot` executable.
+const DOT_ENV_VAR: &str = "GRAPHVIZ_DOT";
+
+/// Image formats that graphviz can render a skill-tree to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    Png,
+    Svg,
+    Pdf,
+}
+
+impl OutputFormat {
+    /// The name graphviz uses for this format, as in `dot -Tpng`.
+    pub fn graphviz_name(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Pdf => "pdf",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    #[throws(Error)]
+    fn from_str(s: &str) -> OutputFormat {
+        match s {
+            "png" => OutputFormat::Png,
+            "svg" => OutputFormat::Svg,
+            "pdf" => OutputFormat::Pdf,
+            _ => throw!(Error::UnknownFormat { name: s.to_owned() }),
+        }
+    }
+}
+
+impl SkillTree {
+    /// Renders this skill-tree to `path` in the given format by running
+    /// graphviz's `dot` executable. The `GRAPHVIZ_DOT` environment variable
+    /// can be used to select a `dot` that is not on the `PATH`.
+    #[throws(Error)]
+    pub fn render(&self, format: OutputFormat, path: &Path) {
+        run_dot(self, &GraphvizOptions::default(), format, Some(path))?;
+    }
+
+    /// Like `render`, but with the given graph-wide settings.
+    #[throws(Error)]
+    pub fn render_with(&self, options: &GraphvizOptions, format: OutputFormat, path: &Path) {
+        run_dot(self, options, format, Some(path))?;
+    }
+
+    /// Like `render`, but writes the rendered image to the given output.
+    #[throws(Error)]
+    pub fn write_rendered(&self, format: OutputFormat, output: &mut dyn Write) {
+        output.write_all(&run_dot(self, &GraphvizOptions::default(), format, None)?)?;
+    }
+
+    /// Like `write_rendered`, but with the given graph-wide settings.
+    #[throws(Error)]
+    pub fn write_rendered_with(
+        &self,
+        options: &GraphvizOptions,
+        format: OutputFormat,
+        output: &mut dyn Write,
+    ) {
+        output.write_all(&run_dot(self, options, format, None)?)?;
+    }
+}
+
+/// Runs `dot` on `tree`, writing to `path` if given. Returns what `dot`
+/// wrote to stdout.
+#[throws(Error)]
+fn run_dot(
+    tree: &SkillTree,
+    options: &GraphvizOptions,
+    format: OutputFormat,
+    path: Option<&Path>,
+) -> Vec<u8> {
+    let dot_text = tree.to_graphviz_with(options)?;
+    let dot = std::env::var_os(DOT_ENV_VAR).unwrap_or_else(|| OsString::from("dot"));
+
+    let mut command = Command::new(&dot);
+    command.arg(format!("-T{}", format.graphviz_name()));
+    if let Some(path) = path {
+        command.arg("-o").arg(path);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::GraphvizNotFound {
+                program: dot.to_string_lossy().into_owned(),
+                env_var: DOT_ENV_VAR.to_owned(),
+            },
+            _ => Error::from(err),
+        })?;
+
+    // Written from another thread, as `dot` may fill its output pipes
+    // before it has read all of its input.
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(dot_text.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    let written = writer.join().expect("writing to graphviz panicked");
+    if !output.status.success() {
+        throw!(Error::GraphvizFailed {
+            program: dot.to_string_lossy().into_owned(),
+            status: output.status.to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        });
+    }
+    written?;
+    output.stdout
+}
+
+#[cfg(test)]
+mod test;
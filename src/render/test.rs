@@ -0,0 +1,75 @@
+use super::{OutputFormat, DOT_ENV_VAR};
+use crate::{Error, SkillTree};
+
+#[test]
+fn parses_formats() {
+    assert_eq!("png".parse::<OutputFormat>().unwrap(), OutputFormat::Png);
+    assert_eq!("svg".parse::<OutputFormat>().unwrap(), OutputFormat::Svg);
+    assert_eq!("pdf".parse::<OutputFormat>().unwrap(), OutputFormat::Pdf);
+    match "gif".parse::<OutputFormat>() {
+        Err(Error::UnknownFormat { name }) => assert_eq!(name, "gif"),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+// The only test that sets `GRAPHVIZ_DOT`, as tests run in parallel.
+#[test]
+fn runs_graphviz_from_env_var() {
+    #[cfg(unix)]
+    echoes_large_graphs();
+
+    let tree = SkillTree::parse("[[group]]\nname = \"a\"\nitems = []\n").unwrap();
+    let program = "/nonexistent/skill-tree/dot";
+    std::env::set_var(DOT_ENV_VAR, program);
+    let result = tree.write_rendered(OutputFormat::Svg, &mut vec![]);
+    std::env::remove_var(DOT_ENV_VAR);
+
+    let err = result.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not find the graphviz `/nonexistent/skill-tree/dot` executable; install \
+         graphviz (https://graphviz.org/download/) or set `GRAPHVIZ_DOT` to its location"
+    );
+    match err {
+        Error::GraphvizNotFound {
+            program: p,
+            env_var,
+        } => {
+            assert_eq!(p, program);
+            assert_eq!(env_var, DOT_ENV_VAR);
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+/// Renders a graph larger than a pipe's buffer with a `dot` that echoes its
+/// input, which only finishes if that is written while the output is read.
+#[cfg(unix)]
+fn echoes_large_graphs() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("skill-tree-dot-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let program = dir.join("dot");
+    std::fs::write(&program, "#!/bin/sh\ncat\n").unwrap();
+    std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut text = String::new();
+    for i in 0..2000 {
+        text.push_str(&format!(
+            "[[group]]\nname = \"group{}\"\nitems = [{{ label = \"Item {}\" }}]\n",
+            i, i
+        ));
+    }
+    let tree = SkillTree::parse(&text).unwrap();
+    std::env::set_var(DOT_ENV_VAR, &program);
+    let mut output = vec![];
+    let result = tree.write_rendered(OutputFormat::Svg, &mut output);
+    std::env::remove_var(DOT_ENV_VAR);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    result.unwrap();
+    let dot_text = tree.to_graphviz().unwrap();
+    assert!(dot_text.len() > 1 << 16);
+    assert_eq!(String::from_utf8(output).unwrap(), dot_text);
+}
@@ -0,0 +1,94 @@
+//! Finds groups, items, and goals by name or label.
+
+use crate::graph::NodeRef;
+use crate::tree::{GoalIndex, GroupIndex, ItemIndex, SkillTree};
+
+/// A group, item, or goal found by `SkillTree::find`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub node: NodeRef,
+    /// The name or label that matched.
+    pub text: String,
+    /// How many edits away from the query the closest word of `text` is;
+    /// 0 if `text` contains the query.
+    pub distance: usize,
+}
+
+impl SkillTree {
+    /// Finds the groups and goals whose name or label, and the items whose
+    /// label, contains `query`, ignoring case. Matches are listed in the
+    /// order they are defined.
+    pub fn find(&self, query: &str) -> Vec<Match> {
+        self.search(query, false)
+    }
+
+    /// Like `find`, but also matches names and labels with a word that is a
+    /// few typos away from `query`. The closest matches come first.
+    pub fn find_fuzzy(&self, query: &str) -> Vec<Match> {
+        let mut matches = self.search(query, true);
+        matches.sort_by_key(|found| found.distance);
+        matches
+    }
+
+    fn search(&self, query: &str, fuzzy: bool) -> Vec<Match> {
+        let query = query.to_lowercase();
+        let max_distance = if fuzzy {
+            std::cmp::max(2, query.chars().count() / 3)
+        } else {
+            0
+        };
+        let mut matches = vec![];
+        let mut check = |node: NodeRef, texts: &[Option<&String>]| {
+            let best = texts
+                .iter()
+                .flatten()
+                .map(|text| (distance(&query, text), *text))
+                .filter(|&(distance, _)| distance <= max_distance)
+                .min_by_key(|&(distance, _)| distance);
+            if let Some((distance, text)) = best {
+                matches.push(Match {
+                    node,
+                    text: text.clone(),
+                    distance,
+                });
+            }
+        };
+
+        for (g, group) in self.groups().enumerate() {
+            check(
+                NodeRef::Group(GroupIndex(g)),
+                &[Some(&group.name), group.label.as_ref()],
+            );
+            for (i, item) in group.items().enumerate() {
+                check(
+                    NodeRef::Item(GroupIndex(g), ItemIndex(i)),
+                    &[Some(&item.label)],
+                );
+            }
+        }
+        for (g, goal) in self.goals().enumerate() {
+            check(
+                NodeRef::Goal(GoalIndex(g)),
+                &[Some(&goal.name), goal.label.as_ref()],
+            );
+        }
+        matches
+    }
+}
+
+/// 0 if `text` contains `query` (which is lowercase), ignoring case, and
+/// otherwise the edit distance from `query` to the closest word of `text`.
+fn distance(query: &str, text: &str) -> usize {
+    let text = text.to_lowercase();
+    if text.contains(query) {
+        return 0;
+    }
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| strsim::levenshtein(query, word))
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod test;
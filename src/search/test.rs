@@ -0,0 +1,49 @@
+use crate::{GoalIndex, GroupIndex, ItemIndex, NodeRef, SkillTree};
+
+const TREE: &str = r#"
+[[group]]
+name = "parser"
+label = "Parsing"
+items = [{ label = "Expressions" }, { label = "Macro expansion" }]
+
+[[group]]
+name = "macros"
+items = [{ label = "Hygiene" }]
+
+[[goal]]
+name = "release"
+label = "Expression-oriented release"
+"#;
+
+#[test]
+fn finds_substrings() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let nodes: Vec<NodeRef> = tree.find("EXPR").iter().map(|found| found.node).collect();
+    assert_eq!(
+        nodes,
+        [
+            NodeRef::Item(GroupIndex(0), ItemIndex(0)),
+            NodeRef::Goal(GoalIndex(0)),
+        ]
+    );
+
+    let matches = tree.find("macro");
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].text, "Macro expansion");
+    assert_eq!(matches[1].node, NodeRef::Group(GroupIndex(1)));
+    assert!(tree.find("hygeine").is_empty());
+}
+
+#[test]
+fn finds_typos() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let matches = tree.find_fuzzy("hygeine");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].node, NodeRef::Item(GroupIndex(1), ItemIndex(0)));
+    assert_eq!(matches[0].distance, 2);
+
+    // Exact matches come before close ones.
+    let matches = tree.find_fuzzy("parsing");
+    assert_eq!(matches[0].text, "Parsing");
+    assert_eq!(matches[0].distance, 0);
+}
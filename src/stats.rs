@@ -0,0 +1,190 @@
+//! Counts items by status, for progress reports.
+
+use crate::tree::SkillTree;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// How many items there are, and how many of them are done or being
+/// worked on, as classified by the `completed` and `in_progress` flags of
+/// their statuses.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub total: usize,
+    pub completed: usize,
+    pub in_progress: usize,
+}
+
+impl Progress {
+    /// The share of the items that are completed, from 0 to 100. Nothing
+    /// counts as 0% complete.
+    pub fn percent_complete(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.completed as f64 * 100.0 / self.total as f64
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GroupStats {
+    pub name: String,
+    /// How many of the group's items have each status.
+    pub statuses: BTreeMap<String, usize>,
+    pub progress: Progress,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GoalStats {
+    pub name: String,
+    /// The goal's own items plus those of everything it requires, directly
+    /// or indirectly. Soft requirements don't count.
+    pub progress: Progress,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Stats {
+    /// How many items in the whole tree, goals' items included, have each
+    /// status. Aliases count as the status they stand for, and items without
+    /// a status aren't counted.
+    pub statuses: BTreeMap<String, usize>,
+    /// The items of each group, in the order of the groups.
+    pub groups: Vec<GroupStats>,
+    /// In the order of the goals.
+    pub goals: Vec<GoalStats>,
+    /// Every item in the tree, goals' items included.
+    pub overall: Progress,
+}
+
+impl SkillTree {
+    /// Counts the items with each status, per group, per goal, and overall.
+    /// Archived groups, items, and goals aren't counted, unless
+    /// `include_archived` is set.
+    pub fn stats(&self) -> Stats {
+        self.visible().count_all()
+    }
+
+    fn count_all(&self) -> Stats {
+        let mut stats = Stats::default();
+        for group in self.groups() {
+            let mut group_stats = GroupStats {
+                name: group.name.clone(),
+                ..GroupStats::default()
+            };
+            for item in group.items() {
+                let status = self.item_status(group, item);
+                self.count(status, &mut group_stats.statuses, &mut group_stats.progress);
+                self.count(status, &mut stats.statuses, &mut stats.overall);
+            }
+            stats.groups.push(group_stats);
+        }
+        for item in self.goals().flat_map(|goal| goal.items()) {
+            let status = self.goal_item_status(item);
+            self.count(status, &mut stats.statuses, &mut stats.overall);
+        }
+        for goal in self.goals() {
+            let mut progress = Progress::default();
+            let mut statuses = BTreeMap::new();
+            for name in self.needed_for(&goal.name) {
+                if let Some(group) = self.group(name) {
+                    for item in group.items() {
+                        let status = self.item_status(group, item);
+                        self.count(status, &mut statuses, &mut progress);
+                    }
+                } else if let Some(goal) = self.goals().find(|goal| goal.name == name) {
+                    for item in goal.items() {
+                        self.count(self.goal_item_status(item), &mut statuses, &mut progress);
+                    }
+                }
+            }
+            stats.goals.push(GoalStats {
+                name: goal.name.clone(),
+                progress,
+            });
+        }
+        stats
+    }
+
+    fn count(
+        &self,
+        status: Option<&String>,
+        statuses: &mut BTreeMap<String, usize>,
+        progress: &mut Progress,
+    ) {
+        progress.total += 1;
+        if let Some(status) = status {
+            *statuses
+                .entry(self.canonical_status(status).to_owned())
+                .or_insert(0) += 1;
+            match self.status_style(status) {
+                Some(style) if style.completed => progress.completed += 1,
+                Some(style) if style.in_progress => progress.in_progress += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A plain-text summary: the count of each status, then a table of the
+/// progress of each group and goal, then the overall progress.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .statuses
+            .keys()
+            .chain(self.groups.iter().map(|group| &group.name))
+            .chain(self.goals.iter().map(|goal| &goal.name))
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("STATUS".len());
+
+        writeln!(f, "{:width$}  ITEMS", "STATUS", width = width)?;
+        for (status, count) in &self.statuses {
+            writeln!(f, "{:width$}  {:>5}", status, count, width = width)?;
+        }
+
+        let rows = self
+            .groups
+            .iter()
+            .map(|group| ("group", &group.name, &group.progress))
+            .chain(
+                self.goals
+                    .iter()
+                    .map(|goal| ("goal", &goal.name, &goal.progress)),
+            );
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:width$}  KIND   DONE  DOING  TOTAL  COMPLETE",
+            "NAME",
+            width = width
+        )?;
+        for (kind, name, progress) in rows {
+            writeln!(
+                f,
+                "{:width$}  {:5}  {:>4}  {:>5}  {:>5}  {:>7.0}%",
+                name,
+                kind,
+                progress.completed,
+                progress.in_progress,
+                progress.total,
+                progress.percent_complete(),
+                width = width
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{} of {} items complete ({:.0}%), {} in progress",
+            self.overall.completed,
+            self.overall.total,
+            self.overall.percent_complete(),
+            self.overall.in_progress
+        )
+    }
+}
+
+#[cfg(test)]
+mod test;
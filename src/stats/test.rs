@@ -0,0 +1,118 @@
+use crate::{Progress, SkillTree};
+
+const TREE: &str = r#"
+[status.Todo]
+
+[status.Doing]
+in_progress = true
+
+[status.Done]
+completed = true
+aliases = ["Shipped"]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", status = "Done" }, { label = "Spans", status = "Doing" }]
+
+[[group]]
+name = "parser"
+default_status = "Todo"
+items = [{ label = "Expressions", status = "Shipped" }, { label = "Macros" }, { label = "Errors" }]
+
+[[goal]]
+name = "release"
+items = [{ label = "Notes" }]
+"#;
+
+#[test]
+fn counts_statuses() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let stats = tree.stats();
+    let statuses: Vec<(&str, usize)> = stats
+        .statuses
+        .iter()
+        .map(|(status, count)| (status.as_str(), *count))
+        .collect();
+    // The goal's item gets the tree's default status.
+    assert_eq!(
+        statuses,
+        [("Doing", 1), ("Done", 2), ("Todo", 2), ("Unassigned", 1)]
+    );
+    assert_eq!(
+        stats.overall,
+        Progress {
+            total: 6,
+            completed: 2,
+            in_progress: 1,
+        }
+    );
+}
+
+#[test]
+fn counts_groups() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let stats = tree.stats();
+    assert_eq!(stats.groups.len(), 2);
+    assert_eq!(stats.groups[0].name, "lexer");
+    assert_eq!(stats.groups[0].progress.percent_complete(), 50.0);
+    assert_eq!(stats.groups[1].statuses["Todo"], 2);
+    assert_eq!(stats.groups[1].progress.completed, 1);
+    assert_eq!(Progress::default().percent_complete(), 0.0);
+}
+
+#[test]
+fn default_statuses() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Assigned" }, { label = "y", status = "Complete" }]
+"#,
+    )
+    .unwrap();
+    let progress = tree.stats().overall;
+    assert_eq!((progress.completed, progress.in_progress), (1, 1));
+}
+
+#[test]
+fn counts_goals() {
+    let tree = SkillTree::parse(&TREE.replace(
+        "name = \"release\"\n",
+        "name = \"release\"\nrequires = [\"lexer\"]\n",
+    ))
+    .unwrap();
+    let stats = tree.stats();
+    assert_eq!(stats.goals[0].name, "release");
+    assert_eq!(
+        stats.goals[0].progress,
+        Progress {
+            total: 3,
+            completed: 1,
+            in_progress: 1,
+        }
+    );
+}
+
+#[test]
+fn summary_table() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let summary = tree.stats().to_string();
+    assert!(summary.starts_with("STATUS      ITEMS\nDoing           1\n"));
+    assert!(summary.contains("\nlexer       group     1      1      2       50%\n"));
+    assert!(summary.contains("\nrelease     goal      0      0      1        0%\n"));
+    assert!(summary.ends_with("\n2 of 6 items complete (33%), 1 in progress\n"));
+}
+
+#[test]
+fn skips_archived() {
+    let text = TREE.replace(
+        "{ label = \"Macros\" }",
+        "{ label = \"Macros\", archived = true }",
+    );
+    let mut tree = SkillTree::parse(&text).unwrap();
+    assert_eq!(tree.stats().overall.total, 5);
+    assert_eq!(tree.stats().groups[1].statuses["Todo"], 1);
+
+    tree.include_archived = true;
+    assert_eq!(tree.stats().overall.total, 6);
+}
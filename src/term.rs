@@ -0,0 +1,219 @@
+use crate::error::Error;
+use crate::tree::{Group, Item, Requirement, SkillTree, StatusStyle};
+use fehler::throws;
+use std::io::Write;
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const ITALIC: &str = "3";
+const UNDERLINE: &str = "4";
+const STRIKETHROUGH: &str = "9";
+
+impl SkillTree {
+    /// Writes this skill-tree to the given output as an indented outline
+    /// meant for a terminal. Colors are used unless the `NO_COLOR`
+    /// environment variable is set to something other than an empty string.
+    #[throws(Error)]
+    pub fn write_terminal(&self, output: &mut dyn Write) {
+        let color = std::env::var_os("NO_COLOR")
+            .filter(|value| !value.is_empty())
+            .is_none();
+        write_terminal(self, color, output)?
+    }
+
+    /// Like `write_terminal`, but with colors explicitly enabled or disabled.
+    #[throws(Error)]
+    pub fn write_terminal_with_color(&self, color: bool, output: &mut dyn Write) {
+        write_terminal(self, color, output)?
+    }
+}
+
+#[throws(Error)]
+fn write_terminal(tree: &SkillTree, color: bool, output: &mut dyn Write) {
+    let tree = &*tree.visible();
+    let paint = Painter { color };
+
+    for group in tree.groups() {
+        write_group(tree, group, &paint, output)?;
+    }
+
+    let mut goals = tree.goals().peekable();
+    if goals.peek().is_some() {
+        writeln!(output, "{}", paint.apply(&[BOLD], "Goals"))?;
+        for goal in goals {
+            let label = goal.label.as_ref().unwrap_or(&goal.name);
+            let emoji = goal
+                .status
+                .as_ref()
+                .and_then(|status| tree.status_style(status))
+                .and_then(|style| style.emoji.as_deref())
+                .unwrap_or("◎");
+            writeln!(output, "  {} {}", emoji, label)?;
+            write_requires(&goal.requires, &goal.requires_any, "    ", &paint, output)?;
+            for item in goal.items() {
+                write_item(
+                    tree,
+                    tree.goal_item_status(item),
+                    &goal.name,
+                    item,
+                    "    ",
+                    &paint,
+                    output,
+                )?;
+            }
+        }
+    }
+}
+
+#[throws(Error)]
+fn write_group(tree: &SkillTree, group: &Group, paint: &Painter, output: &mut dyn Write) {
+    let label = group.label.as_ref().unwrap_or(&group.name);
+    write!(output, "{}", paint.apply(&[BOLD], label))?;
+    if group.label.is_some() {
+        write!(
+            output,
+            " {}",
+            paint.apply(&[DIM], &format!("({})", group.name))
+        )?;
+    }
+    if let Some(owner) = &group.owner {
+        write!(output, " {}", paint.apply(&[ITALIC], owner))?;
+    }
+    writeln!(output)?;
+    write_requires(&group.requires, &group.requires_any, "  ", paint, output)?;
+
+    for item in group.items() {
+        write_item(
+            tree,
+            tree.item_status(group, item),
+            &group.name,
+            item,
+            "  ",
+            paint,
+            output,
+        )?;
+    }
+}
+
+#[throws(Error)]
+fn write_item(
+    tree: &SkillTree,
+    status: Option<&String>,
+    owner: &str,
+    item: &Item,
+    indent: &str,
+    paint: &Painter,
+    output: &mut dyn Write,
+) {
+    let style = match status.and_then(|x| tree.status_style(x)) {
+        Some(style) => style.clone(),
+        None => StatusStyle::default(),
+    };
+
+    let mut codes = Vec::new();
+    if style.start_tag.contains("<b>") {
+        codes.push(BOLD);
+    }
+    if style.start_tag.contains("<i>") {
+        codes.push(ITALIC);
+    }
+    if style.start_tag.contains("<u>") || tree.item_href(owner, item).is_some() {
+        codes.push(UNDERLINE);
+    }
+    if style.start_tag.contains("<s>") {
+        codes.push(STRIKETHROUGH);
+    }
+    let fontcolor = style.start_tag_color().or(style.fontcolor.as_deref());
+    if let Some(code) = fontcolor.and_then(color_code) {
+        codes.push(code);
+    }
+
+    let label = item.label_text();
+    let emoji = item
+        .emoji
+        .as_deref()
+        .or(style.emoji.as_deref())
+        .unwrap_or(" ");
+    write!(output, "{}{} ", indent, emoji)?;
+    if let Some(priority) = &item.priority {
+        write!(output, "{} ", paint.apply(&[BOLD], priority))?;
+    }
+    write!(output, "{}", paint.apply(&codes, &label))?;
+    if let Some(progress) = item.progress {
+        write!(output, " ({}%)", progress)?;
+    }
+    if let Some(due) = &item.due {
+        write!(output, " (due {})", due)?;
+    }
+    if let Some(owner) = &item.owner {
+        write!(output, " {}", paint.apply(&[ITALIC], owner))?;
+    }
+    if let Some(port) = &item.port {
+        write!(output, " {}", paint.apply(&[DIM], &format!("[{}]", port)))?;
+    }
+    writeln!(output)?;
+    let indent = format!("{}    ", indent);
+    write_requires(&item.requires, &item.requires_any, &indent, paint, output)?;
+}
+
+#[throws(Error)]
+fn write_requires(
+    requires: &Option<Vec<Requirement>>,
+    requires_any: &[Vec<Requirement>],
+    indent: &str,
+    paint: &Painter,
+    output: &mut dyn Write,
+) {
+    for requirement in requires.iter().flatten() {
+        let text = format!("← {}", requirement_text(requirement));
+        writeln!(output, "{}{}", indent, paint.apply(&[DIM], &text))?;
+    }
+    for alternatives in requires_any {
+        let alternatives: Vec<String> = alternatives.iter().map(requirement_text).collect();
+        let text = format!("← any of {}", alternatives.join(", "));
+        writeln!(output, "{}{}", indent, paint.apply(&[DIM], &text))?;
+    }
+}
+
+/// Formats a requirement the way it's written in `requires`, followed by
+/// its label.
+fn requirement_text(requirement: &Requirement) -> String {
+    let soft = if requirement.soft { "?" } else { "" };
+    match &requirement.label {
+        Some(label) => format!("{}{} ({})", requirement.on, soft, label),
+        None => format!("{}{}", requirement.on, soft),
+    }
+}
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    /// Wraps `text` in the given ANSI SGR codes, if colors are enabled.
+    fn apply(&self, codes: &[&str], text: &str) -> String {
+        if !self.color || codes.is_empty() {
+            text.to_owned()
+        } else {
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+        }
+    }
+}
+
+/// Maps the graphviz color names commonly used in status styles to ANSI colors.
+fn color_code(color: &str) -> Option<&'static str> {
+    match color {
+        "black" => Some("30"),
+        "red" | "darkred" | "firebrick" => Some("31"),
+        "green" | "darkgreen" | "forestgreen" => Some("32"),
+        "yellow" | "gold" | "darkgoldenrod" | "orange" => Some("33"),
+        "blue" | "darkblue" | "navy" => Some("34"),
+        "magenta" | "purple" => Some("35"),
+        "cyan" => Some("36"),
+        "grey" | "gray" | "lightgrey" | "lightgray" | "darkgrey" | "darkgray" => Some("90"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test;
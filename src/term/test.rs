@@ -0,0 +1,67 @@
+use crate::SkillTree;
+
+const TREE: &str = r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", status = "Complete" }]
+"#;
+
+fn terminal(tree: &SkillTree) -> String {
+    let mut output = vec![];
+    tree.write_terminal(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn with_color() {
+    let tree = SkillTree::parse(TREE).unwrap();
+    let mut output = vec![];
+    tree.write_terminal_with_color(true, &mut output).unwrap();
+    let colored = String::from_utf8(output).unwrap();
+    assert!(colored.starts_with("\x1b[1mlexer\x1b[0m"));
+
+    let mut output = vec![];
+    tree.write_terminal_with_color(false, &mut output).unwrap();
+    let plain = String::from_utf8(output).unwrap();
+    assert!(plain.starts_with("lexer"));
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.contains("Tokens"));
+}
+
+// The only test that sets `NO_COLOR`, as tests run in parallel.
+#[test]
+fn honors_no_color() {
+    let tree = SkillTree::parse(TREE).unwrap();
+
+    std::env::set_var("NO_COLOR", "1");
+    let disabled = terminal(&tree);
+    // An empty `NO_COLOR` doesn't count as set.
+    std::env::set_var("NO_COLOR", "");
+    let empty = terminal(&tree);
+    std::env::remove_var("NO_COLOR");
+    let unset = terminal(&tree);
+
+    assert!(!disabled.contains('\x1b'));
+    assert!(empty.contains('\x1b'));
+    assert!(unset.contains('\x1b'));
+}
+
+#[test]
+fn decodes_only_html_labels() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+    { label = "a &amp; b" },
+    { label = "c &amp; d", label_html = true },
+]
+"#,
+    )
+    .unwrap();
+    let mut output = vec![];
+    tree.write_terminal_with_color(false, &mut output).unwrap();
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.contains(" a &amp; b\n"));
+    assert!(text.contains(" c & d\n"));
+}
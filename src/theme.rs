@@ -0,0 +1,85 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// A set of colors for the parts of a skill-tree that no status or group
+/// styles explicitly. Select one with `theme = "dark"` in the TOML, or
+/// with `GraphvizOptions::theme`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+/// The colors a theme picks. `None` leaves graphviz's default in place.
+pub(crate) struct Palette {
+    pub(crate) background: Option<&'static str>,
+    /// Default text color.
+    pub(crate) fontcolor: Option<&'static str>,
+    pub(crate) edge_color: Option<&'static str>,
+    /// Color of the lines around and between table cells.
+    pub(crate) border_color: Option<&'static str>,
+    pub(crate) header_color: &'static str,
+    pub(crate) header_fontcolor: Option<&'static str>,
+    /// Fill for items with one of the built-in statuses, in place of the
+    /// `bgcolor` those statuses set.
+    pub(crate) item_bgcolor: Option<&'static str>,
+    pub(crate) goal_color: &'static str,
+    pub(crate) goal_fontcolor: Option<&'static str>,
+    /// Background of the boxes around areas.
+    pub(crate) area_color: &'static str,
+    /// The done and not-yet-done parts of the progress bars under group
+    /// headers.
+    pub(crate) progress_color: &'static str,
+    pub(crate) progress_remaining_color: &'static str,
+}
+
+impl Theme {
+    pub(crate) fn palette(self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: None,
+                fontcolor: None,
+                edge_color: None,
+                border_color: None,
+                header_color: "darkgoldenrod",
+                header_fontcolor: None,
+                item_bgcolor: None,
+                goal_color: "darkgoldenrod",
+                goal_fontcolor: None,
+                area_color: "whitesmoke",
+                progress_color: "forestgreen",
+                progress_remaining_color: "lightgrey",
+            },
+            Theme::Dark => Palette {
+                background: Some("#1e1e1e"),
+                fontcolor: Some("#e0e0e0"),
+                edge_color: Some("#a0a0a0"),
+                border_color: Some("#808080"),
+                header_color: "#8b6914",
+                header_fontcolor: Some("white"),
+                item_bgcolor: Some("#2d2d2d"),
+                goal_color: "#8b6914",
+                goal_fontcolor: Some("white"),
+                area_color: "#262626",
+                progress_color: "#4caf50",
+                progress_remaining_color: "#4a4a4a",
+            },
+            Theme::HighContrast => Palette {
+                background: Some("white"),
+                fontcolor: Some("black"),
+                edge_color: Some("black"),
+                border_color: Some("black"),
+                header_color: "black",
+                header_fontcolor: Some("white"),
+                item_bgcolor: Some("white"),
+                goal_color: "yellow",
+                goal_fontcolor: Some("black"),
+                area_color: "white",
+                progress_color: "black",
+                progress_remaining_color: "white",
+            },
+        }
+    }
+}
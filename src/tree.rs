@@ -1,27 +1,191 @@
-use fehler::throws;
-use serde_derive::Deserialize;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::error::Error;
+use crate::theme::Theme;
+use fehler::{throw, throws};
+use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SkillTree {
-    #[serde(default = "default_status_kinds")]
-    pub status: HashMap<String, StatusStyle>,
-    #[serde(default = "default_status")]
+    /// The version of the format the tree is written in; see
+    /// `LATEST_VERSION`. Trees without one are taken to be version 1.
+    pub version: Option<u32>,
+    /// Shown above the rendered graph, along with `description` and
+    /// `updated`. Only the top-level file's are used, not those of includes.
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// When the plan was last revised, as free-form text (`"2020-03-01"`).
+    pub updated: Option<String>,
+    /// Styles for each status. If no statuses are defined (here, in a
+    /// `status_file`, or in an included file) the default set is used, or
+    /// with `inherit_default_statuses` it is added to those that are.
+    #[serde(default, skip_serializing_if = "is_default_status_kinds")]
+    pub status: BTreeMap<String, StatusStyle>,
+    /// A TOML file, relative to this one, with `[status.*]` tables shared
+    /// between several trees. Statuses defined in the tree itself win.
+    pub status_file: Option<String>,
+    #[serde(default = "default_status", skip_serializing_if = "is_default_status")]
     pub default_status: Option<String>,
+    /// Labels and colors for the areas groups are clustered into. Areas
+    /// that aren't listed here get their name as label and the theme's color.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub area: BTreeMap<String, AreaStyle>,
+    /// Styles for item priorities. `P0` and `P1` get a colored border
+    /// unless they are styled here.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub priority: BTreeMap<String, PriorityStyle>,
+    /// Labels, target dates, and colors for the milestones goals are
+    /// clustered into, like `area` for groups.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub milestone: BTreeMap<String, MilestoneStyle>,
+    /// The stages of the plan, such as quarters, in order. Each is drawn as
+    /// a labeled band holding the groups in it, and the bands are lined up
+    /// one after the other.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phase: Vec<Phase>,
+    /// Word-wrap group, item, and goal labels at this many characters.
+    pub wrap_width: Option<usize>,
+    /// The link for items without an `href`, such as
+    /// `"https://github.com/org/repo/issues/{issue}"`. `{issue}` is the
+    /// item's `issue`, `{port}` its port (made up if it has none, as in
+    /// `Group::ports`), and `{name}` the name of its group or goal. Items
+    /// missing a value the template uses get no link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub href_template: Option<String>,
+    /// Prepended to relative links of groups, items, and goals when
+    /// rendering, e.g. to turn links between the chapters of a book into
+    /// absolute ones for a standalone image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub href_base: Option<String>,
+    /// The statuses to use if none are defined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub palette: Option<StatusPalette>,
+    /// Adds the statuses of `palette` to those defined, instead of only
+    /// using them when none are, so that a tree can define a status of its
+    /// own without copying the built-in ones. Defined statuses win over
+    /// built-in ones with the same name or alias.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inherit_default_statuses: bool,
+    /// Colors for whatever the statuses and groups don't style themselves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+    /// Other skill-tree files, relative to this one, whose groups, goals,
+    /// and statuses are merged into this tree by `SkillTree::load`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// When combined with other trees by `SkillTree::merge`, this tree's
+    /// groups and goals are renamed to `namespace/name`, and other trees
+    /// refer to them that way, as in `requires = ["compiler/parser:lexer"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Lets `requires` entries name groups and goals without matching
+    /// their case or surrounding whitespace, as in `" Parser:lexer"` for
+    /// `parser:lexer`. `check` warns about each entry that relies on it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub loose_references: bool,
+    /// Draws and counts archived groups, items, and goals like any others,
+    /// rather than leaving them out. Usually set with `--include-archived`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_archived: bool,
+    #[serde(default)]
     pub group: Vec<Group>,
     pub goal: Option<Vec<Goal>>,
+    /// Extra graphviz attributes for the whole graph, emitted as is.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub graphviz_attrs: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub(crate) loaded: Loaded,
 }
 
-#[derive(Debug, Default, Clone, Deserialize)]
+/// What `SkillTree::load` added to a tree beyond what its file says, so
+/// `to_toml_string` can write the file back the way it was.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Loaded {
+    /// The file's `include` list, emptied once they are merged.
+    include: Vec<String>,
+    /// Groups and goals merged in from included files.
+    groups: HashSet<String>,
+    goals: HashSet<String>,
+    /// Styles and phases merged in from the `status_file` or included files.
+    status: HashSet<String>,
+    area: HashSet<String>,
+    priority: HashSet<String>,
+    milestone: HashSet<String>,
+    phase: HashSet<String>,
+    /// Icon paths as written, by the path they were resolved to.
+    icons: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct StatusStyle {
     pub emoji: Option<String>,
+    /// Plain text shown instead of `emoji` when rendering without emoji,
+    /// such as `[x]`.
+    pub marker: Option<String>,
     pub bgcolor: Option<String>,
     pub fontcolor: Option<String>,
     #[serde(default)]
     pub start_tag: String,
     #[serde(default)]
     pub end_tag: String,
+    /// Graphviz `style` of edges out of items with this status, such as
+    /// `dotted`. Soft requirements are drawn `dashed` whatever the status,
+    /// so `dashed` would make hard ones look soft.
+    pub edge_style: Option<String>,
+    pub edge_color: Option<String>,
+    /// Other names items may use for this status, such as `Done` for
+    /// `Complete`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Whether items with this status count as done in a group's rollup.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub completed: bool,
+    /// Whether items with this status count as being worked on in `stats`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_progress: bool,
+}
+
+impl StatusStyle {
+    /// The color set by a `<font color="...">` in `start_tag`, if any.
+    pub(crate) fn start_tag_color(&self) -> Option<&str> {
+        let start = self.start_tag.find("color=\"")? + "color=\"".len();
+        let len = self.start_tag[start..].find('"')?;
+        Some(&self.start_tag[start..start + len])
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AreaStyle {
+    pub label: Option<String>,
+    pub bgcolor: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PriorityStyle {
+    /// Shown before the label of items with this priority; defaults to the
+    /// priority's name.
+    pub badge: Option<String>,
+    /// Color of the border around the item.
+    pub color: Option<String>,
+    pub bgcolor: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MilestoneStyle {
+    pub label: Option<String>,
+    /// When the milestone is due, as free-form text; shown under the label.
+    pub date: Option<String>,
+    pub bgcolor: Option<String>,
+}
+
+/// An entry in `SkillTree::phase`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Phase {
+    pub name: String,
+    pub label: Option<String>,
+    pub bgcolor: Option<String>,
 }
 
 const WATCH_EMOJI: &str = "⌚";
@@ -29,105 +193,743 @@ const HAMMER_WRENCH_EMOJI: &str = "🛠️";
 const CHECKED_BOX_EMOJI: &str = "☑️";
 const RAISED_HAND_EMOJI: &str = "🙋";
 
-fn default_status_kinds() -> HashMap<String, StatusStyle> {
+#[rustfmt::skip]
+fn default_status_kinds() -> BTreeMap<String, StatusStyle> {
     vec![
         // Can't work on it now
         ("Blocked".to_owned(), StatusStyle {
             emoji: Some(WATCH_EMOJI.to_owned()),
+            marker: Some("[-]".to_owned()),
             bgcolor: Some("cornsilk".to_owned()),
             fontcolor: None,
             start_tag: "<i><font color=\"lightgrey\">".to_owned(),
             end_tag: "</font></i>".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: Some("grey".to_owned()),
+            aliases: vec![],
+            completed: false,
+            in_progress: false,
         }),
 
         // Would like to work on it, but need someone
         ("Unassigned".to_owned(), StatusStyle {
             emoji: Some(RAISED_HAND_EMOJI.to_owned()),
+            marker: Some("[ ]".to_owned()),
             bgcolor: Some("cornsilk".to_owned()),
             fontcolor: Some("red".to_owned()),
             start_tag: "".to_owned(),
             end_tag: "".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: None,
+            aliases: vec![],
+            completed: false,
+            in_progress: false,
         }),
 
         // People are actively working on it
         ("Assigned".to_owned(), StatusStyle {
             emoji: Some(HAMMER_WRENCH_EMOJI.to_owned()),
+            marker: Some("[~]".to_owned()),
             bgcolor: Some("cornsilk".to_owned()),
             fontcolor: None,
             start_tag: "".to_owned(),
             end_tag: "".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: None,
+            aliases: vec![],
+            completed: false,
+            in_progress: true,
         }),
 
         // This is done!
         ("Complete".to_owned(), StatusStyle {
             emoji: Some(CHECKED_BOX_EMOJI.to_owned()),
+            marker: Some("[x]".to_owned()),
             bgcolor: Some("cornsilk".to_owned()),
             fontcolor: None,
             start_tag: "<s>".to_owned(),
             end_tag: "</s>".to_owned(),
+            edge_style: None,
+            edge_color: None,
+            aliases: vec![],
+            completed: true,
+            in_progress: false,
+        }),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// The default statuses with the Okabe-Ito colors, which stay distinct
+/// under the common kinds of color blindness. Each status also has its own
+/// text style, so none is told apart by color alone.
+#[rustfmt::skip]
+fn colorblind_status_kinds() -> BTreeMap<String, StatusStyle> {
+    vec![
+        ("Blocked".to_owned(), StatusStyle {
+            emoji: Some(WATCH_EMOJI.to_owned()),
+            marker: Some("[-]".to_owned()),
+            bgcolor: Some("#f7f7f7".to_owned()),
+            fontcolor: Some("#999999".to_owned()),
+            start_tag: "<i>".to_owned(),
+            end_tag: "</i>".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: Some("#999999".to_owned()),
+            aliases: vec![],
+            completed: false,
+            in_progress: false,
+        }),
+
+        ("Unassigned".to_owned(), StatusStyle {
+            emoji: Some(RAISED_HAND_EMOJI.to_owned()),
+            marker: Some("[ ]".to_owned()),
+            bgcolor: Some("#f7f7f7".to_owned()),
+            fontcolor: Some("#d55e00".to_owned()),
+            start_tag: "<b>".to_owned(),
+            end_tag: "</b>".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: None,
+            aliases: vec![],
+            completed: false,
+            in_progress: false,
+        }),
+
+        ("Assigned".to_owned(), StatusStyle {
+            emoji: Some(HAMMER_WRENCH_EMOJI.to_owned()),
+            marker: Some("[~]".to_owned()),
+            bgcolor: Some("#f7f7f7".to_owned()),
+            fontcolor: Some("#0072b2".to_owned()),
+            start_tag: "<u>".to_owned(),
+            end_tag: "</u>".to_owned(),
+            edge_style: Some("dotted".to_owned()),
+            edge_color: None,
+            aliases: vec![],
+            completed: false,
+            in_progress: true,
+        }),
+
+        ("Complete".to_owned(), StatusStyle {
+            emoji: Some(CHECKED_BOX_EMOJI.to_owned()),
+            marker: Some("[x]".to_owned()),
+            bgcolor: Some("#f7f7f7".to_owned()),
+            fontcolor: Some("#009e73".to_owned()),
+            start_tag: "<s>".to_owned(),
+            end_tag: "</s>".to_owned(),
+            edge_style: None,
+            edge_color: None,
+            aliases: vec![],
+            completed: true,
+            in_progress: false,
         }),
     ]
     .into_iter()
     .collect()
 }
 
-fn default_status() -> Option<String> {
+/// Which set of statuses a tree gets when it defines none of its own.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusPalette {
+    #[default]
+    Default,
+    Colorblind,
+}
+
+impl StatusPalette {
+    fn status_kinds(self) -> BTreeMap<String, StatusStyle> {
+        match self {
+            StatusPalette::Default => default_status_kinds(),
+            StatusPalette::Colorblind => colorblind_status_kinds(),
+        }
+    }
+}
+
+/// Whether `style` is a built-in style for the status `name`, which themes
+/// are free to recolor.
+pub(crate) fn is_builtin_style(name: &str, style: &StatusStyle) -> bool {
+    default_status_kinds().get(name) == Some(style)
+        || colorblind_status_kinds().get(name) == Some(style)
+}
+
+pub(crate) fn default_status() -> Option<String> {
     Some("Unassigned".to_owned())
 }
 
+fn is_default_status_kinds(status: &BTreeMap<String, StatusStyle>) -> bool {
+    status.is_empty() || *status == default_status_kinds() || *status == colorblind_status_kinds()
+}
+
+fn is_default_status(status: &Option<String>) -> bool {
+    *status == default_status()
+}
+
+/// The contents of a `status_file`.
 #[derive(Debug, Deserialize)]
+struct StatusFile {
+    #[serde(default)]
+    status: BTreeMap<String, StatusStyle>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Goal {
     pub name: String,
     pub label: Option<String>,
-    pub requires: Option<Vec<String>>,
+    pub requires: Option<Vec<Requirement>>,
+    /// Sets of alternatives, each satisfied by any one of its requirements.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_any: Vec<Vec<Requirement>>,
     pub href: Option<String>,
+    /// Open `href` in a new tab (or window) when the goal is clicked in an
+    /// SVG.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub new_tab: bool,
+    /// Like `Group::archived`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Like `Group::rank`.
+    pub rank: Option<u32>,
+    /// A longer explanation than fits in the label, shown as a tooltip.
+    pub description: Option<String>,
+    /// Whether the goal has been reached. Its emoji is shown before the
+    /// label, which is styled like an item's.
+    pub status: Option<String>,
+    /// Fill color of the goal's node, instead of the theme's goal color.
+    pub fillcolor: Option<String>,
+    /// Graphviz shape of the goal's node; `note` if not set.
+    pub shape: Option<String>,
+    /// Goals with the same milestone are drawn together in a labeled box.
+    pub milestone: Option<String>,
+    /// Like `Item::due`.
+    pub due: Option<String>,
+    /// A checklist of what it takes to reach the goal, shown inside its
+    /// node like a group's items. Other nodes can't require these items.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Item>,
+    /// Extra graphviz attributes for the goal's node, emitted as is.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub graphviz_attrs: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Group {
     pub name: String,
     pub label: Option<String>,
-    pub requires: Option<Vec<String>>,
+    pub requires: Option<Vec<Requirement>>,
+    /// Sets of alternatives, each satisfied by any one of its requirements.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_any: Vec<Vec<Requirement>>,
     pub items: Vec<Item>,
+    /// Maximum width of the group, in points. Item labels that would be
+    /// wider are wrapped onto several lines.
     pub width: Option<f64>,
+    /// The status of the group itself, as shown on edges out of it. Also
+    /// used by its items, unless `default_status` is set.
     pub status: Option<String>,
+    /// The status of items in the group that don't have one. Takes
+    /// precedence over `status` and over the tree's `default_status`.
+    pub default_status: Option<String>,
     pub href: Option<String>,
+    /// Shown when hovering over the group's header.
+    pub tooltip: Option<String>,
+    /// Who is responsible for the group, shown in its header.
+    #[serde(alias = "assignee")]
+    pub owner: Option<String>,
+    /// Whether to draw the group as just its header once all of its items
+    /// are completed. Defaults to `GraphvizOptions::collapse_completed`.
+    pub collapse: Option<Collapse>,
+    /// Tags that apply to every item in the group. See `SkillTree::with_tags`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Finished work kept around so that requirements on it still resolve.
+    /// Archived groups are left out when drawing the tree and counting its
+    /// items; see `SkillTree::without_archived`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// An image shown next to the label, e.g. a logo. Paths are relative to
+    /// the file the group is defined in.
+    pub icon: Option<String>,
+    /// A second link for the header, e.g. to a design doc when `href` links
+    /// the tracking issue.
+    pub details_href: Option<String>,
     pub header_color: Option<String>,
+    /// Groups with the same area are drawn together in a labeled box, e.g.
+    /// one per team or component.
+    pub area: Option<String>,
+    /// Groups and goals with the same rank are lined up in one column (one
+    /// row, with a top-to-bottom `rankdir`), and lower ranks come first.
+    pub rank: Option<u32>,
+    /// The name of the phase the group is part of; see `SkillTree::phase`.
+    pub phase: Option<String>,
+    /// Extra graphviz attributes for the group's node, emitted as is.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub graphviz_attrs: BTreeMap<String, String>,
+    /// Sub-groups, written as `[[group.group]]`. When the tree is parsed
+    /// they are moved into its list of groups, right after this one, with
+    /// `parent` set, so they can be required like any other group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<Group>,
+    /// The name of the group this one is a sub-group of. Sub-groups are
+    /// drawn in a box together with their parent, and share its area and
+    /// phase unless they have their own.
+    pub parent: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Collapse {
+    Never,
+    WhenComplete,
 }
 
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct GroupIndex(pub usize);
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Item {
     pub label: String,
+    /// Passes HTML tags and entities in the label through to graphviz
+    /// rather than escaping them, for labels with intentional markup.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub label_html: bool,
     pub href: Option<String>,
+    /// The item's issue number, for the tree's `href_template`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<u64>,
+    /// The name other nodes use to require this item, as `group:port`.
+    /// Items without one get a port made from their label; see
+    /// `Group::ports`.
     pub port: Option<String>,
-    pub requires: Option<Vec<String>>,
+    pub requires: Option<Vec<Requirement>>,
+    /// Sets of alternatives, each satisfied by any one of its requirements.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_any: Vec<Vec<Requirement>>,
     pub status: Option<String>,
+    /// A longer explanation than fits in the label, shown as a tooltip.
+    pub description: Option<String>,
+    /// Shown in place of the status's emoji, e.g. 🔥 for an item at risk.
+    pub emoji: Option<String>,
+    /// How far along the item is, as a percentage. Shown after the label
+    /// and counted towards the group's progress bar.
+    pub progress: Option<u8>,
+    /// An estimate of the work the item takes, in any unit, e.g. story
+    /// points. See `SkillTree::effort_summary`.
+    pub effort: Option<f64>,
+    /// Who is working on the item, shown after its label.
+    #[serde(alias = "assignee")]
+    pub owner: Option<String>,
+    /// Free-form tags, such as a team or a topic, to filter the tree by.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Like `Group::archived`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// How urgent the item is, from `P0` (most) to `P3` (least).
+    pub priority: Option<String>,
+    /// When the item should be done, as `YYYY-MM-DD`. Items that aren't
+    /// completed by then are highlighted as overdue (as of
+    /// `GraphvizOptions::as_of`).
+    pub due: Option<String>,
+    /// An image shown next to the status emoji, e.g. a logo. Paths are
+    /// relative to the file the item is defined in.
+    pub icon: Option<String>,
+    /// Extra attributes for the table cell holding the item's label. Items
+    /// are cells in their group's node, so only HTML-label cell attributes
+    /// such as `tooltip` or `title` apply.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub graphviz_attrs: BTreeMap<String, String>,
 }
 
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct ItemIndex(pub usize);
 
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct GoalIndex(pub usize);
+
+/// An entry in `requires`: the name of a group or goal, or `group:port` or
+/// `group/item label` for an item, optionally with a label for the edge.
+/// Written as a plain string or as
+/// `{ on = "parser:lexer", label = "needs stable API" }`. A trailing `?`,
+/// as in `"parser:lexer?"`, marks the requirement as soft.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "RequirementRepr", into = "RequirementRepr")]
+pub struct Requirement {
+    pub on: String,
+    pub label: Option<String>,
+    /// Soft requirements are "nice to have first" rather than blockers.
+    /// They are drawn dashed, and don't affect layout or cycle checks.
+    pub soft: bool,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum RequirementRepr {
+    Plain(String),
+    Labeled { on: String, label: Option<String> },
+}
+
+impl From<RequirementRepr> for Requirement {
+    fn from(repr: RequirementRepr) -> Self {
+        match repr {
+            RequirementRepr::Plain(on) => Requirement::from(on),
+            RequirementRepr::Labeled { on, label } => Requirement {
+                label,
+                ..Requirement::from(on)
+            },
+        }
+    }
+}
+
+impl From<Requirement> for RequirementRepr {
+    fn from(requirement: Requirement) -> Self {
+        let on = if requirement.soft {
+            format!("{}?", requirement.on)
+        } else {
+            requirement.on
+        };
+        match requirement.label {
+            None => RequirementRepr::Plain(on),
+            Some(label) => RequirementRepr::Labeled {
+                on,
+                label: Some(label),
+            },
+        }
+    }
+}
+
+impl From<String> for Requirement {
+    fn from(mut on: String) -> Self {
+        let soft = on.ends_with('?');
+        if soft {
+            on.pop();
+        }
+        Requirement {
+            on,
+            label: None,
+            soft,
+        }
+    }
+}
+
+impl From<&str> for Requirement {
+    fn from(on: &str) -> Self {
+        Requirement::from(on.to_owned())
+    }
+}
+
+#[throws(Error)]
+fn read_file(path: &Path) -> String {
+    std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: Some(path.to_owned()),
+        source,
+    })?
+}
+
+/// Splits a requirement of the form `name` or `name:port` into its parts.
+pub(crate) fn split_requirement(requirement: &str) -> (&str, Option<&str>) {
+    match requirement.find(':') {
+        Some(index) => (&requirement[..index], Some(&requirement[index + 1..])),
+        None => (requirement, None),
+    }
+}
+
+/// The words of `label`, made of ASCII letters and digits, lowercased and
+/// joined with `_`, if there are any.
+fn port_slug(label: &str) -> Option<String> {
+    let words: Vec<String> = label
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join("_"))
+    }
+}
+
+/// Adds to `noted` the names in `included` that `existing` doesn't have.
+fn note_new<T>(
+    noted: &mut HashSet<String>,
+    existing: &BTreeMap<String, T>,
+    included: &BTreeMap<String, T>,
+) {
+    noted.extend(
+        included
+            .keys()
+            .filter(|name| !existing.contains_key(*name))
+            .cloned(),
+    );
+}
+
+/// The namespace a group or goal name of the form `namespace/name` is in,
+/// if it has one.
+pub(crate) fn namespace_of(name: &str) -> Option<&str> {
+    name.find('/').map(|index| &name[..index])
+}
+
 impl SkillTree {
-    #[throws(anyhow::Error)]
+    /// Loads a skill-tree from `path`. Files ending in `.json` are parsed as
+    /// JSON, and with the `serde_yaml` feature those ending in `.yaml` or
+    /// `.yml` as YAML; everything else is parsed as TOML. Any files listed
+    /// in `include` are loaded and merged in as well.
+    #[throws(Error)]
     pub fn load(path: &Path) -> SkillTree {
-        let skill_tree_text = std::fs::read_to_string(path)?;
-        Self::parse(&skill_tree_text)?
+        let mut tree = Self::load_including(path, &mut vec![], &mut HashSet::new())?;
+        tree.add_default_statuses();
+        tree
+    }
+
+    /// Loads `path` and, recursively, its includes. `stack` holds the files
+    /// currently being loaded (to detect cycles) and `seen` every file loaded
+    /// so far (so a file included from two places is only merged once).
+    #[throws(Error)]
+    fn load_including(
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        seen: &mut HashSet<PathBuf>,
+    ) -> SkillTree {
+        let canonical = path.canonicalize().map_err(|source| Error::Io {
+            path: Some(path.to_owned()),
+            source,
+        })?;
+        if let Some(index) = stack.iter().position(|p| *p == canonical) {
+            let mut cycle = stack[index..].to_vec();
+            cycle.push(canonical);
+            throw!(Error::IncludeCycle { cycle });
+        }
+        seen.insert(canonical.clone());
+
+        let skill_tree_text = read_file(path)?;
+        let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => SkillTree::from_json(&skill_tree_text),
+            #[cfg(feature = "serde_yaml")]
+            Some("yaml") | Some("yml") => SkillTree::from_yaml(&skill_tree_text),
+            _ => SkillTree::from_toml(&skill_tree_text),
+        };
+        let mut tree = parsed.map_err(|e| e.with_path(path))?;
+        tree.flatten_subgroups();
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        tree.resolve_icons(base);
+        if let Some(status_file) = &tree.status_file {
+            let status_path = base.join(status_file);
+            let status_text = read_file(&status_path)?;
+            let shared: StatusFile =
+                toml::from_str(&status_text).map_err(|e| Error::from(e).with_path(&status_path))?;
+            for (name, style) in shared.status {
+                if !tree.status.contains_key(&name) {
+                    tree.loaded.status.insert(name.clone());
+                    tree.status.insert(name, style);
+                }
+            }
+        }
+
+        stack.push(canonical);
+        // Once merged, the includes are part of this tree; `loaded` keeps
+        // them for `to_toml_string`.
+        tree.loaded.include = std::mem::take(&mut tree.include);
+        for include in tree.loaded.include.clone() {
+            let include_path = base.join(&include);
+            // Files included from more than one place are only merged once;
+            // cycles are reported by the recursive call.
+            if let Ok(canonical) = include_path.canonicalize() {
+                if seen.contains(&canonical) && !stack.contains(&canonical) {
+                    continue;
+                }
+            }
+            let included =
+                Self::load_including(&include_path, stack, seen).map_err(|e| Error::Include {
+                    include: include.clone(),
+                    from: path.to_owned(),
+                    source: Box::new(e),
+                })?;
+            tree.note_included(&included);
+            tree.merge_included(included);
+        }
+        stack.pop();
+
+        tree
+    }
+
+    /// Makes the icon paths in this tree, which are relative to the file it
+    /// was loaded from, relative to the current directory instead. URLs are
+    /// left alone.
+    fn resolve_icons(&mut self, base: &Path) {
+        let icons = &mut self.loaded.icons;
+        let mut resolve = |icon: &mut Option<String>| {
+            if let Some(icon) = icon {
+                if !icon.contains("://") {
+                    let resolved = base.join(&*icon).to_string_lossy().into_owned();
+                    icons.insert(resolved.clone(), std::mem::replace(icon, resolved));
+                }
+            }
+        };
+        for group in &mut self.group {
+            resolve(&mut group.icon);
+            for item in &mut group.items {
+                resolve(&mut item.icon);
+            }
+        }
+    }
+
+    /// Records which of `included`'s contents are new to this tree, before
+    /// they are merged in by `merge_included`.
+    fn note_included(&mut self, included: &SkillTree) {
+        let loaded = &mut self.loaded;
+        loaded
+            .groups
+            .extend(included.group.iter().map(|group| group.name.clone()));
+        loaded
+            .goals
+            .extend(included.goals().map(|goal| goal.name.clone()));
+        note_new(&mut loaded.status, &self.status, &included.status);
+        note_new(&mut loaded.area, &self.area, &included.area);
+        note_new(&mut loaded.priority, &self.priority, &included.priority);
+        note_new(&mut loaded.milestone, &self.milestone, &included.milestone);
+        for phase in &included.phase {
+            if !self.phase.iter().any(|p| p.name == phase.name) {
+                loaded.phase.insert(phase.name.clone());
+            }
+        }
+    }
+
+    /// Undoes what `load` did beyond parsing the file: lists the includes
+    /// again, leaves out what they and the `status_file` added, and puts
+    /// back icon paths as written.
+    fn unload(&mut self) {
+        let Loaded {
+            include,
+            groups,
+            goals,
+            status,
+            area,
+            priority,
+            milestone,
+            phase,
+            icons,
+        } = std::mem::take(&mut self.loaded);
+        self.include = include;
+        self.group.retain(|group| !groups.contains(&group.name));
+        if let Some(kept) = &mut self.goal {
+            kept.retain(|goal| !goals.contains(&goal.name));
+            if kept.is_empty() {
+                self.goal = None;
+            }
+        }
+        self.status.retain(|name, _| !status.contains(name));
+        self.area.retain(|name, _| !area.contains(name));
+        self.priority.retain(|name, _| !priority.contains(name));
+        self.milestone.retain(|name, _| !milestone.contains(name));
+        self.phase.retain(|p| !phase.contains(&p.name));
+        let restore = |icon: &mut Option<String>| {
+            if let Some(written) = icon.as_ref().and_then(|icon| icons.get(icon)) {
+                *icon = Some(written.clone());
+            }
+        };
+        for group in &mut self.group {
+            restore(&mut group.icon);
+            for item in &mut group.items {
+                restore(&mut item.icon);
+            }
+        }
     }
 
-    #[throws(anyhow::Error)]
+    /// Merges the contents of an included file into this tree. Statuses,
+    /// areas, priorities, milestones, and phases defined by the including
+    /// file take precedence; the included file's other phases come after
+    /// this file's.
+    pub(crate) fn merge_included(&mut self, included: SkillTree) {
+        for (name, style) in included.status {
+            self.status.entry(name).or_insert(style);
+        }
+        for (name, style) in included.area {
+            self.area.entry(name).or_insert(style);
+        }
+        for (name, style) in included.priority {
+            self.priority.entry(name).or_insert(style);
+        }
+        for (name, style) in included.milestone {
+            self.milestone.entry(name).or_insert(style);
+        }
+        for phase in included.phase {
+            if !self.phase.iter().any(|p| p.name == phase.name) {
+                self.phase.push(phase);
+            }
+        }
+        self.group.extend(included.group);
+        if let Some(goals) = included.goal {
+            self.goal.get_or_insert_with(Vec::new).extend(goals);
+        }
+    }
+
+    /// Parses a skill-tree from TOML text.
+    #[throws(Error)]
     pub fn parse(text: &str) -> SkillTree {
-        toml::from_str(text)?
+        let mut tree = SkillTree::from_toml(text)?;
+        tree.flatten_subgroups();
+        tree.add_default_statuses();
+        tree
     }
 
-    #[throws(anyhow::Error)]
-    pub fn validate(&self) {
-        // gather: valid requires entries
+    /// Reads TOML from `reader` (stdin, say) and parses it as a skill-tree.
+    #[throws(Error)]
+    pub fn parse_reader(mut reader: impl Read) -> SkillTree {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Self::parse(&text)?
+    }
+
+    /// Parses a skill-tree from JSON text, using the same structure as the TOML format.
+    #[throws(Error)]
+    pub fn parse_json(text: &str) -> SkillTree {
+        let mut tree = SkillTree::from_json(text)?;
+        tree.flatten_subgroups();
+        tree.add_default_statuses();
+        tree
+    }
 
-        for group in &self.group {
-            group.validate()?;
+    /// Parses a skill-tree from YAML text, using the same structure as the TOML format.
+    #[cfg(feature = "serde_yaml")]
+    #[throws(Error)]
+    pub fn parse_yaml(text: &str) -> SkillTree {
+        let mut tree = SkillTree::from_yaml(text)?;
+        tree.flatten_subgroups();
+        tree.add_default_statuses();
+        tree
+    }
+
+    /// Serializes this skill-tree back into TOML. Statuses that are just the
+    /// defaults are left out, as are inherited ones. For a tree from `load`,
+    /// what came from includes and the `status_file` is left out as well,
+    /// and icon paths are written as they were in the file.
+    #[throws(Error)]
+    pub fn to_toml_string(&self) -> String {
+        let mut tree = Cow::Borrowed(self);
+        if self.loaded != Loaded::default() {
+            tree.to_mut().unload();
+        }
+        if self.inherit_default_statuses {
+            let defaults = self.palette.unwrap_or_default().status_kinds();
+            tree.to_mut()
+                .status
+                .retain(|name, style| defaults.get(name) != Some(style));
+        }
+        // Going through `toml::Value` takes care of emitting plain values
+        // before tables, as TOML requires.
+        toml::Value::try_from(&*tree)?.to_string()
+    }
+
+    /// Falls back to the default statuses if none were defined, or adds
+    /// those that weren't with `inherit_default_statuses`.
+    pub(crate) fn add_default_statuses(&mut self) {
+        if self.status.is_empty() {
+            self.status = self.palette.unwrap_or_default().status_kinds();
+        } else if self.inherit_default_statuses {
+            for (name, style) in self.palette.unwrap_or_default().status_kinds() {
+                if self.status_style(&name).is_none() {
+                    self.status.insert(name, style);
+                }
+            }
         }
     }
 
@@ -142,16 +944,389 @@ impl SkillTree {
     pub fn groups(&self) -> impl Iterator<Item = &Group> {
         self.group.iter()
     }
+
+    /// The group `group` is a sub-group of, if it has a parent and that
+    /// parent exists.
+    pub fn parent_group(&self, group: &Group) -> Option<&Group> {
+        self.group(group.parent.as_deref()?)
+    }
+
+    /// The groups whose parent is the group called `name`.
+    pub fn subgroups<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Group> {
+        self.groups()
+            .filter(move |group| group.parent.as_deref() == Some(name))
+    }
+
+    /// Moves the sub-groups written inside other groups into the tree's own
+    /// list of groups, each right after its parent.
+    pub(crate) fn flatten_subgroups(&mut self) {
+        fn push(groups: &mut Vec<Group>, mut group: Group) {
+            let subgroups = std::mem::take(&mut group.group);
+            let (name, area, phase) = (group.name.clone(), group.area.clone(), group.phase.clone());
+            groups.push(group);
+            for mut subgroup in subgroups {
+                subgroup.parent = Some(name.clone());
+                if subgroup.area.is_none() {
+                    subgroup.area = area.clone();
+                }
+                if subgroup.phase.is_none() {
+                    subgroup.phase = phase.clone();
+                }
+                push(groups, subgroup);
+            }
+        }
+
+        for group in std::mem::take(&mut self.group) {
+            push(&mut self.group, group);
+        }
+    }
+
+    /// Resolves a requirement written as `group/item label` to the
+    /// `group:port` of that item, making up the port if it has none (see
+    /// `Group::ports`). With `loose_references`, a group or goal name that
+    /// only matches ignoring case and whitespace is replaced by the name it
+    /// matches. Anything else, including the names of groups and goals with
+    /// a `/` in them, is returned as it is.
+    pub fn resolve_requirement<'r>(&self, requirement: &'r str) -> Cow<'r, str> {
+        if let Some((name, group, index)) = self.item_path(requirement) {
+            return Cow::Owned(format!("{}:{}", name, group.port_of(index)));
+        }
+        let (name, port) = split_requirement(requirement);
+        match (self.loose_match(name), port) {
+            (Some(name), Some(port)) => Cow::Owned(format!("{}:{}", name, port)),
+            (Some(name), None) => Cow::Owned(name.to_owned()),
+            (None, _) => Cow::Borrowed(requirement),
+        }
+    }
+
+    /// With `loose_references`, the name of the group or goal that `name`
+    /// matches when case and surrounding whitespace are ignored, if it
+    /// doesn't match one exactly.
+    pub(crate) fn loose_match(&self, name: &str) -> Option<&str> {
+        if !self.loose_references || self.group(name).is_some() || self.is_goal(name) {
+            return None;
+        }
+        let name = name.trim().to_lowercase();
+        self.groups()
+            .map(|group| group.name.as_str())
+            .chain(self.goals().map(|goal| goal.name.as_str()))
+            .find(|candidate| candidate.to_lowercase() == name)
+    }
+
+    /// Returns a copy of the tree with every requirement replaced by what
+    /// `resolve_requirement` makes of it, so that what renders it only has
+    /// to deal with exact names and ports.
+    pub(crate) fn with_resolved_requirements(&self) -> SkillTree {
+        let mut tree = self.clone();
+        let resolve = |requirement: &mut Requirement| {
+            if let Cow::Owned(on) = self.resolve_requirement(&requirement.on) {
+                requirement.on = on;
+            }
+        };
+        for group in &mut tree.group {
+            group
+                .requires
+                .iter_mut()
+                .flatten()
+                .chain(group.requires_any.iter_mut().flatten())
+                .for_each(resolve);
+            for item in &mut group.items {
+                item.requires
+                    .iter_mut()
+                    .flatten()
+                    .chain(item.requires_any.iter_mut().flatten())
+                    .for_each(resolve);
+            }
+        }
+        for goal in tree.goal.iter_mut().flatten() {
+            goal.requires
+                .iter_mut()
+                .flatten()
+                .chain(goal.requires_any.iter_mut().flatten())
+                .for_each(resolve);
+        }
+        tree
+    }
+
+    /// The name of the group or goal `requirement` refers to, like
+    /// `split_requirement`, but knowing about `group/item label` and
+    /// `loose_references` too.
+    pub(crate) fn required_name<'a>(&'a self, requirement: &'a str) -> &'a str {
+        if let Some((name, _, _)) = self.item_path(requirement) {
+            return name;
+        }
+        let name = split_requirement(requirement).0;
+        self.loose_match(name).unwrap_or(name)
+    }
+
+    /// Splits a requirement of the form `group/item label` into the group's
+    /// name, the group, and the index of the item. The group's name may
+    /// itself have a `/` in it, so each is tried in turn.
+    fn item_path<'r>(&self, requirement: &'r str) -> Option<(&'r str, &Group, ItemIndex)> {
+        if self.group(requirement).is_some() || self.is_goal(requirement) {
+            return None;
+        }
+        requirement.match_indices('/').find_map(|(index, _)| {
+            let (name, label) = (&requirement[..index], &requirement[index + 1..]);
+            let group = self.group(name)?;
+            let item = group.items().position(|item| item.label == label)?;
+            Some((name, group, ItemIndex(item)))
+        })
+    }
+
+    /// Returns the status of whatever `requirement` refers to: the item's,
+    /// for `name:port` or `name/label`, or the group's or goal's own status,
+    /// for a plain name.
+    pub(crate) fn requirement_status(&self, requirement: &str) -> Option<&String> {
+        let requirement = self.resolve_requirement(requirement);
+        let (name, port) = split_requirement(&requirement);
+        if let Some(goal) = self.goals().find(|goal| goal.name == name) {
+            return goal.status.as_ref();
+        }
+        let group = self.groups().find(|group| group.name == name)?;
+        match port {
+            Some(port) => {
+                let ItemIndex(index) = group.item_with_port(port)?;
+                self.item_status(group, &group.items[index])
+            }
+            None => group.status.as_ref(),
+        }
+    }
+
+    /// Returns the status that applies to `item` in a goal's checklist,
+    /// falling back to the tree's default status.
+    pub fn goal_item_status<'a>(&'a self, item: &'a Item) -> Option<&'a String> {
+        item.status.as_ref().or(self.default_status.as_ref())
+    }
+
+    /// Whether `status` is one that counts as done.
+    pub fn is_completed(&self, status: Option<&String>) -> bool {
+        let style = status.and_then(|status| self.status_style(status));
+        matches!(style, Some(style) if style.completed)
+    }
+
+    /// Returns the share of the group's work that is done, from 0 to 1.
+    /// Completed items count fully and others by their `progress`.
+    pub fn group_completion(&self, group: &Group) -> f64 {
+        if group.items.is_empty() {
+            return 0.0;
+        }
+        let done: f64 = group
+            .items()
+            .map(|item| {
+                if self.is_completed(self.item_status(group, item)) {
+                    1.0
+                } else {
+                    f64::from(item.progress.unwrap_or(0).min(100)) / 100.0
+                }
+            })
+            .sum();
+        done / group.items.len() as f64
+    }
+
+    /// Returns how many of the group's items are done, and how many
+    /// items it has.
+    pub fn group_progress(&self, group: &Group) -> (usize, usize) {
+        let completed = group
+            .items()
+            .filter(|item| self.is_completed(self.item_status(group, item)))
+            .count();
+        (completed, group.items.len())
+    }
+
+    /// The group or goal `name` and every group and goal it requires,
+    /// directly or indirectly. Soft requirements don't count.
+    pub(crate) fn needed_for<'a>(&'a self, name: &'a str) -> Vec<&'a str> {
+        let mut needed = vec![];
+        let mut pending = vec![name];
+        while let Some(name) = pending.pop() {
+            if needed.contains(&name) {
+                continue;
+            }
+            let requirements: Vec<&Requirement> = if let Some(group) = self.group(name) {
+                group
+                    .all_requirements()
+                    .chain(group.items().flat_map(Item::all_requirements))
+                    .collect()
+            } else if let Some(goal) = self.goals().find(|goal| goal.name == name) {
+                goal.all_requirements().collect()
+            } else {
+                continue;
+            };
+            needed.push(name);
+            pending.extend(
+                requirements
+                    .into_iter()
+                    .filter(|requirement| !requirement.soft)
+                    .map(|requirement| self.required_name(&requirement.on)),
+            );
+        }
+        needed
+    }
+
+    /// Looks up the style of `priority`, falling back to the built-in one.
+    pub fn priority_style(&self, priority: &str) -> PriorityStyle {
+        if let Some(style) = self.priority.get(priority) {
+            return style.clone();
+        }
+        let color = match priority {
+            "P0" => Some("red"),
+            "P1" => Some("orange"),
+            _ => None,
+        };
+        PriorityStyle {
+            color: color.map(str::to_owned),
+            ..PriorityStyle::default()
+        }
+    }
+
+    /// Looks up the style of `status`, which may be one of its aliases.
+    pub fn status_style(&self, status: &str) -> Option<&StatusStyle> {
+        self.status.get(status).or_else(|| {
+            self.status
+                .values()
+                .find(|style| style.aliases.iter().any(|alias| alias == status))
+        })
+    }
+
+    /// The name `status` is defined under, if it is an alias, or else
+    /// `status` itself.
+    pub fn canonical_status<'a>(&'a self, status: &'a str) -> &'a str {
+        if self.status.contains_key(status) {
+            return status;
+        }
+        self.status
+            .iter()
+            .find(|(_, style)| style.aliases.iter().any(|alias| alias == status))
+            .map_or(status, |(name, _)| name.as_str())
+    }
+
+    /// Returns the status that applies to `item`, falling back to its
+    /// group's `default_status`, then to the status of the group itself,
+    /// and then to the tree's default status.
+    pub fn item_status<'a>(&'a self, group: &'a Group, item: &'a Item) -> Option<&'a String> {
+        item.status
+            .as_ref()
+            .or(group.default_status.as_ref())
+            .or(group.status.as_ref())
+            .or(self.default_status.as_ref())
+    }
+
+    /// Returns the link for `item`, in the group or goal named `owner`: its
+    /// own `href`, or else the tree's `href_template` filled in for it.
+    pub fn item_href(&self, owner: &str, item: &Item) -> Option<String> {
+        if let Some(href) = &item.href {
+            return Some(self.resolve_href(href));
+        }
+        let issue = item.issue.map(|issue| issue.to_string());
+        let port = self.item_port(owner, item);
+        let href = expand_template(
+            self.href_template.as_deref()?,
+            &[
+                ("issue", issue.as_deref()),
+                ("port", port.as_deref()),
+                ("name", Some(owner)),
+            ],
+        )?;
+        Some(self.resolve_href(&href))
+    }
+
+    /// The port of `item`, in the group or goal named `owner`, making it up
+    /// if it has none (see `Group::ports`). `None` if `item` isn't one of
+    /// that group's or goal's own items.
+    fn item_port(&self, owner: &str, item: &Item) -> Option<String> {
+        if let Some(port) = &item.port {
+            return Some(port.clone());
+        }
+        let items = match self.group(owner) {
+            Some(group) => &group.items,
+            None => &self.goals().find(|goal| goal.name == owner)?.items,
+        };
+        let index = items.iter().position(|i| std::ptr::eq(i, item))?;
+        Some(port_of(items, ItemIndex(index)))
+    }
+
+    /// The tooltip for `item`, whose status is `status`: the name of the
+    /// status, so that it can be told apart from others with a similar
+    /// emoji, followed by the item's description, if it has one.
+    pub(crate) fn item_tooltip(&self, status: Option<&String>, item: &Item) -> Option<String> {
+        let status = status.map(|status| self.canonical_status(status));
+        match (status, &item.description) {
+            (Some(status), Some(description)) => Some(format!("{}: {}", status, description)),
+            (Some(status), None) => Some(status.to_owned()),
+            (None, description) => description.clone(),
+        }
+    }
+
+    /// Returns `href` with the tree's `href_base` in front, if it is
+    /// relative. Links with a scheme (`https:`, `mailto:`), links starting
+    /// with `/`, and `#fragment`s are left as they are.
+    pub fn resolve_href(&self, href: &str) -> String {
+        let base = match &self.href_base {
+            Some(base) if is_relative(href) => base,
+            _ => return href.to_owned(),
+        };
+        let href = href.trim_start_matches("./");
+        if base.ends_with('/') {
+            format!("{}{}", base, href)
+        } else {
+            format!("{}/{}", base, href)
+        }
+    }
+}
+
+/// Whether `href` is relative to the page it is on, for `resolve_href`.
+fn is_relative(href: &str) -> bool {
+    url_scheme(href).is_none() && !href.starts_with('/') && !href.starts_with('#')
+}
+
+/// The scheme of a URL, such as `https` or `mailto`, if `href` has one.
+pub(crate) fn url_scheme(href: &str) -> Option<&str> {
+    let scheme = &href[..href.find(':')?];
+    let mut chars = scheme.chars();
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    Some(scheme).filter(|_| valid)
+}
+
+/// Replaces each `{variable}` in `template` with its value in `variables`.
+/// Returns `None` if one of them has no value. Braces around anything else
+/// are left alone.
+fn expand_template(template: &str, variables: &[(&str, Option<&str>)]) -> Option<String> {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let variable = rest[1..].find('}').and_then(|end| {
+            let name = &rest[1..end + 1];
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| (end + 2, *value))
+        });
+        match variable {
+            Some((length, value)) => {
+                expanded.push_str(value?);
+                rest = &rest[length..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Some(expanded)
 }
 
 impl Group {
-    #[throws(anyhow::Error)]
+    #[throws(Error)]
     pub fn validate(&self) {
         // check: that `name` is a valid graphviz identifier
 
-        // check: each of the things in requires has the form
-        //        `identifier` or `identifier:port` and that all those
-        //        identifiers map to groups
+        // (requirements are checked against the rest of the tree by
+        // `SkillTree::validate`)
 
         for item in &self.items {
             item.validate()?;
@@ -161,15 +1336,158 @@ impl Group {
     pub fn items(&self) -> impl Iterator<Item = &Item> {
         self.items.iter()
     }
+
+    /// The port of each item, in order. Items with a `port` use it; the
+    /// rest get the words of their label, lowercased and joined with `_`
+    /// (`fn_items` for "`fn` items"), or `item_N` if the label has none.
+    /// A number is added to a made-up port that would clash with another.
+    pub fn ports(&self) -> Vec<String> {
+        item_ports(&self.items)
+    }
+
+    /// The port of the item at `index`, as in `ports`.
+    pub fn port_of(&self, index: ItemIndex) -> String {
+        port_of(&self.items, index)
+    }
+
+    /// The index of the item whose port, as in `ports`, is `port`.
+    pub fn item_with_port(&self, port: &str) -> Option<ItemIndex> {
+        item_with_port(&self.items, port)
+    }
+
+    /// Iterates over `requires` and every alternative in `requires_any`.
+    pub fn all_requirements(&self) -> impl Iterator<Item = &Requirement> {
+        all_requirements(&self.requires, &self.requires_any)
+    }
 }
 
 impl Item {
-    #[throws(anyhow::Error)]
+    /// Iterates over `requires` and every alternative in `requires_any`.
+    pub fn all_requirements(&self) -> impl Iterator<Item = &Requirement> {
+        all_requirements(&self.requires, &self.requires_any)
+    }
+
+    /// The label as it reads, for output that isn't HTML: with
+    /// `label_html`, its entities decoded (tags are left as they are).
+    pub(crate) fn label_text(&self) -> Cow<'_, str> {
+        if self.label_html {
+            if let Ok(text) = htmlescape::decode_html(&self.label) {
+                return Cow::Owned(text);
+            }
+        }
+        Cow::Borrowed(&self.label)
+    }
+
+    /// Whether the item has any requirements, plain or alternative.
+    pub fn has_requirements(&self) -> bool {
+        self.all_requirements().next().is_some()
+    }
+
+    /// Fails if the item's `progress` is over 100.
+    #[throws(Error)]
     pub fn validate(&self) {
-        // check: each of the things in requires has the form
-        //        `identifier` or `identifier:port` and that all those
-        //        identifiers map to groups
+        if let Some(progress) = self.progress.filter(|&progress| progress > 100) {
+            throw!(Error::InvalidProgress {
+                item: self.label.clone(),
+                progress,
+            });
+        }
+    }
+}
+
+impl Goal {
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter()
+    }
+
+    /// The port of each item, in order, as in `Group::ports`.
+    pub fn ports(&self) -> Vec<String> {
+        item_ports(&self.items)
+    }
+
+    /// The port of the item at `index`, as in `ports`.
+    pub fn port_of(&self, index: ItemIndex) -> String {
+        port_of(&self.items, index)
+    }
+
+    /// The index of the item whose port, as in `ports`, is `port`.
+    pub fn item_with_port(&self, port: &str) -> Option<ItemIndex> {
+        item_with_port(&self.items, port)
+    }
+
+    /// Iterates over `requires` and every alternative in `requires_any`.
+    pub fn all_requirements(&self) -> impl Iterator<Item = &Requirement> {
+        all_requirements(&self.requires, &self.requires_any)
+    }
+}
+
+/// The ports of `items`, as in `Group::ports`.
+fn item_ports(items: &[Item]) -> Vec<String> {
+    let mut ports = Vec::with_capacity(items.len());
+    while ports.len() < items.len() {
+        ports.push(next_port(items, &ports));
+    }
+    ports
+}
+
+fn port_of(items: &[Item], ItemIndex(index): ItemIndex) -> String {
+    let mut ports = Vec::with_capacity(index + 1);
+    while ports.len() <= index {
+        ports.push(next_port(items, &ports));
+    }
+    ports.swap_remove(index)
+}
+
+fn item_with_port(items: &[Item], port: &str) -> Option<ItemIndex> {
+    // Made-up ports never clash with a `port`, so those can be found
+    // without making any up.
+    if let Some(index) = items
+        .iter()
+        .position(|item| item.port.as_deref() == Some(port))
+    {
+        return Some(ItemIndex(index));
+    }
+    let mut ports = Vec::with_capacity(items.len());
+    while ports.len() < items.len() {
+        let next = next_port(items, &ports);
+        if next == port {
+            return Some(ItemIndex(ports.len()));
+        }
+        ports.push(next);
+    }
+    None
+}
 
-        // check: if you have a non-empty `requires`, must have a port
+/// The port of the item after those whose `ports` have been worked out. A
+/// made-up port only depends on the ports before it.
+fn next_port(items: &[Item], ports: &[String]) -> String {
+    let index = ports.len();
+    let item = &items[index];
+    if let Some(port) = &item.port {
+        return port.clone();
     }
+    let base = port_slug(&item.label).unwrap_or_else(|| format!("item_{}", index + 1));
+    let taken = |port: &str| {
+        ports.iter().any(|p| p == port) || items.iter().any(|i| i.port.as_deref() == Some(port))
+    };
+    let mut port = base.clone();
+    let mut n = 2;
+    while taken(&port) {
+        port = format!("{}_{}", base, n);
+        n += 1;
+    }
+    port
+}
+
+fn all_requirements<'a>(
+    requires: &'a Option<Vec<Requirement>>,
+    requires_any: &'a [Vec<Requirement>],
+) -> impl Iterator<Item = &'a Requirement> {
+    requires
+        .iter()
+        .flatten()
+        .chain(requires_any.iter().flatten())
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,510 @@
+use super::{ItemIndex, SkillTree};
+#[cfg(feature = "serde_yaml")]
+use crate::Error;
+
+#[test]
+fn toml_round_trip() {
+    let text = r#"[[group]]
+name = "parser"
+label = "Parser"
+requires = ["lexer:tokens"]
+items = [
+  { label = "Write grammar", status = "Complete" },
+  { label = "Error recovery", port = "errors" },
+]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+"#;
+
+    let tree = SkillTree::parse(text).unwrap();
+    let output = tree.to_toml_string().unwrap();
+
+    // Default statuses are not written back out.
+    assert!(!output.contains("[status"));
+
+    let reparsed = SkillTree::parse(&output).unwrap();
+    assert_eq!(output, reparsed.to_toml_string().unwrap());
+    assert_eq!(reparsed.group.len(), 2);
+    assert_eq!(reparsed.group[0].items[1].port.as_deref(), Some("errors"));
+}
+
+#[test]
+fn parses_json() {
+    let text = r#"{ "group": [{ "name": "a", "items": [{ "label": "x" }] }] }"#;
+
+    let tree = SkillTree::parse_json(text).unwrap();
+    assert_eq!(tree.group[0].items[0].label, "x");
+    assert!(tree.status.contains_key("Complete"));
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn parses_yaml() {
+    let text = "
+group:
+  - name: a
+    items:
+      - label: x
+        status: Complete
+  - name: b
+    requires: [a]
+    items: []
+";
+    let tree = SkillTree::parse_yaml(text).unwrap();
+    assert_eq!(tree.group[0].items[0].label, "x");
+    assert_eq!(tree.group[1].requires.as_ref().unwrap()[0].on, "a");
+    assert!(tree.status.contains_key("Complete"));
+    tree.validate().unwrap();
+
+    match SkillTree::parse_yaml("group:\n  - name: [a\n").unwrap_err() {
+        Error::Parse {
+            line_col: Some((line, _)),
+            ..
+        } => assert_eq!(line, 2),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn loads_yaml_files() {
+    let dir = std::env::temp_dir().join(format!("skill-tree-yaml-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("tree.yml"),
+        "include: [more.yaml]\ngroup:\n  - name: a\n    requires: [b]\n    items: []\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("more.yaml"),
+        "group:\n  - name: b\n    items: []\n",
+    )
+    .unwrap();
+
+    let tree = SkillTree::load(&dir.join("tree.yml")).unwrap();
+    let names: Vec<&str> = tree.groups().map(|group| group.name.as_str()).collect();
+    assert_eq!(names, ["a", "b"]);
+    tree.validate().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parses_from_reader() {
+    let text = "[[group]]\nname = \"a\"\nitems = []\n";
+
+    let tree = SkillTree::parse_reader(text.as_bytes()).unwrap();
+    assert_eq!(tree.group[0].name, "a");
+}
+
+#[test]
+fn labeled_requirements() {
+    let text = r#"[[group]]
+name = "parser"
+requires = [{ on = "lexer:tokens", label = "needs stable API" }]
+items = [{ label = "Grammar", port = "grammar", requires = ["lexer"] }]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+"#;
+
+    let tree = SkillTree::parse(text).unwrap();
+    let requirement = &tree.group[0].requires.as_ref().unwrap()[0];
+    assert_eq!(requirement.on, "lexer:tokens");
+    assert_eq!(requirement.label.as_deref(), Some("needs stable API"));
+
+    // Unlabeled requirements are written back as plain strings.
+    let output = tree.to_toml_string().unwrap();
+    assert!(output.contains(r#"requires = ["lexer"]"#));
+    let reparsed = SkillTree::parse(&output).unwrap();
+    assert_eq!(reparsed.group[0].requires, tree.group[0].requires);
+}
+
+#[test]
+fn soft_requirements() {
+    let text = r#"[[group]]
+name = "parser"
+requires = [{ on = "lexer:tokens?", label = "nice to have" }]
+items = [{ label = "Grammar", port = "grammar", requires = ["lexer?"] }]
+
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+"#;
+
+    let tree = SkillTree::parse(text).unwrap();
+    let requirement = &tree.group[0].requires.as_ref().unwrap()[0];
+    assert_eq!(requirement.on, "lexer:tokens");
+    assert!(requirement.soft);
+    assert!(tree.group[0].items[0].requires.as_ref().unwrap()[0].soft);
+
+    let output = tree.to_toml_string().unwrap();
+    assert!(output.contains(r#"on = "lexer:tokens?""#));
+    assert!(output.contains(r#"requires = ["lexer?"]"#));
+}
+
+#[test]
+fn resolves_icons_relative_to_file() {
+    let dir = std::env::temp_dir().join(format!("skill-tree-icons-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(dir.join("tree.toml"), "include = [\"sub/more.toml\"]\n").unwrap();
+    std::fs::write(
+        dir.join("sub/more.toml"),
+        r#"[[group]]
+name = "a"
+icon = "logo.png"
+items = [{ label = "x", icon = "https://example.com/x.svg" }]
+"#,
+    )
+    .unwrap();
+
+    let tree = SkillTree::load(&dir.join("tree.toml")).unwrap();
+    let group = &tree.group[0];
+    assert_eq!(
+        group.icon.as_deref().map(std::path::Path::new),
+        Some(dir.join("sub/logo.png").as_path())
+    );
+    assert_eq!(
+        group.items[0].icon.as_deref(),
+        Some("https://example.com/x.svg")
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saves_loaded_tree_as_written() {
+    let dir = std::env::temp_dir().join(format!("skill-tree-save-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(
+        dir.join("tree.toml"),
+        r#"include = ["sub/more.toml"]
+status_file = "statuses.toml"
+
+[[group]]
+name = "a"
+icon = "logo.png"
+items = [{ label = "x", icon = "icons/x.png" }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("statuses.toml"),
+        "[status.Shared]\ncolor = \"blue\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("sub/more.toml"),
+        r#"[status.Included]
+color = "red"
+
+[[group]]
+name = "b"
+items = []
+
+[[goal]]
+name = "done"
+requires = ["a"]
+"#,
+    )
+    .unwrap();
+
+    let tree = SkillTree::load(&dir.join("tree.toml")).unwrap();
+    assert!(tree.group("b").is_some());
+    assert!(tree.status_style("Shared").is_some());
+    let output = tree.to_toml_string().unwrap();
+    assert!(output.contains(r#"include = ["sub/more.toml"]"#));
+    assert!(output.contains(r#"status_file = "statuses.toml""#));
+    assert!(output.contains(r#"icon = "logo.png""#));
+    assert!(output.contains(r#"icon = "icons/x.png""#));
+    for merged in &["name = \"b\"", "[[goal]]", "Shared", "Included"] {
+        assert!(!output.contains(merged), "{} in {}", merged, output);
+    }
+    // What was written back loads the same way.
+    std::fs::write(dir.join("tree.toml"), &output).unwrap();
+    let reloaded = SkillTree::load(&dir.join("tree.toml")).unwrap();
+    assert_eq!(reloaded.to_toml_string().unwrap(), output);
+    assert_eq!(reloaded.group[0].icon, tree.group[0].icon);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn colorblind_palette() {
+    let tree = SkillTree::parse(
+        r#"palette = "colorblind"
+
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Complete" }]
+"#,
+    )
+    .unwrap();
+    let complete = &tree.status["Complete"];
+    assert_eq!(complete.fontcolor.as_deref(), Some("#009e73"));
+    assert_eq!(complete.start_tag, "<s>");
+
+    // The generated statuses aren't written back, but the palette is.
+    let output = toml::to_string(&tree).unwrap();
+    assert!(output.contains(r#"palette = "colorblind""#));
+    assert!(!output.contains("[status"));
+}
+
+#[test]
+fn inherit_default_statuses() {
+    let text = r#"inherit_default_statuses = true
+
+[status.Review]
+emoji = "👀"
+
+[status.Done]
+completed = true
+aliases = ["Complete"]
+
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Review" }, { label = "y", status = "Blocked" }]
+"#;
+    let tree = SkillTree::parse(text).unwrap();
+    let statuses: Vec<&str> = tree.status.keys().map(String::as_str).collect();
+    // `Complete` is an alias of `Done`, so the built-in one isn't added.
+    assert_eq!(
+        statuses,
+        ["Assigned", "Blocked", "Done", "Review", "Unassigned"]
+    );
+    assert!(tree.status_style("Complete").unwrap().completed);
+    assert!(tree.check().is_empty());
+
+    // Only the tree's own statuses are written back.
+    let output = tree.to_toml_string().unwrap();
+    assert!(output.contains("[status.Review]"));
+    assert!(!output.contains("[status.Blocked]"));
+    let reparsed = SkillTree::parse(&output).unwrap();
+    assert_eq!(reparsed.status, tree.status);
+
+    // Without the flag, defining any status replaces the built-in ones.
+    let tree = SkillTree::parse(&text.replace("inherit_default_statuses = true", "")).unwrap();
+    assert!(!tree.status.contains_key("Blocked"));
+}
+
+#[test]
+fn group_default_status() {
+    let tree = SkillTree::parse(
+        r#"
+default_status = "Blocked"
+
+[[group]]
+name = "a"
+status = "Complete"
+default_status = "Assigned"
+items = [{ label = "x" }, { label = "y", status = "Unassigned" }]
+
+[[group]]
+name = "b"
+status = "Complete"
+items = [{ label = "z" }]
+
+[[group]]
+name = "c"
+items = [{ label = "w" }]
+"#,
+    )
+    .unwrap();
+    let statuses: Vec<&str> = tree
+        .groups()
+        .flat_map(|group| group.items().map(move |item| (group, item)))
+        .map(|(group, item)| tree.item_status(group, item).unwrap().as_str())
+        .collect();
+    assert_eq!(statuses, ["Assigned", "Unassigned", "Complete", "Blocked"]);
+    assert_eq!(tree.requirement_status("a").unwrap(), "Complete");
+}
+
+#[test]
+fn href_template() {
+    let tree = SkillTree::parse(
+        r#"
+href_template = "https://github.com/org/repo/issues/{issue}?from={name}#{port}{other}"
+
+[[group]]
+name = "parser"
+items = [
+    { label = "Exprs", issue = 12, port = "exprs" },
+    { label = "Stmts", issue = 13 },
+    { label = "Items", href = "https://example.com" },
+    { label = "Macros" },
+]
+"#,
+    )
+    .unwrap();
+    let hrefs: Vec<Option<String>> = tree.group[0]
+        .items()
+        .map(|item| tree.item_href("parser", item))
+        .collect();
+    assert_eq!(
+        hrefs,
+        [
+            Some("https://github.com/org/repo/issues/12?from=parser#exprs{other}".to_owned()),
+            Some("https://github.com/org/repo/issues/13?from=parser#stmts{other}".to_owned()),
+            Some("https://example.com".to_owned()),
+            None,
+        ]
+    );
+
+    let dot = tree.to_graphviz().unwrap();
+    assert!(
+        dot.contains(r#"href="https://github.com/org/repo/issues/12?from=parser#exprs{other}""#)
+    );
+    assert!(
+        dot.contains(r#"href="https://github.com/org/repo/issues/13?from=parser#stmts{other}""#)
+    );
+}
+
+#[test]
+fn href_base() {
+    let tree = SkillTree::parse(
+        r##"
+href_base = "https://example.com/book"
+
+[[group]]
+name = "parser"
+href = "parser.html"
+items = [
+    { label = "Exprs", href = "./exprs.html" },
+    { label = "Stmts", href = "https://example.org/stmts" },
+    { label = "Items", href = "/items.html" },
+    { label = "Macros", href = "#macros" },
+]
+
+[[goal]]
+name = "done"
+href = "done.html"
+"##,
+    )
+    .unwrap();
+    let hrefs: Vec<Option<String>> = tree.group[0]
+        .items()
+        .map(|item| tree.item_href("parser", item))
+        .collect();
+    assert_eq!(
+        hrefs,
+        [
+            Some("https://example.com/book/exprs.html".to_owned()),
+            Some("https://example.org/stmts".to_owned()),
+            Some("/items.html".to_owned()),
+            Some("#macros".to_owned()),
+        ]
+    );
+    assert_eq!(
+        tree.resolve_href("mailto:someone@example.com"),
+        "mailto:someone@example.com"
+    );
+
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#"href="https://example.com/book/parser.html""#));
+    assert!(dot.contains(r#"href = "https://example.com/book/done.html""#));
+}
+
+#[test]
+fn made_up_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "parser"
+items = [
+    { label = "`fn` items" },
+    { label = "Expressions", port = "exprs" },
+    { label = "exprs" },
+    { label = "🚧" },
+    { label = "Fn items!" },
+]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.group[0].ports(),
+        ["fn_items", "exprs", "exprs_2", "item_4", "fn_items_2"]
+    );
+    assert_eq!(tree.group[0].item_with_port("item_4"), Some(ItemIndex(3)));
+    assert_eq!(tree.group[0].item_with_port("exprs"), Some(ItemIndex(1)));
+    assert_eq!(tree.group[0].item_with_port("missing"), None);
+    assert_eq!(tree.group[0].port_of(ItemIndex(4)), "fn_items_2");
+}
+
+#[test]
+fn goal_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[goal]]
+name = "ship"
+items = [{ label = "Announce" }, { label = "Tag", port = "announce" }]
+"#,
+    )
+    .unwrap();
+    let goal = tree.goals().next().unwrap();
+    assert_eq!(goal.ports(), ["announce_2", "announce"]);
+    assert_eq!(goal.port_of(ItemIndex(0)), "announce_2");
+    assert_eq!(goal.item_with_port("announce"), Some(ItemIndex(1)));
+    assert_eq!(goal.item_with_port("announce_2"), Some(ItemIndex(0)));
+}
+
+#[test]
+fn subgroups() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "frontend"
+area = "web"
+items = [{ label = "Shell" }]
+
+[[group.group]]
+name = "forms"
+items = [{ label = "Inputs", port = "inputs" }]
+
+[[group.group.group]]
+name = "validation"
+area = "shared"
+items = [{ label = "Rules" }]
+
+[[group.group]]
+name = "pages"
+requires = ["forms:inputs"]
+items = [{ label = "Home" }]
+
+[[group]]
+name = "backend"
+requires = ["validation"]
+items = []
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+
+    let groups: Vec<(&str, Option<&str>, Option<&str>)> = tree
+        .groups()
+        .map(|g| (g.name.as_str(), g.parent.as_deref(), g.area.as_deref()))
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("frontend", None, Some("web")),
+            ("forms", Some("frontend"), Some("web")),
+            ("validation", Some("forms"), Some("shared")),
+            ("pages", Some("frontend"), Some("web")),
+            ("backend", None, None),
+        ]
+    );
+    let subgroups: Vec<&str> = tree
+        .subgroups("frontend")
+        .map(|g| g.name.as_str())
+        .collect();
+    assert_eq!(subgroups, ["forms", "pages"]);
+    let forms = tree.group("forms").unwrap();
+    assert_eq!(tree.parent_group(forms).unwrap().name, "frontend");
+
+    // Written back out, sub-groups keep their parents.
+    let reparsed = SkillTree::parse(&tree.to_toml_string().unwrap()).unwrap();
+    assert_eq!(
+        reparsed.group("validation").unwrap().parent.as_deref(),
+        Some("forms")
+    );
+}
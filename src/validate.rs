@@ -0,0 +1,542 @@
+use crate::date;
+use crate::error::Error;
+use crate::graph::NodeRef;
+use crate::tree::{split_requirement, url_scheme, SkillTree};
+use fehler::{throw, throws};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// How serious a `ValidationIssue` is. Errors make `validate` fail;
+/// warnings are only reported by `check`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by `SkillTree::check`.
+#[derive(Debug)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub error: Error,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.error)
+    }
+}
+
+impl SkillTree {
+    /// Fails with the first error `check` finds, if any.
+    #[throws(Error)]
+    pub fn validate(&self) {
+        if let Some(issue) = self
+            .check()
+            .into_iter()
+            .find(|issue| issue.severity == Severity::Error)
+        {
+            throw!(issue.error);
+        }
+    }
+
+    /// Checks the whole tree, returning every problem found rather than
+    /// stopping at the first one.
+    pub fn check(&self) -> Vec<ValidationIssue> {
+        let mut issues = Issues::default();
+        for group in &self.group {
+            for item in group.items() {
+                if let Err(error) = item.validate() {
+                    issues.error(error);
+                }
+            }
+        }
+
+        self.check_for_duplicates(&mut issues);
+        self.check_parents(&mut issues);
+        self.check_requirements(&mut issues);
+        self.check_statuses(&mut issues);
+        self.check_phases(&mut issues);
+        self.check_dates(&mut issues);
+        self.check_links(&mut issues);
+        self.check_for_cycles(&mut issues);
+        issues.0
+    }
+
+    /// Reports groups or goals that share a name, and items in the same
+    /// group that share a port.
+    fn check_for_duplicates(&self, issues: &mut Issues) {
+        let mut definitions: HashMap<&str, String> = HashMap::new();
+        let groups = self
+            .groups()
+            .enumerate()
+            .map(|(i, g)| (&g.name, describe("group", i, &g.label)));
+        let goals = self
+            .goals()
+            .enumerate()
+            .map(|(i, g)| (&g.name, describe("goal", i, &g.label)));
+        for (name, definition) in groups.chain(goals) {
+            match definitions.get(name.as_str()) {
+                Some(previous) => issues.error(Error::DuplicateName {
+                    name: name.clone(),
+                    first: previous.clone(),
+                    second: definition,
+                }),
+                None => {
+                    definitions.insert(name, definition);
+                }
+            }
+        }
+
+        for group in self.groups() {
+            let mut ports: HashMap<&str, &str> = HashMap::new();
+            for item in group.items() {
+                if let Some(port) = &item.port {
+                    match ports.get(port.as_str()) {
+                        Some(previous) => issues.error(Error::DuplicatePort {
+                            group: group.name.clone(),
+                            port: port.clone(),
+                            first: (*previous).to_owned(),
+                            second: item.label.clone(),
+                        }),
+                        None => {
+                            ports.insert(port, &item.label);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports sub-groups whose parent doesn't exist, and groups that are
+    /// (indirectly) sub-groups of themselves.
+    fn check_parents(&self, issues: &mut Issues) {
+        let mut reported: Vec<&str> = vec![];
+        for group in self.groups() {
+            let mut chain = vec![group.name.as_str()];
+            let mut current = group;
+            while let Some(parent) = &current.parent {
+                match self.group(parent) {
+                    None => {
+                        if current.name == group.name {
+                            issues.error(Error::UnknownParent {
+                                group: group.name.clone(),
+                                parent: parent.clone(),
+                            });
+                        }
+                        break;
+                    }
+                    Some(next) => {
+                        if let Some(start) = chain.iter().position(|name| *name == parent) {
+                            // Report each cycle once, from whichever of its
+                            // groups comes first.
+                            if !reported.contains(&parent.as_str()) {
+                                reported.extend(&chain[start..]);
+                                let mut cycle = chain[start..].to_vec();
+                                cycle.push(parent);
+                                issues.error(Error::ParentCycle {
+                                    cycle: cycle.into_iter().map(str::to_owned).collect(),
+                                });
+                            }
+                            break;
+                        }
+                        chain.push(&next.name);
+                        current = next;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports `requires` and `requires_any` entries that don't name a group,
+    /// a goal, or a `group:port` pair, suggesting the closest match when
+    /// there is one.
+    fn check_requirements(&self, issues: &mut Issues) {
+        for group in self.groups() {
+            for requirement in group.all_requirements() {
+                self.check_requirement(
+                    &requirement.on,
+                    || format!("group `{}`", group.name),
+                    issues,
+                );
+            }
+            for item in group.items() {
+                for requirement in item.all_requirements() {
+                    self.check_requirement(
+                        &requirement.on,
+                        || format!("item `{}` in group `{}`", item.label, group.name),
+                        issues,
+                    );
+                }
+            }
+        }
+
+        for goal in self.goals() {
+            for requirement in goal.all_requirements() {
+                self.check_requirement(&requirement.on, || format!("goal `{}`", goal.name), issues);
+            }
+        }
+    }
+
+    /// Reports `requirement` if it doesn't resolve, or warns if it only
+    /// does thanks to `loose_references`.
+    fn check_requirement(
+        &self,
+        requirement: &str,
+        owner: impl Fn() -> String,
+        issues: &mut Issues,
+    ) {
+        match self.requirement_error(requirement, &owner) {
+            Some(error) => issues.error(error),
+            None => {
+                if let Some(name) = self.loose_match(split_requirement(requirement).0) {
+                    issues.warning(Error::LooseReference {
+                        owner: owner(),
+                        requirement: requirement.to_owned(),
+                        name: name.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// The error, if any, with `requirement`, which `owner` describes the
+    /// place of.
+    pub(crate) fn requirement_error(
+        &self,
+        requirement: &str,
+        owner: impl Fn() -> String,
+    ) -> Option<Error> {
+        let resolved = self.resolve_requirement(requirement);
+        let (name, port) = split_requirement(&resolved);
+        match (self.group(name), port) {
+            (Some(_), None) => None,
+            (Some(group), Some(port)) => {
+                let ports = group.ports();
+                if ports.iter().any(|p| p == port) {
+                    return None;
+                }
+                Some(Error::UnknownPort {
+                    owner: owner(),
+                    requirement: requirement.to_owned(),
+                    group: name.to_owned(),
+                    port: port.to_owned(),
+                    suggestion: did_you_mean(port, ports.iter().map(String::as_str))
+                        .map(|p| format!("{}:{}", name, p)),
+                })
+            }
+            (None, port) if self.is_goal(name) => port.map(|_| Error::GoalPort {
+                owner: owner(),
+                requirement: requirement.to_owned(),
+                goal: name.to_owned(),
+            }),
+            (None, _) => {
+                // Something with a `/` may be meant as `group/item label`.
+                let item_paths: Vec<String> = if name.contains('/') {
+                    self.groups()
+                        .flat_map(|g| g.items().map(move |i| format!("{}/{}", g.name, i.label)))
+                        .collect()
+                } else {
+                    vec![]
+                };
+                let names = self
+                    .groups()
+                    .map(|g| g.name.as_str())
+                    .chain(self.goals().map(|g| g.name.as_str()))
+                    .chain(item_paths.iter().map(String::as_str));
+                Some(Error::UnknownReference {
+                    owner: owner(),
+                    name: name.to_owned(),
+                    suggestion: did_you_mean(name, names).map(str::to_owned),
+                })
+            }
+        }
+    }
+
+    /// Warns about statuses that have no style defined, suggesting a status
+    /// or alias with a similar name. They still render, just without any
+    /// styling.
+    fn check_statuses(&self, issues: &mut Issues) {
+        let mut check = |status: &Option<String>, owner: &dyn Fn() -> String| {
+            if let Some(status) = status {
+                if self.status_style(status).is_none() {
+                    let names = self.status.iter().flat_map(|(name, style)| {
+                        std::iter::once(name)
+                            .chain(&style.aliases)
+                            .map(String::as_str)
+                    });
+                    issues.warning(Error::UnknownStatus {
+                        owner: owner(),
+                        status: status.clone(),
+                        suggestion: did_you_mean(status, names).map(str::to_owned),
+                    });
+                }
+            }
+        };
+
+        check(&self.default_status, &|| "the default status".to_owned());
+        for group in self.groups() {
+            check(&group.status, &|| format!("group `{}`", group.name));
+            check(&group.default_status, &|| {
+                format!("the default status of group `{}`", group.name)
+            });
+            for item in group.items() {
+                check(&item.status, &|| {
+                    format!("item `{}` in group `{}`", item.label, group.name)
+                });
+            }
+        }
+        for goal in self.goals() {
+            check(&goal.status, &|| format!("goal `{}`", goal.name));
+            for item in goal.items() {
+                check(&item.status, &|| {
+                    format!("item `{}` in goal `{}`", item.label, goal.name)
+                });
+            }
+        }
+    }
+
+    /// Reports groups in a phase that isn't listed, which are drawn as if
+    /// they had none.
+    fn check_phases(&self, issues: &mut Issues) {
+        for group in self.groups() {
+            if let Some(phase) = &group.phase {
+                if !self.phase.iter().any(|p| p.name == *phase) {
+                    let names = self.phase.iter().map(|p| p.name.as_str());
+                    issues.warning(Error::UnknownPhase {
+                        group: group.name.clone(),
+                        phase: phase.clone(),
+                        suggestion: did_you_mean(phase, names).map(str::to_owned),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Reports due dates that aren't `YYYY-MM-DD`, which can't be compared.
+    fn check_dates(&self, issues: &mut Issues) {
+        let mut check = |due: &Option<String>, owner: &dyn Fn() -> String| {
+            if let Some(date) = due {
+                if !date::is_date(date) {
+                    issues.error(Error::InvalidDate {
+                        owner: owner(),
+                        date: date.clone(),
+                    });
+                }
+            }
+        };
+
+        for group in self.groups() {
+            for item in group.items() {
+                check(&item.due, &|| {
+                    format!("item `{}` in group `{}`", item.label, group.name)
+                });
+            }
+        }
+        for goal in self.goals() {
+            check(&goal.due, &|| format!("goal `{}`", goal.name));
+            for item in goal.items() {
+                check(&item.due, &|| {
+                    format!("item `{}` in goal `{}`", item.label, goal.name)
+                });
+            }
+        }
+    }
+
+    /// Warns about links that are neither URLs nor relative paths. Item
+    /// links are checked as `href_template` fills them in.
+    fn check_links(&self, issues: &mut Issues) {
+        let mut check = |href: Option<&str>, owner: &dyn Fn() -> String| {
+            if let Some(href) = href {
+                if let Some(reason) = href_problem(href) {
+                    issues.warning(Error::InvalidHref {
+                        owner: owner(),
+                        href: href.to_owned(),
+                        reason: reason.to_owned(),
+                    });
+                }
+            }
+        };
+
+        check(self.href_base.as_deref(), &|| "the `href_base`".to_owned());
+        for group in self.groups() {
+            check(group.href.as_deref(), &|| format!("group `{}`", group.name));
+            check(group.details_href.as_deref(), &|| {
+                format!("the details of group `{}`", group.name)
+            });
+            for item in group.items() {
+                let href = item
+                    .href
+                    .clone()
+                    .or_else(|| self.item_href(&group.name, item));
+                check(href.as_deref(), &|| {
+                    format!("item `{}` in group `{}`", item.label, group.name)
+                });
+            }
+        }
+        for goal in self.goals() {
+            check(goal.href.as_deref(), &|| format!("goal `{}`", goal.name));
+            for item in goal.items() {
+                let href = item
+                    .href
+                    .clone()
+                    .or_else(|| self.item_href(&goal.name, item));
+                check(href.as_deref(), &|| {
+                    format!("item `{}` in goal `{}`", item.label, goal.name)
+                });
+            }
+        }
+    }
+
+    /// Reports cycles in the requirements between groups and goals, with
+    /// the full path of each. Requirements of an item count as requirements
+    /// of its group, as does every alternative in a `requires_any`; soft
+    /// requirements don't count at all.
+    fn check_for_cycles(&self, issues: &mut Issues) {
+        // Items are ordered on their own, as in `topological_order`, so
+        // items in a group can require one another.
+        let (nodes, before) = self.ordering();
+        let mut state = vec![Visit::New; nodes.len()];
+        let mut stack = Vec::new();
+        let mut cycles = Vec::new();
+        for index in 0..nodes.len() {
+            find_cycles(index, &before, &mut state, &mut stack, &mut cycles);
+        }
+
+        let mut reported = HashSet::new();
+        for mut cycle in cycles {
+            // Start from the node defined first, so that the same cycle is
+            // always told the same way.
+            let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+            cycle.rotate_left(first);
+            let nodes: Vec<NodeRef> = cycle.into_iter().map(|i| nodes[i]).collect();
+            let cycle = self.cycle_names(&nodes);
+            if reported.insert(cycle.clone()) {
+                issues.error(Error::CycleDetected { cycle });
+            }
+        }
+    }
+}
+
+/// What is wrong with `href` as a URL or relative path, if anything.
+fn href_problem(href: &str) -> Option<&'static str> {
+    if href.trim().is_empty() {
+        return Some("it is empty");
+    }
+    if href.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Some("it contains spaces or control characters");
+    }
+    if href.contains(['<', '>', '"', '\\', '^', '`', '{', '|', '}']) {
+        return Some("it contains characters that must be escaped");
+    }
+    let bytes = href.as_bytes();
+    let bad_escape = bytes.iter().enumerate().any(|(index, &byte)| {
+        byte == b'%'
+            && !matches!(bytes.get(index + 1..index + 3), Some(hex) if hex.iter().all(u8::is_ascii_hexdigit))
+    });
+    if bad_escape {
+        return Some("it has a `%` not followed by two hex digits");
+    }
+    if let Some(scheme) = url_scheme(href) {
+        let scheme = scheme.to_ascii_lowercase();
+        if matches!(scheme.as_str(), "http" | "https" | "ftp") {
+            let rest = &href[scheme.len() + 1..];
+            let host = rest
+                .strip_prefix("//")
+                .map(|rest| rest.split(['/', '?', '#']).next().unwrap());
+            if !matches!(host, Some(host) if !host.is_empty()) {
+                return Some("it has no host");
+            }
+        }
+    }
+    None
+}
+
+#[derive(Default)]
+struct Issues(Vec<ValidationIssue>);
+
+impl Issues {
+    fn error(&mut self, error: Error) {
+        self.0.push(ValidationIssue {
+            severity: Severity::Error,
+            error,
+        });
+    }
+
+    fn warning(&mut self, error: Error) {
+        self.0.push(ValidationIssue {
+            severity: Severity::Warning,
+            error,
+        });
+    }
+}
+
+/// Describes the `index`th group or goal, for error messages.
+fn describe(kind: &str, index: usize, label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("{} #{} ({:?})", kind, index + 1, label),
+        None => format!("{} #{}", kind, index + 1),
+    }
+}
+
+/// Finds the candidate closest to `name` by edit distance, if any is close
+/// enough to plausibly be what was meant.
+fn did_you_mean<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = std::cmp::max(2, name.chars().count() / 3);
+    candidates
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+/// Depth-first search from `index` through what each node waits for
+/// (`before`), collecting the nodes along each cycle found, in the
+/// direction the edges are drawn (from prerequisite to the thing that
+/// requires it).
+fn find_cycles(
+    index: usize,
+    before: &[Vec<usize>],
+    state: &mut Vec<Visit>,
+    stack: &mut Vec<usize>,
+    cycles: &mut Vec<Vec<usize>>,
+) {
+    match state[index] {
+        Visit::Done => return,
+        Visit::InProgress => {
+            let start = stack.iter().position(|&i| i == index).unwrap();
+            let mut cycle = stack[start..].to_vec();
+            cycle.reverse();
+            cycles.push(cycle);
+            return;
+        }
+        Visit::New => {}
+    }
+
+    state[index] = Visit::InProgress;
+    stack.push(index);
+    for &next in &before[index] {
+        find_cycles(next, before, state, stack, cycles);
+    }
+    stack.pop();
+    state[index] = Visit::Done;
+}
+
+#[cfg(test)]
+mod test;
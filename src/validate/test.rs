@@ -0,0 +1,474 @@
+use crate::{Error, Severity, SkillTree};
+
+#[test]
+fn accepts_acyclic_tree() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", port = "x" }]
+
+[[group]]
+name = "b"
+requires = ["a:x"]
+items = []
+
+[[goal]]
+name = "done"
+requires = ["a", "b"]
+"#,
+    )
+    .unwrap();
+
+    tree.validate().unwrap();
+}
+
+#[test]
+fn reports_cycle_path() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+requires = ["c"]
+items = []
+
+[[group]]
+name = "b"
+items = [{ label = "x", port = "x", requires = ["a"] }]
+
+[[group]]
+name = "c"
+requires = ["b:x"]
+items = []
+"#,
+    )
+    .unwrap();
+
+    let err = tree.validate().unwrap_err();
+    assert_eq!(err.to_string(), "dependency cycle: a -> b -> c -> a");
+    match err {
+        Error::CycleDetected { cycle } => assert_eq!(cycle, ["a", "b", "c", "a"]),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn accepts_requirements_within_a_group() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "parser"
+items = [
+  { label = "Tokens", port = "tokens" },
+  { label = "AST", requires = ["parser:tokens"] },
+]
+"#,
+    )
+    .unwrap();
+    tree.validate().unwrap();
+    tree.topological_order().unwrap();
+}
+
+#[test]
+fn reports_cycles_within_a_group() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "parser"
+items = [
+  { label = "Tokens", port = "tokens", requires = ["parser:ast"] },
+  { label = "AST", port = "ast", requires = ["parser:tokens"] },
+]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "dependency cycle: parser -> parser"
+    );
+    assert!(tree.topological_order().is_err());
+}
+
+fn requirement_error(requires: &str) -> String {
+    let tree = SkillTree::parse(&format!(
+        r#"
+[[group]]
+name = "lexer"
+items = [{{ label = "x", port = "tokens" }}]
+
+[[group]]
+name = "parser"
+requires = ["{}"]
+items = []
+
+[[goal]]
+name = "ship"
+"#,
+        requires
+    ))
+    .unwrap();
+
+    tree.validate().unwrap_err().to_string()
+}
+
+#[test]
+fn suggests_group_names() {
+    assert_eq!(
+        requirement_error("lexr"),
+        "group `parser` requires unknown group or goal `lexr`; did you mean `lexer`?"
+    );
+    assert_eq!(
+        requirement_error("typechecker"),
+        "group `parser` requires unknown group or goal `typechecker`"
+    );
+}
+
+#[test]
+fn suggests_ports() {
+    assert_eq!(
+        requirement_error("lexer:token"),
+        "group `parser` requires `lexer:token`, but group `lexer` has no port `token`; \
+         did you mean `lexer:tokens`?"
+    );
+}
+
+#[test]
+fn rejects_goal_ports() {
+    assert_eq!(
+        requirement_error("ship:it"),
+        "group `parser` requires `ship:it`, but `ship` is a goal and goals have no ports"
+    );
+}
+
+#[test]
+fn rejects_duplicate_names() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = []
+
+[[group]]
+name = "b"
+label = "Bee"
+items = []
+
+[[goal]]
+name = "b"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        r#"`b` is defined more than once: group #2 ("Bee") and goal #1"#
+    );
+}
+
+#[test]
+fn rejects_duplicate_ports() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [
+  { label = "One", port = "p" },
+  { label = "Two", port = "p" },
+]
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        r#"group `a` has more than one item with port `p`: "One" and "Two""#
+    );
+}
+
+#[test]
+fn check_collects_every_issue() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+requires = ["b", "missing"]
+items = [
+  { label = "One", port = "p", status = "Compelte" },
+  { label = "Two", requires = ["b"], progress = 150 },
+]
+
+[[group]]
+name = "b"
+requires = ["a"]
+items = []
+"#,
+    )
+    .unwrap();
+
+    let issues: Vec<String> = tree.check().iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        [
+            "error: item `Two` has progress 150, but progress is a percentage from 0 to 100",
+            "error: group `a` requires unknown group or goal `missing`",
+            "warning: item `One` in group `a` has unknown status `Compelte`; \
+             did you mean `Complete`?",
+            "error: dependency cycle: a -> b -> a",
+        ]
+    );
+
+    // `validate` stops at the first error.
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "item `Two` has progress 150, but progress is a percentage from 0 to 100"
+    );
+}
+
+#[test]
+fn warnings_do_not_fail_validation() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+status = "Someday"
+items = []
+"#,
+    )
+    .unwrap();
+
+    let issues = tree.check();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Warning);
+    tree.validate().unwrap();
+}
+
+#[test]
+fn checks_alternatives() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "lexer"
+items = []
+
+[[goal]]
+name = "done"
+requires_any = [["lexer", "lexr"]]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "goal `done` requires unknown group or goal `lexr`; did you mean `lexer`?"
+    );
+}
+
+#[test]
+fn accepts_status_aliases() {
+    let tree = SkillTree::parse(
+        r#"
+default_status = "Todo"
+
+[status.Unassigned]
+aliases = ["Todo"]
+
+[status.Complete]
+aliases = ["Done"]
+
+[[group]]
+name = "a"
+status = "Done"
+items = [{ label = "x" }]
+"#,
+    )
+    .unwrap();
+    assert!(tree.check().is_empty());
+}
+
+#[test]
+fn suggests_status_aliases() {
+    let tree = SkillTree::parse(
+        r#"
+[status.Unassigned]
+
+[status.Complete]
+aliases = ["Shipped"]
+
+[[group]]
+name = "a"
+items = [{ label = "x", status = "Shiped" }]
+"#,
+    )
+    .unwrap();
+    let issues: Vec<String> = tree.check().iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        ["warning: item `x` in group `a` has unknown status `Shiped`; did you mean `Shipped`?"]
+    );
+}
+
+#[test]
+fn loose_references() {
+    let text = r#"
+[[group]]
+name = "lexer"
+items = [{ label = "Tokens", port = "tokens" }]
+
+[[group]]
+name = "parser"
+requires = [" Lexer"]
+items = [{ label = "Grammar", requires = ["LEXER:tokens"] }]
+"#;
+    let tree = SkillTree::parse(text).unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "group `parser` requires unknown group or goal ` Lexer`; did you mean `lexer`?"
+    );
+
+    let tree = SkillTree::parse(&format!("loose_references = true\n{}", text)).unwrap();
+    tree.validate().unwrap();
+    let issues: Vec<String> = tree.check().iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        [
+            "warning: group `parser` requires ` Lexer`, which only matches `lexer` \
+             ignoring case and whitespace",
+            "warning: item `Grammar` in group `parser` requires `LEXER:tokens`, which only \
+             matches `lexer` ignoring case and whitespace",
+        ]
+    );
+    let dot = tree.to_graphviz().unwrap();
+    assert!(dot.contains(r#""lexer":all -> "parser":all;"#));
+    assert!(dot.contains(r#""lexer":_tokens_out -> "parser":_grammar_in"#));
+    assert!(!dot.contains("LEXER"));
+}
+
+#[test]
+fn rejects_progress_over_100() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", progress = 120 }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "item `x` has progress 120, but progress is a percentage from 0 to 100"
+    );
+}
+
+#[test]
+fn rejects_malformed_due_dates() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+items = [{ label = "x", due = "September" }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        tree.validate().unwrap_err().to_string(),
+        "item `x` in group `a` is due `September`, but dates must be written as YYYY-MM-DD"
+    );
+}
+
+#[test]
+fn warns_about_invalid_links() {
+    let tree = SkillTree::parse(
+        r#"
+href_template = "https://example.com/{issue}/{other}"
+
+[[group]]
+name = "a"
+href = "chapter one.html"
+details_href = "docs/a.html#design"
+items = [
+    { label = "x", href = "https:/example.com" },
+    { label = "y", href = "../y%2Fz.html" },
+    { label = "z", href = "mailto:someone@example.com" },
+    { label = "w", issue = 5 },
+    { label = "v", href = "100%.html" },
+]
+
+[[goal]]
+name = "g"
+href = "https://example.com/g?q=1"
+"#,
+    )
+    .unwrap();
+    let issues: Vec<String> = tree.check().iter().map(|issue| issue.to_string()).collect();
+    assert_eq!(
+        issues,
+        [
+            "warning: group `a` links to `chapter one.html`, which is not a valid URL or path: \
+             it contains spaces or control characters",
+            "warning: item `x` in group `a` links to `https:/example.com`, which is not a valid \
+             URL or path: it has no host",
+            "warning: item `w` in group `a` links to `https://example.com/5/{other}`, which is \
+             not a valid URL or path: it contains characters that must be escaped",
+            "warning: item `v` in group `a` links to `100%.html`, which is not a valid URL or \
+             path: it has a `%` not followed by two hex digits",
+        ]
+    );
+    tree.validate().unwrap();
+}
+
+#[test]
+fn checks_parents() {
+    let tree = SkillTree::parse(
+        r#"
+[[group]]
+name = "a"
+parent = "b"
+items = []
+
+[[group]]
+name = "b"
+parent = "a"
+items = []
+
+[[group]]
+name = "c"
+parent = "a"
+items = []
+
+[[group]]
+name = "d"
+parent = "missing"
+items = []
+"#,
+    )
+    .unwrap();
+    let issues: Vec<String> = tree.check().iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        [
+            "error: groups are each other's sub-groups: a -> b -> a",
+            "error: group `d` is a sub-group of unknown group `missing`",
+        ]
+    );
+}
+
+#[test]
+fn warns_about_unknown_phases() {
+    let tree = SkillTree::parse(
+        r#"
+[[phase]]
+name = "alpha"
+
+[[group]]
+name = "a"
+phase = "alpah"
+items = []
+"#,
+    )
+    .unwrap();
+    let issues: Vec<String> = tree.check().iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        ["warning: group `a` is in unknown phase `alpah`; did you mean `alpha`?"]
+    );
+    tree.validate().unwrap();
+}
@@ -0,0 +1,38 @@
+use crate::error::Error;
+use crate::tree::SkillTree;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// How often `watch` checks for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Loads the skill-tree at `path` and passes it to `callback`, then does so
+/// again every time the file changes, until `callback` returns `false`.
+///
+/// Only `path` itself is watched, not its `status_file` or includes. The
+/// file is polled rather than watched through OS notifications, so this
+/// works the same everywhere (including across editors that replace the
+/// file when saving it).
+pub fn watch(path: &Path, mut callback: impl FnMut(Result<SkillTree, Error>) -> bool) {
+    let mut last_seen = None;
+    loop {
+        let stamp = stamp(path);
+        // A missing file is probably being saved; wait for it to come back.
+        if stamp.is_some() && stamp != last_seen {
+            last_seen = stamp;
+            if !callback(SkillTree::load(path)) {
+                return;
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Identifies a version of the file, so changes can be noticed.
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,32 @@
+use crate::watch;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn reloads_on_change() {
+    let path = std::env::temp_dir().join(format!("skill-tree-watch-{}.toml", std::process::id()));
+    std::fs::write(&path, "[[group]]\nname = \"a\"\nitems = []\n").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let watched = path.clone();
+    let watcher = std::thread::spawn(move || {
+        watch(&watched, |tree| {
+            let names: Vec<String> = tree.unwrap().groups().map(|g| g.name.clone()).collect();
+            let keep_going = names.len() < 2;
+            sender.send(names).unwrap();
+            keep_going
+        })
+    });
+
+    let timeout = Duration::from_secs(10);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), ["a"]);
+    std::fs::write(
+        &path,
+        "[[group]]\nname = \"a\"\nitems = []\n\n[[group]]\nname = \"b\"\nitems = []\n",
+    )
+    .unwrap();
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), ["a", "b"]);
+
+    watcher.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+}